@@ -89,11 +89,46 @@ fn write_benchmark(c: &mut Criterion) {
     });
 }
 
+// Benchmark per-entry logging against batch logging
+fn batch_benchmark(c: &mut Criterion) {
+    let entries: Vec<Log> = (0..100)
+        .map(|i| {
+            Log::new(
+                &i.to_string(),
+                "2023-01-23 14:04:09.881393 +00:00:00",
+                &LogLevel::INFO,
+                "test",
+                "test log message",
+                &LogFormat::CLF,
+            )
+        })
+        .collect();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    c.bench_function("log_per_entry_100", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                for entry in &entries {
+                    let _ = entry.log().await;
+                }
+            })
+        })
+    });
+    c.bench_function("log_batch_100", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let _ = Log::log_batch(black_box(&entries)).await;
+            })
+        })
+    });
+}
+
 // Group benchmarks together
 criterion_group!(
     benches,
     new_benchmark,
     format_benchmark,
-    write_benchmark
+    write_benchmark,
+    batch_benchmark
 );
 criterion_main!(benches);
@@ -94,7 +94,8 @@ async fn config_loading_example(
     let config_file_path = temp_dir.path().join("config.toml");
     fs::write(&config_file_path, config_content).await?;
 
-    let config = Config::load_async(Some(&config_file_path)).await?;
+    let config =
+        Config::load_async(Some(&config_file_path), None).await?;
     println!("    ✅  Loaded config:\n    {:#?}", config);
 
     Ok(())
@@ -0,0 +1,289 @@
+// buffered.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    config::Config,
+    log::Log,
+    utils::{rotate_log_file_blocking, run_blocking},
+    LoggingDestination, RlgError, RlgResult,
+};
+use log::{error, warn};
+use parking_lot::Mutex;
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+use tokio::{
+    runtime::Handle,
+    sync::mpsc::{self, error::TrySendError},
+    task::JoinHandle,
+    time,
+};
+
+/// A logger that buffers entries in memory and writes them to the log
+/// file in batches.
+///
+/// Created by `Config::create_buffered_logger`. Entries passed to `write`
+/// are formatted as log file lines and kept in memory until a background
+/// Tokio task flushes them to `Config::log_file_path`, every
+/// `Config::async_flush_interval_ms` milliseconds. When the interval is
+/// zero, every entry is written as soon as it is received. Remaining
+/// entries are flushed when the logger is dropped.
+///
+/// The background flushes run on Tokio's blocking thread pool. When one
+/// fails, the error is reported through the `log` crate and the entries
+/// are kept for the next flush.
+///
+/// At most `Config::max_queue_depth` entries are queued between two
+/// flushes. Once the queue is full, `write` fails immediately with a
+/// `log queue full` error instead of blocking or growing without bound,
+/// so that callers can apply backpressure. Entries a flush fails to write
+/// are kept, and the queue is not drained again until they are written,
+/// so it also fills while the log file cannot be written.
+///
+/// Entries are prepared as `Log::log_with_config` prepares them: those
+/// below the level configured for their component are dropped, and the
+/// others are redacted and truncated before being queued. The log file is
+/// rotated, if due, before each flush. The other logging destinations of
+/// the configuration are not written to.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::{config::Config, log::Log};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let config = Config {
+///     log_file_path: dir.path().join("app.log"),
+///     async_flush_interval_ms: 100,
+///     ..Default::default()
+/// };
+/// let logger = config.create_buffered_logger().unwrap();
+/// logger.write(&Log::default()).unwrap();
+/// logger.flush_now().unwrap();
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct BufferedLogger {
+    config: Arc<Config>,
+    queue: mpsc::Sender<String>,
+    buffer: Arc<Mutex<Buffer>>,
+    flusher: Option<JoinHandle<()>>,
+}
+
+/// Lines waiting to be written, and the configuration of the file they
+/// are written to.
+#[derive(Debug)]
+struct Buffer {
+    config: Arc<Config>,
+    queue: mpsc::Receiver<String>,
+    /// Bytes taken from the queue but not written yet.
+    pending: Vec<u8>,
+    /// Whether a failed write stopped in the middle of a line.
+    partial_line: bool,
+}
+
+impl Buffer {
+    /// Writes the pending and queued lines to the file. Lines are kept if
+    /// the write fails, so that the next flush retries them.
+    ///
+    /// The queue is only drained once the pending lines are written, so
+    /// that `Config::max_queue_depth` still bounds the entries held while
+    /// the file cannot be written.
+    fn flush(&mut self) -> RlgResult<()> {
+        self.write_pending()?;
+        while let Ok(line) = self.queue.try_recv() {
+            self.pending.extend_from_slice(line.as_bytes());
+        }
+        self.write_pending()
+    }
+
+    /// Appends the pending bytes to the file. The bytes written before a
+    /// failure are removed, so that a retry does not write them twice.
+    fn write_pending(&mut self) -> RlgResult<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let path = &self.config.log_file_path;
+        // As with `Log::log_with_config`, a failed rotation must not lose
+        // the entries, which still go to the current file. A line cut
+        // short is finished in the file it was started in.
+        if !self.partial_line {
+            if let Err(e) = rotate_log_file_blocking(path, &self.config)
+            {
+                warn!("Failed to rotate {}: {}", path.display(), e);
+            }
+        }
+        let mut file =
+            OpenOptions::new().append(true).create(true).open(path)?;
+        let mut written = 0;
+        let result = loop {
+            if written == self.pending.len() {
+                break file.flush();
+            }
+            match file.write(&self.pending[written..]) {
+                Ok(0) => break Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => written += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(e),
+            }
+        };
+        if written > 0 {
+            self.partial_line = self.pending[written - 1] != b'\n';
+            let _ = self.pending.drain(..written);
+        }
+        Ok(result?)
+    }
+}
+
+impl BufferedLogger {
+    /// Opens the log file of `config` and, if
+    /// `config.async_flush_interval_ms` is not zero, starts the flush
+    /// task.
+    pub(crate) fn new(config: &Config) -> RlgResult<Self> {
+        // Report an unwritable log file now rather than at the first
+        // flush.
+        let _ = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&config.log_file_path)?;
+        let config = Arc::new(config.clone());
+        // A channel cannot be empty; a zero depth fails validation.
+        let (sender, receiver) =
+            mpsc::channel(config.max_queue_depth.max(1));
+        let buffer = Arc::new(Mutex::new(Buffer {
+            config: Arc::clone(&config),
+            queue: receiver,
+            pending: Vec::new(),
+            partial_line: false,
+        }));
+
+        let flusher = match config.async_flush_interval_ms {
+            0 => None,
+            interval_ms => {
+                let handle = Handle::try_current().map_err(|_| {
+                    RlgError::custom(
+                        "A buffered logger with a flush interval must be created within a Tokio runtime",
+                    )
+                })?;
+                Some(handle.spawn(flush_periodically(
+                    Arc::downgrade(&buffer),
+                    Duration::from_millis(interval_ms),
+                    config.log_file_path.clone(),
+                )))
+            }
+        };
+        Ok(BufferedLogger {
+            config,
+            queue: sender,
+            buffer,
+            flusher,
+        })
+    }
+
+    /// Adds `log` to the queue, or writes it immediately if the logger
+    /// has no flush interval. This never blocks.
+    ///
+    /// Entries below the level configured for their component are
+    /// dropped, returning `Ok(())`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::Custom` with the message `log queue full` if
+    /// `Config::max_queue_depth` entries are already queued,
+    /// `RlgError::UnsupportedFormat` if the format of `log` cannot be
+    /// written to a file, or an `RlgError::IoError` if an immediate write
+    /// fails.
+    pub fn write(&self, log: &Log) -> RlgResult<()> {
+        let entry = match log.prepared_for(&self.config) {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+        let file =
+            LoggingDestination::File(self.config.log_file_path.clone());
+        let mut line = String::new();
+        entry
+            .formatted_for(&self.config, &file)
+            .write_entry_with_separator(
+                &mut line,
+                self.config.structured_fields_separator,
+            )?;
+        match self.queue.try_send(line) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                return Err(RlgError::custom("log queue full"))
+            }
+            // The receiver lives as long as the logger.
+            Err(TrySendError::Closed(_)) => {
+                return Err(RlgError::custom("log queue closed"))
+            }
+        }
+        if self.flusher.is_none() {
+            self.buffer.lock().flush()?;
+        }
+        Ok(())
+    }
+
+    /// Adds `log` to the queue like `write`, returning the error, if any,
+    /// instead of a result.
+    ///
+    /// This suits callers that drop entries when the queue is full rather
+    /// than propagating the failure.
+    pub fn try_write(&self, log: &Log) -> Option<RlgError> {
+        self.write(log).err()
+    }
+
+    /// Writes the buffered entries to the log file now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `RlgError::IoError` if the file cannot be written. The
+    /// entries stay buffered in that case.
+    pub fn flush_now(&self) -> RlgResult<()> {
+        self.buffer.lock().flush()
+    }
+}
+
+impl Drop for BufferedLogger {
+    fn drop(&mut self) {
+        if let Some(flusher) = self.flusher.take() {
+            flusher.abort();
+        }
+        let _ = self.buffer.lock().flush();
+    }
+}
+
+/// Flushes `buffer` every `period` until its logger is dropped, reporting
+/// failed flushes.
+async fn flush_periodically(
+    buffer: Weak<Mutex<Buffer>>,
+    period: Duration,
+    path: PathBuf,
+) {
+    let mut interval = time::interval(period);
+    // The first tick completes immediately.
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let buffer = match buffer.upgrade() {
+            Some(buffer) => buffer,
+            None => break,
+        };
+        // The file is written without blocking the runtime's workers.
+        if let Err(e) =
+            run_blocking(move || buffer.lock().flush()).await
+        {
+            error!(
+                "Failed to flush buffered log entries to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
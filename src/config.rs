@@ -10,20 +10,30 @@
 //! for loading, saving, and manipulating configuration settings, as well
 //! as handling environment variables, error management, and log rotation.
 
-use crate::LogLevel;
+use crate::{
+    buffered::BufferedLogger, utils::file_age, LogFormat, LogLevel,
+    RlgError, RlgResult,
+};
+use chrono::{DateTime, Utc};
 use config::{
-    Config as ConfigSource, ConfigError as SourceConfigError,
+    Config as SourceConfig, ConfigError as SourceConfigError,
     File as ConfigFile,
 };
+use cron::Schedule;
 use envy;
 use log::{error, info, warn};
 use notify::{Event, EventKind, RecursiveMode, Watcher};
+use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
     env, fmt,
     fs::{self, OpenOptions},
+    hash::Hash,
+    io,
     net::{SocketAddr, ToSocketAddrs},
     num::NonZeroU64,
     path::{Path, PathBuf},
@@ -34,9 +44,14 @@
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 use tokio::sync::mpsc;
+use url::Url;
 
 const CURRENT_CONFIG_VERSION: &str = "1.0";
 
+/// The application-wide configuration set by `Config::init_global`.
+pub(crate) static GLOBAL_CONFIG: OnceCell<Arc<RwLock<Config>>> =
+    OnceCell::new();
+
 /// Custom error types for configuration management.
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -72,6 +87,10 @@ pub enum ConfigError {
     #[error("Missing required field: {0}")]
     MissingFieldError(String),
 
+    /// A URL in the configuration is not a valid HTTP or HTTPS URL.
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
     /// Error setting up the file watcher.
     #[error("Watcher error: {0}")]
     WatcherError(#[from] notify::Error),
@@ -80,7 +99,6 @@ pub enum ConfigError {
 /// Enum representing log rotation options.
 #[derive(
     Clone,
-    Copy,
     Debug,
     Deserialize,
     Serialize,
@@ -99,6 +117,164 @@ pub enum LogRotation {
     Date,
     /// Count-based log rotation.
     Count(u32),
+    /// Schedule-based log rotation using a cron expression, such as
+    /// `0 0 * * *` for every day at midnight.
+    ///
+    /// Standard five-field expressions are accepted, as well as the six and
+    /// seven-field forms with leading seconds and trailing years.
+    Cron(String),
+}
+
+impl LogRotation {
+    /// Returns the next time a rotation is due strictly after `after`.
+    ///
+    /// Only `Cron` policies have a schedule, so `None` is returned for the
+    /// other variants, as well as for cron expressions that do not parse or
+    /// never fire again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use rlg::config::LogRotation;
+    ///
+    /// let rotation: LogRotation = "cron:0 0 * * *".parse().unwrap();
+    /// let after = Utc.with_ymd_and_hms(2024, 1, 1, 13, 30, 0).unwrap();
+    /// assert_eq!(
+    ///     rotation.next_trigger(after),
+    ///     Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap())
+    /// );
+    /// ```
+    pub fn next_trigger(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            LogRotation::Cron(expression) => {
+                parse_cron_schedule(expression)
+                    .ok()?
+                    .after(&after)
+                    .next()
+            }
+            _ => None,
+        }
+    }
+
+    /// Describes how close the log file at `base_path` is to its next
+    /// rotation.
+    ///
+    /// - `Size` reports the file size against the limit, such as
+    ///   `512 / 1024 bytes (50% full)`.
+    /// - `Time` reports the seconds left until the interval has elapsed
+    ///   since the file was created, such as `rotates in 3600 seconds`.
+    /// - `Date` reports `rotates at next midnight`.
+    /// - `Count` reports the number of lines against the limit, such as
+    ///   `25 / 100 entries (25% full)`.
+    /// - `Cron` reports the next scheduled time, such as
+    ///   `rotates at 2024-01-02T00:00:00+00:00`.
+    ///
+    /// A missing file is treated as empty and freshly rotated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::IoError` if the file exists but cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::LogRotation;
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("app.log");
+    /// std::fs::write(&path, vec![b'x'; 256]).unwrap();
+    ///
+    /// let rotation: LogRotation = "size:1024".parse().unwrap();
+    /// assert_eq!(
+    ///     rotation.display_next_trigger(&path).unwrap(),
+    ///     "256 / 1024 bytes (25% full)"
+    /// );
+    /// ```
+    pub fn display_next_trigger(
+        &self,
+        base_path: &Path,
+    ) -> RlgResult<String> {
+        let percent = |current: u64, max: u64| {
+            u128::from(current) * 100 / u128::from(max.max(1))
+        };
+        match self {
+            LogRotation::Size(max) => {
+                let size = match fs::metadata(base_path) {
+                    Ok(metadata) => metadata.len(),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+                    Err(e) => return Err(e.into()),
+                };
+                Ok(format!(
+                    "{} / {} bytes ({}% full)",
+                    size,
+                    max,
+                    percent(size, max.get())
+                ))
+            }
+            LogRotation::Time(interval) => {
+                let elapsed = match fs::metadata(base_path) {
+                    Ok(metadata) => file_age(&metadata).as_secs(),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+                    Err(e) => return Err(e.into()),
+                };
+                Ok(format!(
+                    "rotates in {} seconds",
+                    interval.get().saturating_sub(elapsed)
+                ))
+            }
+            LogRotation::Date => {
+                Ok("rotates at next midnight".to_string())
+            }
+            LogRotation::Count(max) => {
+                let count = match fs::read(base_path) {
+                    Ok(contents) => contents
+                        .split(|&byte| byte == b'\n')
+                        .filter(|line| !line.is_empty())
+                        .count()
+                        as u64,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+                    Err(e) => return Err(e.into()),
+                };
+                Ok(format!(
+                    "{} / {} entries ({}% full)",
+                    count,
+                    max,
+                    percent(count, u64::from(*max))
+                ))
+            }
+            LogRotation::Cron(_) => {
+                Ok(match self.next_trigger(Utc::now()) {
+                    Some(next) => {
+                        format!("rotates at {}", next.to_rfc3339())
+                    }
+                    None => "no scheduled rotation".to_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Parses a cron expression, adding the seconds field to five-field
+/// expressions as expected by the `cron` crate.
+fn parse_cron_schedule(
+    expression: &str,
+) -> Result<Schedule, ConfigError> {
+    let expression = expression.trim();
+    let result = if expression.split_whitespace().count() == 5 {
+        Schedule::from_str(&format!("0 {}", expression))
+    } else {
+        Schedule::from_str(expression)
+    };
+    result.map_err(|e| {
+        ConfigError::ValidationError(format!(
+            "Invalid cron expression for log rotation: '{}': {}",
+            expression, e
+        ))
+    })
 }
 
 impl FromStr for LogRotation {
@@ -119,17 +295,34 @@ impl FromStr for LogRotation {
     /// This function will return an error if the string format is invalid or if the values
     /// provided (e.g. size, time, count) are not valid.
     ///
+    /// The output of `Display`, such as `Size: 1024 bytes`, is accepted as
+    /// well.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts: Vec<&str> = s.trim().splitn(2, ':').collect();
+        let value = |unit: &str| {
+            parts.get(1).map(|value| {
+                let value = value.trim();
+                value.strip_suffix(unit).unwrap_or(value).trim_end()
+            })
+        };
         match parts[0].to_lowercase().as_str() {
-            "size" => parse_nonzero_u64(parts.get(1).copied(), "size")
+            "size" => parse_nonzero_u64(value("bytes"), "size")
                 .map(LogRotation::Size),
-            "time" => parse_nonzero_u64(parts.get(1).copied(), "time")
+            "time" => parse_nonzero_u64(value("seconds"), "time")
                 .map(LogRotation::Time),
-            "date" => Ok(LogRotation::Date),
+            "date" | "date-based rotation" => Ok(LogRotation::Date),
+            "cron" => {
+                let expression = parts.get(1).ok_or_else(|| {
+                    ConfigError::ValidationError(
+                        "Missing cron expression for log rotation"
+                            .to_string(),
+                    )
+                })?;
+                let _ = parse_cron_schedule(expression)?;
+                Ok(LogRotation::Cron(expression.trim().to_string()))
+            }
             "count" => {
-                let count = parts
-                    .get(1)
+                let count = value("logs")
                     .ok_or_else(|| ConfigError::ValidationError("Missing count value for log rotation".to_string()))?
                     .parse()
                     .map_err(|_| ConfigError::ValidationError(format!("Invalid count value for log rotation: '{}'", parts[1])))?;
@@ -192,6 +385,21 @@ fn parse_nonzero_u64(
     })
 }
 
+/// Returns whether `key` names a destination as displayed by
+/// `LoggingDestination`.
+fn is_destination_key(key: &str) -> bool {
+    match key.split_once(':') {
+        Some(("file", target))
+        | Some(("network", target))
+        | Some(("http", target))
+        | Some(("udp-syslog", target))
+        | Some(("tcp", target))
+        | Some(("udp", target)) => !target.trim().is_empty(),
+        Some(_) => false,
+        None => key == "stdout" || key == "stderr",
+    }
+}
+
 /// Enum representing different logging destinations.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(tag = "type", content = "value")]
@@ -200,8 +408,36 @@ pub enum LoggingDestination {
     File(PathBuf),
     /// Log to standard output.
     Stdout,
+    /// Log to standard error.
+    Stderr,
     /// Log to a network destination.
+    ///
+    /// No entries are sent to this destination, as it does not specify a
+    /// transport; use `Tcp` or `Udp` instead.
+    #[deprecated(
+        since = "0.0.7",
+        note = "use `LoggingDestination::Tcp` or `LoggingDestination::Udp`"
+    )]
     Network(String), // Expects format like "127.0.0.1:8080" or "example.com:8080"
+    /// Log to an HTTP endpoint, such as a webhook, by POSTing each entry.
+    ///
+    /// Entries are only delivered when the `reqwest` feature is enabled.
+    Http {
+        /// The HTTP or HTTPS URL that entries are posted to.
+        url: String,
+        /// Headers sent with every request.
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Send each entry to a syslog server as an RFC 5424 UDP datagram.
+    UdpSyslog {
+        /// The address of the syslog server.
+        addr: SocketAddr,
+    },
+    /// Stream each entry to a TCP server as a newline-terminated line.
+    Tcp(SocketAddr),
+    /// Send each entry to a UDP server as a single datagram.
+    Udp(SocketAddr),
 }
 
 // Configuration structure for the logging system.
@@ -219,6 +455,26 @@ pub enum LoggingDestination {
 /// - `log_format`: The format for log messages.
 /// - `logging_destinations`: List of destinations where logs will be sent.
 /// - `env_vars`: Environment variables that apply to the logging system.
+/// - `component_log_levels`: Per-component overrides of the log level.
+/// - `compress_rotated_files`: Whether rotated log files are gzip-compressed.
+/// - `redact_patterns`: Regular expressions whose matches are masked in log entries.
+/// - `per_destination_format`: Log formats overriding the entry format for individual destinations.
+/// - `profiles`: Named configurations, such as `dev` or `prod`, that can be activated over this one.
+/// - `max_message_size`: The maximum length in bytes of a log entry description.
+/// - `fallback_destination`: Where entries go when the log file cannot be written.
+/// - `anonymize_session_ids`: Whether session IDs are hashed before log entries are written.
+/// - `log_rotation_max_files`: The number of rotated log files kept.
+/// - `include_process_context`: Whether the process ID and thread name are attached to log entries.
+/// - `include_hostname`: Whether the machine's hostname is attached to log entries.
+/// - `async_flush_interval_ms`: How often a `BufferedLogger` flushes its entries to the log file.
+/// - `colorize_stdout`: Whether entries written to stdout are colored multi-line blocks.
+/// - `max_queue_depth`: The number of entries a `BufferedLogger` queues before rejecting more.
+/// - `structured_fields_separator`: The character separating extra fields in CLF log file lines.
+/// - `log_level_color_map`: ANSI escape codes coloring the level of entries written to stdout.
+/// - `log_archive_dir`: Where rotated log files older than `max_log_age_days` are moved.
+/// - `max_log_age_days`: The age in days after which rotated log files are archived.
+/// - `output_format_aliases`: Short-hand names, such as `j` for JSON, accepted as `log_format`.
+/// - `rotation_naming_convention`: How rotated log files are named.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Version of the configuration.
@@ -235,7 +491,9 @@ pub struct Config {
     pub log_level: LogLevel,
     /// Log rotation settings.
     pub log_rotation: Option<LogRotation>,
-    /// Log format string.
+    /// Log format string: a `LogFormat` name such as `json`, or a
+    /// printf-style template such as `%level - %message` (see
+    /// `resolve_format`).
     #[serde(default = "default_log_format")]
     pub log_format: String,
     /// Logging destinations for the system.
@@ -244,6 +502,99 @@ pub struct Config {
     /// Environment variables for the system.
     #[serde(default)]
     pub env_vars: HashMap<String, String>,
+    /// Minimum log levels for individual components, overriding `log_level`.
+    #[serde(default)]
+    pub component_log_levels: HashMap<String, LogLevel>,
+    /// Whether rotated log files are compressed with gzip.
+    #[serde(default)]
+    pub compress_rotated_files: bool,
+    /// Regular expressions whose matches are replaced with `[REDACTED]`
+    /// before log entries are written.
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+    /// Log formats for individual destinations, overriding the format of
+    /// the log entry. Keys are destinations as displayed by
+    /// `LoggingDestination`, such as `stdout`, `file:/var/log/app.log` or
+    /// `network:127.0.0.1:514`.
+    #[serde(default)]
+    pub per_destination_format: HashMap<String, LogFormat>,
+    /// Named profiles, such as `dev`, `staging` or `prod`, that can be
+    /// activated with `activate_profile`.
+    #[serde(default)]
+    pub profiles: HashMap<String, Config>,
+    /// Maximum length in bytes of a log entry description. Longer
+    /// descriptions are truncated by `Log::log` (see
+    /// `Log::ensure_max_description`).
+    #[serde(default = "default_max_message_size")]
+    pub max_message_size: usize,
+    /// Destination that entries are written to when writing to
+    /// `log_file_path` fails with an I/O error.
+    #[serde(default)]
+    pub fallback_destination: Option<LoggingDestination>,
+    /// Whether session IDs are replaced by their hash before log entries
+    /// are written (see `Log::anonymize_session_id`).
+    #[serde(default)]
+    pub anonymize_session_ids: bool,
+    /// Number of rotated log files kept, from `RLG.log.1` to
+    /// `RLG.log.<log_rotation_max_files>`. Older files are deleted (see
+    /// `utils::enforce_max_rotation_files`).
+    #[serde(default = "default_log_rotation_max_files")]
+    pub log_rotation_max_files: u32,
+    /// Whether the process ID and thread name are attached to log
+    /// entries before they are written (see
+    /// `Log::with_process_context`).
+    #[serde(default)]
+    pub include_process_context: bool,
+    /// Whether the hostname of the machine is attached to log entries
+    /// before they are written (see `Log::with_hostname`).
+    #[serde(default)]
+    pub include_hostname: bool,
+    /// Interval in milliseconds at which a `BufferedLogger` created by
+    /// `create_buffered_logger` flushes its entries to the log file. Zero
+    /// writes every entry immediately.
+    #[serde(default)]
+    pub async_flush_interval_ms: u64,
+    /// Whether entries written to `LoggingDestination::Stdout` are
+    /// written as colored multi-line blocks (see `Log::to_pretty_string`)
+    /// instead of in their format. Ignored when stdout is not a terminal.
+    #[serde(default)]
+    pub colorize_stdout: bool,
+    /// Maximum number of entries a `BufferedLogger` created by
+    /// `create_buffered_logger` queues before its next flush. Further
+    /// entries are rejected until the queue drains.
+    #[serde(default = "default_max_queue_depth")]
+    pub max_queue_depth: usize,
+    /// Character written before each extra `key=value` field of CLF
+    /// entries in log files, such as a tab or `;`. Must not be `=` or a
+    /// line break.
+    #[serde(default = "default_structured_fields_separator")]
+    pub structured_fields_separator: char,
+    /// ANSI escape codes, such as `"\x1b[31m"` for red, coloring the
+    /// level of entries written as colored blocks (see
+    /// `colorize_stdout`). Levels missing from the map take their color
+    /// from `Config::default_color_map`.
+    #[serde(default)]
+    pub log_level_color_map: HashMap<LogLevel, String>,
+    /// Directory that rotated log files older than `max_log_age_days`
+    /// are moved to whenever the log file is rotated (see
+    /// `utils::archive_old_logs`). Must be set together with
+    /// `max_log_age_days`.
+    #[serde(default)]
+    pub log_archive_dir: Option<PathBuf>,
+    /// Age in days after which rotated log files are moved to
+    /// `log_archive_dir`. Must be set together with `log_archive_dir`.
+    #[serde(default)]
+    pub max_log_age_days: Option<u64>,
+    /// Short-hand names accepted as `log_format`, such as `"j"` for
+    /// `LogFormat::JSON`. Names missing from the map are looked up in
+    /// `Config::default_format_aliases`. `Config::validate` rejects
+    /// aliases of formats that log entries cannot be written in.
+    #[serde(default)]
+    pub output_format_aliases: HashMap<String, LogFormat>,
+    /// How rotated log files are named: `RLG.log.1`, `RLG.2024-01-15.log`
+    /// or `RLG.1705312800000.log` (see `RotationNamingConvention`).
+    #[serde(default)]
+    pub rotation_naming_convention: RotationNamingConvention,
 }
 
 /// Default values for configuration fields.
@@ -262,6 +613,18 @@ fn default_log_format() -> String {
 fn default_logging_destinations() -> Vec<LoggingDestination> {
     vec![LoggingDestination::File(PathBuf::from("RLG.log"))]
 }
+fn default_max_message_size() -> usize {
+    64 * 1024
+}
+fn default_log_rotation_max_files() -> u32 {
+    10
+}
+fn default_max_queue_depth() -> usize {
+    10_000
+}
+fn default_structured_fields_separator() -> char {
+    ' '
+}
 
 impl Default for Config {
     fn default() -> Self {
@@ -275,130 +638,980 @@ fn default() -> Self {
             log_format: default_log_format(),
             logging_destinations: default_logging_destinations(),
             env_vars: HashMap::new(),
+            component_log_levels: HashMap::new(),
+            compress_rotated_files: false,
+            redact_patterns: Vec::new(),
+            per_destination_format: HashMap::new(),
+            profiles: HashMap::new(),
+            max_message_size: default_max_message_size(),
+            fallback_destination: None,
+            anonymize_session_ids: false,
+            log_rotation_max_files: default_log_rotation_max_files(),
+            include_process_context: false,
+            include_hostname: false,
+            async_flush_interval_ms: 0,
+            colorize_stdout: false,
+            max_queue_depth: default_max_queue_depth(),
+            structured_fields_separator:
+                default_structured_fields_separator(),
+            log_level_color_map: HashMap::new(),
+            log_archive_dir: None,
+            max_log_age_days: None,
+            output_format_aliases: HashMap::new(),
+            rotation_naming_convention:
+                RotationNamingConvention::default(),
         }
     }
 }
 
 impl Config {
     /// Loads configuration from a file or environment variables.
+    ///
+    /// The file format is chosen from the file extension with
+    /// `detect_format`, so JSON and YAML files are read as well as TOML.
+    /// When `profile` is given, the named profile is activated after
+    /// loading, as with `activate_profile`.
     pub async fn load_async<P: AsRef<Path>>(
         config_path: Option<P>,
+        profile: Option<&str>,
     ) -> Result<Arc<RwLock<Config>>, ConfigError> {
         let config = if let Some(path) = config_path {
-            let mut file = File::open(&path).await.map_err(|e| {
-                ConfigError::FileReadError(e.to_string())
-            })?;
-            let mut contents = String::new();
-            file.read_to_string(&mut contents).await.map_err(|e| {
-                ConfigError::FileReadError(e.to_string())
-            })?;
-            let config_source = ConfigSource::builder()
-                .add_source(ConfigFile::from_str(
-                    &contents,
-                    config::FileFormat::Toml,
-                ))
-                .build()?;
-            let version: String = config_source.get("version")?;
-            if version != CURRENT_CONFIG_VERSION {
-                return Err(ConfigError::VersionError(format!(
-                    "Unsupported configuration version: {}",
-                    version
-                )));
-            }
-            config_source.try_deserialize()?
+            Config::read_file_async(path.as_ref()).await?
         } else {
             Config::default()
         };
         config.validate()?;
+        let config = match profile {
+            Some(name) => config.apply_profile(name)?,
+            None => config,
+        };
         Ok(Arc::new(RwLock::new(config)))
     }
 
-    /// Retrieves a value from the configuration based on the specified key.
-    pub fn get<T>(&self, key: &str) -> Option<T>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let value = match key {
-            "version" => serde_json::to_value(&self.version).ok()?,
-            "profile" => serde_json::to_value(&self.profile).ok()?,
-            "log_file_path" => {
-                serde_json::to_value(&self.log_file_path).ok()?
-            }
-            "log_level" => serde_json::to_value(self.log_level).ok()?,
-            "log_rotation" => {
-                serde_json::to_value(self.log_rotation).ok()?
-            }
-            "log_format" => {
-                serde_json::to_value(&self.log_format).ok()?
+    /// Loads configuration by layering several sources in order.
+    ///
+    /// Each source is loaded and validated on its own. Only the keys a
+    /// source actually sets, such as the fields named in a file or the
+    /// `RLG_*` variables that are set, replace those of the sources
+    /// before it, so later sources win without resetting the other
+    /// fields to their defaults. `ConfigSource::Defaults` sets every key.
+    /// The merged configuration is validated once more.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first source that cannot be read, parsed
+    /// or validated, or the validation error of the merged configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::{Config, ConfigSource};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Config::load_layered_async(&[
+    ///     ConfigSource::Defaults,
+    ///     ConfigSource::Str("version = \"1.0\"\nlog_level = \"DEBUG\""),
+    /// ])
+    /// .await
+    /// .unwrap();
+    /// assert_eq!(config.read().log_level, rlg::LogLevel::DEBUG);
+    /// # });
+    /// ```
+    pub async fn load_layered_async(
+        sources: &[ConfigSource],
+    ) -> Result<Arc<RwLock<Config>>, ConfigError> {
+        let json_error = |e: serde_json::Error| {
+            ConfigError::ConfigParseError(SourceConfigError::Message(
+                e.to_string(),
+            ))
+        };
+        let to_map =
+            |config: &Config| match serde_json::to_value(config) {
+                Ok(serde_json::Value::Object(map)) => Ok(map),
+                Ok(_) => Ok(serde_json::Map::new()),
+                Err(e) => Err(json_error(e)),
+            };
+
+        let mut merged = to_map(&Config::default())?;
+        for source in sources {
+            let (layer, keys) = match source {
+                ConfigSource::Defaults => (Config::default(), None),
+                ConfigSource::File(path) => {
+                    let contents =
+                        Config::read_file_contents(path).await?;
+                    let format = Config::detect_format(path);
+                    let layer =
+                        Config::parse_contents(&contents, format)?;
+                    layer.validate()?;
+                    (
+                        layer,
+                        Some(Config::document_keys(&contents, format)?),
+                    )
+                }
+                ConfigSource::EnvVars => {
+                    (Config::load_from_env()?, Some(Config::env_keys()))
+                }
+                ConfigSource::Str(s) => (
+                    Config::from_toml_str(s)?,
+                    Some(Config::document_keys(
+                        s,
+                        config::FileFormat::Toml,
+                    )?),
+                ),
+            };
+            for (key, value) in to_map(&layer)? {
+                if keys
+                    .as_ref()
+                    .map_or(true, |keys| keys.contains(&key))
+                {
+                    let _ = merged.insert(key, value);
+                }
             }
-            "logging_destinations" => {
-                serde_json::to_value(&self.logging_destinations).ok()?
+        }
+        let config: Config =
+            serde_json::from_value(serde_json::Value::Object(merged))
+                .map_err(json_error)?;
+        config.validate()?;
+        Ok(Arc::new(RwLock::new(config)))
+    }
+
+    /// Returns the top-level keys set by a configuration document.
+    fn document_keys(
+        contents: &str,
+        format: config::FileFormat,
+    ) -> Result<Vec<String>, ConfigError> {
+        if format == config::FileFormat::Yaml {
+            let mapping: serde_yml::Mapping =
+                serde_yml::from_str(contents).map_err(|e| {
+                    ConfigError::ConfigParseError(
+                        SourceConfigError::Message(e.to_string()),
+                    )
+                })?;
+            return Ok(mapping
+                .keys()
+                .filter_map(|key| key.as_str().map(str::to_string))
+                .collect());
+        }
+        let values = SourceConfig::builder()
+            .add_source(ConfigFile::from_str(contents, format))
+            .build()?
+            .try_deserialize::<config::Map<String, config::Value>>()?;
+        Ok(values.into_keys().collect())
+    }
+
+    /// Returns the keys set by the `RLG_*` environment variables read by
+    /// `Config::load_from_env`.
+    fn env_keys() -> Vec<String> {
+        [
+            ("RLG_VERSION", "version"),
+            ("RLG_PROFILE", "profile"),
+            ("RLG_LOG_FILE_PATH", "log_file_path"),
+            ("RLG_LOG_LEVEL", "log_level"),
+            ("RLG_LOG_FORMAT", "log_format"),
+            ("RLG_LOG_ROTATION", "log_rotation"),
+        ]
+        .iter()
+        .filter(|(name, _)| env::var_os(name).is_some())
+        .map(|(_, key)| key.to_string())
+        .collect()
+    }
+
+    /// Reads and parses a configuration file, in the format given by its
+    /// extension, without validating it.
+    async fn read_file_async(
+        path: &Path,
+    ) -> Result<Config, ConfigError> {
+        let contents = Config::read_file_contents(path).await?;
+        Config::parse_contents(&contents, Config::detect_format(path))
+    }
+
+    /// Reads the contents of a configuration file.
+    async fn read_file_contents(
+        path: &Path,
+    ) -> Result<String, ConfigError> {
+        let mut file = File::open(path)
+            .await
+            .map_err(|e| ConfigError::FileReadError(e.to_string()))?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .await
+            .map_err(|e| ConfigError::FileReadError(e.to_string()))?;
+        Ok(contents)
+    }
+
+    /// Parses a configuration string in `format`, without validating it.
+    fn parse_contents(
+        contents: &str,
+        format: config::FileFormat,
+    ) -> Result<Config, ConfigError> {
+        match format {
+            config::FileFormat::Yaml => {
+                Config::parse_yaml_str(contents)
             }
-            "env_vars" => serde_json::to_value(&self.env_vars).ok()?,
-            _ => return None,
-        };
-        serde_json::from_value(value).ok()
+            format => Config::parse_str(contents, format),
+        }
     }
 
-    /// Saves the current configuration to a file.
-    pub fn save_to_file<P: AsRef<Path>>(
-        &self,
-        path: P,
-    ) -> Result<(), ConfigError> {
-        let config_string = serde_json::to_string_pretty(self)
-            .map_err(|e| {
-                ConfigError::FileWriteError(format!(
-                    "Failed to serialize config: {}",
+    /// Sets the application-wide configuration returned by
+    /// `Config::global`.
+    ///
+    /// Once set, it is used by `Log::log()` instead of the default
+    /// configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the global configuration has already been set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, LogLevel};
+    ///
+    /// Config::init_global(Config::default());
+    /// Config::global().write().log_level = LogLevel::WARN;
+    /// assert_eq!(Config::global().read().log_level, LogLevel::WARN);
+    /// ```
+    pub fn init_global(config: Config) {
+        if GLOBAL_CONFIG.set(Arc::new(RwLock::new(config))).is_err() {
+            panic!("The global configuration has already been set");
+        }
+    }
+
+    /// Returns the application-wide configuration set by
+    /// `Config::init_global`.
+    ///
+    /// Changes made through the returned lock are seen by every holder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Config::init_global` has not been called yet.
+    pub fn global() -> Arc<RwLock<Config>> {
+        GLOBAL_CONFIG
+            .get()
+            .cloned()
+            .expect("The global configuration is not set; call Config::init_global first")
+    }
+
+    /// Returns the format of a configuration file, based on its
+    /// extension.
+    ///
+    /// `.json` files are JSON and `.yaml` or `.yml` files are YAML; any
+    /// other extension, or none, falls back to TOML. Extensions are
+    /// matched case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::Config;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(Config::detect_format(Path::new("rlg.json")), config::FileFormat::Json);
+    /// assert_eq!(Config::detect_format(Path::new("rlg.yml")), config::FileFormat::Yaml);
+    /// assert_eq!(Config::detect_format(Path::new("rlg.conf")), config::FileFormat::Toml);
+    /// ```
+    pub fn detect_format(path: &Path) -> config::FileFormat {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("json") => config::FileFormat::Json,
+            Some("yaml" | "yml") => config::FileFormat::Yaml,
+            _ => config::FileFormat::Toml,
+        }
+    }
+
+    /// Parses and validates a configuration from a TOML string.
+    ///
+    /// This is a synchronous alternative to `load_async` for code that runs
+    /// outside an async runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingFieldError` if `version` is missing,
+    /// `ConfigError::VersionError` if the version is not supported, or any
+    /// parse or validation error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::Config;
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("app.log");
+    /// let toml = format!(
+    ///     "version = \"1.0\"\nlog_file_path = {:?}\nlog_level = \"DEBUG\"\n\
+    ///      logging_destinations = [{{ type = \"Stdout\" }}]",
+    ///     path
+    /// );
+    /// let config = Config::from_toml_str(&toml).unwrap();
+    /// assert_eq!(config.log_level, rlg::LogLevel::DEBUG);
+    /// ```
+    pub fn from_toml_str(s: &str) -> Result<Config, ConfigError> {
+        let config = Config::parse_str(s, config::FileFormat::Toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses and validates a configuration from a JSON string.
+    ///
+    /// This behaves like `from_toml_str` for JSON-formatted configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::MissingFieldError` if `version` is missing,
+    /// `ConfigError::VersionError` if the version is not supported, or any
+    /// parse or validation error.
+    pub fn from_json_str(s: &str) -> Result<Config, ConfigError> {
+        let config = Config::parse_str(s, config::FileFormat::Json)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Builds and validates a configuration from environment variables
+    /// only.
+    ///
+    /// The variables `RLG_VERSION`, `RLG_PROFILE`, `RLG_LOG_FILE_PATH`,
+    /// `RLG_LOG_LEVEL`, `RLG_LOG_FORMAT` and `RLG_LOG_ROTATION` set the
+    /// matching fields; fields whose variable is not set keep the values
+    /// of `Config::default()`. `RLG_LOG_ROTATION` uses the syntax accepted
+    /// by `LogRotation::from_str`, such as `size:1048576` or `date`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::ValidationError` if a variable holds an
+    /// invalid value or the resulting configuration is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::Config;
+    ///
+    /// std::env::set_var("RLG_LOG_LEVEL", "WARN");
+    /// let config = Config::load_from_env().unwrap();
+    /// assert_eq!(config.log_level, rlg::LogLevel::WARN);
+    /// # std::env::remove_var("RLG_LOG_LEVEL");
+    /// ```
+    pub fn load_from_env() -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+
+        if let Ok(version) = env::var("RLG_VERSION") {
+            config.version = version;
+        }
+        if let Ok(profile) = env::var("RLG_PROFILE") {
+            config.profile = profile;
+        }
+        if let Ok(path) = env::var("RLG_LOG_FILE_PATH") {
+            config.log_file_path = PathBuf::from(path);
+        }
+        if let Ok(level) = env::var("RLG_LOG_LEVEL") {
+            config.log_level = level.parse().map_err(|e| {
+                ConfigError::ValidationError(format!(
+                    "Invalid RLG_LOG_LEVEL value: {}",
                     e
                 ))
             })?;
-        fs::write(path, config_string).map_err(|e| {
-            ConfigError::FileWriteError(format!(
-                "Failed to write config file: {}",
-                e
-            ))
-        })?;
-        Ok(())
+        }
+        if let Ok(format) = env::var("RLG_LOG_FORMAT") {
+            config.log_format = format;
+        }
+        if let Ok(rotation) = env::var("RLG_LOG_ROTATION") {
+            config.log_rotation = Some(rotation.parse()?);
+        }
+
+        config.validate()?;
+        Ok(config)
     }
 
-    /// Sets a value in the configuration based on the specified key.
-    pub fn set<T: Serialize>(
-        &mut self,
-        key: &str,
-        value: T,
-    ) -> Result<(), ConfigError> {
-        let serialize_value =
-            |v: T| -> Result<serde_json::Value, ConfigError> {
-                serde_json::to_value(v).map_err(|e| {
-                    ConfigError::ValidationError(e.to_string())
-                })
+    /// Builds and validates a configuration from command-line arguments.
+    ///
+    /// The flags `--log-level`, `--log-file`, `--log-format`,
+    /// `--log-rotation` and `--profile` set the matching fields, either
+    /// followed by their value, as in `--log-level debug`, or as
+    /// `--log-level=debug`. Other arguments are ignored, and fields
+    /// without a flag keep the values of `Config::default()`. When a flag
+    /// is given more than once, the last value wins. `--log-rotation`
+    /// uses the syntax accepted by `LogRotation::from_str`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::ValidationError` if a flag has no value or
+    /// an invalid one, or if the resulting configuration is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, LogLevel};
+    ///
+    /// let config = Config::from_args(&["--log-level", "debug", "--verbose"]).unwrap();
+    /// assert_eq!(config.log_level, LogLevel::DEBUG);
+    /// ```
+    pub fn from_args(args: &[&str]) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value)),
+                None => (*arg, None),
             };
-
-        match key {
-            "version" => {
-                self.version = serialize_value(value)?
-                    .as_str()
-                    .ok_or_else(|| {
-                        ConfigError::ValidationError(
-                            "Invalid version format".to_string(),
-                        )
-                    })?
-                    .to_string()
+            if !matches!(
+                flag,
+                "--log-level"
+                    | "--log-file"
+                    | "--log-format"
+                    | "--log-rotation"
+                    | "--profile"
+            ) {
+                continue;
             }
-            "profile" => {
-                self.profile = serialize_value(value)?
-                    .as_str()
-                    .ok_or_else(|| {
-                        ConfigError::ValidationError(
-                            "Invalid profile format".to_string(),
-                        )
+            let value = match inline.or_else(|| args.next().copied()) {
+                Some(value) => value,
+                None => {
+                    return Err(ConfigError::ValidationError(format!(
+                        "Missing value for {}",
+                        flag
+                    )))
+                }
+            };
+            match flag {
+                "--log-level" => {
+                    config.log_level = value.parse().map_err(|e| {
+                        ConfigError::ValidationError(format!(
+                            "Invalid --log-level value: {}",
+                            e
+                        ))
                     })?
-                    .to_string()
+                }
+                "--log-file" => {
+                    config.log_file_path = PathBuf::from(value)
+                }
+                "--log-format" => config.log_format = value.to_string(),
+                "--log-rotation" => {
+                    config.log_rotation = Some(value.parse()?)
+                }
+                "--profile" => config.profile = value.to_string(),
+                _ => {}
             }
-            "log_file_path" => {
-                self.log_file_path =
-                    serde_json::from_value(serialize_value(value)?)
-                        .map_err(|e| {
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Serializes the configuration to environment variables, such as
+    /// `RLG_LOG_LEVEL` or `RLG_LOG_FILE_PATH`, for container deployments.
+    ///
+    /// Every field becomes a variable named after it in upper case, with
+    /// the `RLG_` prefix. Strings, such as paths and levels, are written
+    /// as they are, `log_rotation` as its `Display` output, and other
+    /// values as JSON. Unset optional fields are written as empty
+    /// strings. Fields that cannot be serialized, such as custom formats
+    /// in `per_destination_format`, are left out. The variables are sorted
+    /// by name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::Config;
+    ///
+    /// let vars = Config::default().to_env_vars();
+    /// assert!(vars.contains(&("RLG_LOG_LEVEL".to_string(), "INFO".to_string())));
+    /// ```
+    pub fn to_env_vars(&self) -> Vec<(String, String)> {
+        let fields = match serde_json::to_value(Config::default()) {
+            Ok(serde_json::Value::Object(fields)) => fields,
+            _ => return Vec::new(),
+        };
+        let mut vars: Vec<(String, String)> = fields
+            .keys()
+            .filter_map(|key| {
+                let value = if key == "log_rotation" {
+                    self.log_rotation
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default()
+                } else {
+                    match self.get::<serde_json::Value>(key)? {
+                        serde_json::Value::Null => String::new(),
+                        serde_json::Value::String(value) => value,
+                        value => value.to_string(),
+                    }
+                };
+                Some((format!("RLG_{}", key.to_uppercase()), value))
+            })
+            .collect();
+        vars.sort();
+        vars
+    }
+
+    /// Builds and validates a configuration from environment variables
+    /// written by `Config::to_env_vars`.
+    ///
+    /// Pairs whose name does not start with `RLG_` are ignored; fields
+    /// without a variable keep the values of `Config::default()`. Empty
+    /// values unset optional fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::ValidationError` if a variable names an
+    /// unknown field or holds an invalid value, or if the resulting
+    /// configuration is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, LogLevel};
+    ///
+    /// let config = Config::from_env_pairs(&[
+    ///     ("RLG_LOG_LEVEL", "WARN"),
+    ///     ("RLG_LOG_ROTATION", "count:100"),
+    ///     ("HOME", "/root"),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(config.log_level, LogLevel::WARN);
+    /// ```
+    pub fn from_env_pairs(
+        pairs: &[(&str, &str)],
+    ) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        for (name, value) in pairs {
+            let key = match name.strip_prefix("RLG_") {
+                Some(key) => key.to_lowercase(),
+                None => continue,
+            };
+            if key == "log_rotation" {
+                config.log_rotation = match *value {
+                    "" => None,
+                    rotation => Some(rotation.parse()?),
+                };
+                continue;
+            }
+
+            // Values are written as plain strings where the field is a
+            // string, and as JSON otherwise.
+            let plain = if value.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::Value::String(value.to_string())
+            };
+            if config.set(&key, plain).is_ok() {
+                continue;
+            }
+            let parsed =
+                serde_json::from_str(value).unwrap_or_else(|_| {
+                    serde_json::Value::String(value.to_string())
+                });
+            config.set(&key, parsed).map_err(|e| {
+                ConfigError::ValidationError(format!(
+                    "Invalid {} value: {}",
+                    name, e
+                ))
+            })?;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses a configuration string, checking its version.
+    fn parse_str(
+        s: &str,
+        format: config::FileFormat,
+    ) -> Result<Config, ConfigError> {
+        let config_source = SourceConfig::builder()
+            .add_source(ConfigFile::from_str(s, format))
+            .build()?;
+        let version: String = match config_source.get("version") {
+            Err(SourceConfigError::NotFound(_)) => {
+                return Err(ConfigError::MissingFieldError(
+                    "version".to_string(),
+                ))
+            }
+            result => result?,
+        };
+        if version != CURRENT_CONFIG_VERSION {
+            return Err(ConfigError::VersionError(format!(
+                "Unsupported configuration version: {}",
+                version
+            )));
+        }
+        let mut config: Config =
+            config_source.clone().try_deserialize()?;
+        config.profiles = Config::parse_profiles(&config_source)?;
+        Ok(config)
+    }
+
+    /// Parses the `[profiles.<name>]` sections of a configuration.
+    ///
+    /// Each profile starts from the base configuration, with the values set
+    /// in its section layered on top.
+    fn parse_profiles(
+        config_source: &SourceConfig,
+    ) -> Result<HashMap<String, Config>, ConfigError> {
+        let sections = match config_source.get_table("profiles") {
+            Err(SourceConfigError::NotFound(_)) => {
+                return Ok(HashMap::new())
+            }
+            result => result?,
+        };
+
+        let mut profiles = HashMap::new();
+        for (name, section) in sections {
+            let mut builder = SourceConfig::builder()
+                .add_source(config_source.clone())
+                .set_override(
+                    "profiles",
+                    config::Map::<String, config::Value>::new(),
+                )?;
+            for (key, value) in section.into_table()? {
+                builder = builder.set_override(key.as_str(), value)?;
+            }
+            let mut profile: Config =
+                builder.build()?.try_deserialize()?;
+            profile.profile = name.clone();
+            let _ = profiles.insert(name, profile);
+        }
+        Ok(profiles)
+    }
+
+    /// Returns this configuration with the named profile activated.
+    ///
+    /// The values of the profile are merged over the base configuration
+    /// with `merge`, and `profile` is set to `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConfigError::ValidationError` wrapped in
+    /// `RlgError::ConfigError` if no profile named `name` is defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, log_level::LogLevel};
+    ///
+    /// let mut config = Config::default();
+    /// let prod = Config {
+    ///     log_level: LogLevel::ERROR,
+    ///     ..config.clone()
+    /// };
+    /// config.profiles.insert("prod".to_string(), prod);
+    ///
+    /// let active = config.activate_profile("prod").unwrap();
+    /// assert_eq!(active.log_level, LogLevel::ERROR);
+    /// assert_eq!(active.profile, "prod");
+    /// assert!(config.activate_profile("qa").is_err());
+    /// ```
+    pub fn activate_profile(&self, name: &str) -> RlgResult<Config> {
+        Ok(self.apply_profile(name)?)
+    }
+
+    /// Merges the named profile over this configuration.
+    fn apply_profile(&self, name: &str) -> Result<Config, ConfigError> {
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            ConfigError::ValidationError(format!(
+                "Unknown configuration profile: '{}'",
+                name
+            ))
+        })?;
+        let mut active = self.merge(profile);
+        active.profile = name.to_string();
+        Ok(active)
+    }
+
+    /// Retrieves a value from the configuration based on the specified key.
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let value = match key {
+            "version" => serde_json::to_value(&self.version).ok()?,
+            "profile" => serde_json::to_value(&self.profile).ok()?,
+            "log_file_path" => {
+                serde_json::to_value(&self.log_file_path).ok()?
+            }
+            "log_level" => serde_json::to_value(self.log_level).ok()?,
+            "log_rotation" => {
+                serde_json::to_value(&self.log_rotation).ok()?
+            }
+            "log_format" => {
+                serde_json::to_value(&self.log_format).ok()?
+            }
+            "logging_destinations" => {
+                serde_json::to_value(&self.logging_destinations).ok()?
+            }
+            "env_vars" => serde_json::to_value(&self.env_vars).ok()?,
+            "component_log_levels" => {
+                serde_json::to_value(&self.component_log_levels).ok()?
+            }
+            "compress_rotated_files" => {
+                serde_json::to_value(self.compress_rotated_files)
+                    .ok()?
+            }
+            "redact_patterns" => {
+                serde_json::to_value(&self.redact_patterns).ok()?
+            }
+            "per_destination_format" => {
+                serde_json::to_value(&self.per_destination_format)
+                    .ok()?
+            }
+            "profiles" => serde_json::to_value(&self.profiles).ok()?,
+            "max_message_size" => {
+                serde_json::to_value(self.max_message_size).ok()?
+            }
+            "fallback_destination" => {
+                serde_json::to_value(&self.fallback_destination).ok()?
+            }
+            "anonymize_session_ids" => {
+                serde_json::to_value(self.anonymize_session_ids).ok()?
+            }
+            "log_rotation_max_files" => {
+                serde_json::to_value(self.log_rotation_max_files)
+                    .ok()?
+            }
+            "include_process_context" => {
+                serde_json::to_value(self.include_process_context)
+                    .ok()?
+            }
+            "include_hostname" => {
+                serde_json::to_value(self.include_hostname).ok()?
+            }
+            "async_flush_interval_ms" => {
+                serde_json::to_value(self.async_flush_interval_ms)
+                    .ok()?
+            }
+            "colorize_stdout" => {
+                serde_json::to_value(self.colorize_stdout).ok()?
+            }
+            "max_queue_depth" => {
+                serde_json::to_value(self.max_queue_depth).ok()?
+            }
+            "structured_fields_separator" => {
+                serde_json::to_value(self.structured_fields_separator)
+                    .ok()?
+            }
+            "log_level_color_map" => {
+                serde_json::to_value(&self.log_level_color_map).ok()?
+            }
+            "log_archive_dir" => {
+                serde_json::to_value(&self.log_archive_dir).ok()?
+            }
+            "max_log_age_days" => {
+                serde_json::to_value(self.max_log_age_days).ok()?
+            }
+            "output_format_aliases" => {
+                serde_json::to_value(&self.output_format_aliases)
+                    .ok()?
+            }
+            "rotation_naming_convention" => {
+                serde_json::to_value(self.rotation_naming_convention)
+                    .ok()?
+            }
+            _ => return None,
+        };
+        serde_json::from_value(value).ok()
+    }
+
+    /// Saves the current configuration to a file.
+    pub fn save_to_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), ConfigError> {
+        let config_string = serde_json::to_string_pretty(self)
+            .map_err(|e| {
+                ConfigError::FileWriteError(format!(
+                    "Failed to serialize config: {}",
+                    e
+                ))
+            })?;
+        fs::write(path, config_string).map_err(|e| {
+            ConfigError::FileWriteError(format!(
+                "Failed to write config file: {}",
+                e
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Validates the configuration and saves it to a YAML file.
+    ///
+    /// Sequences such as `logging_destinations` are written in block
+    /// style, one item per line, so the file is easy to edit by hand.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if the configuration is invalid, or
+    /// `ConfigError::FileWriteError` if it cannot be written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::Config;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("rlg.yaml");
+    /// Config::default().save_to_yaml(&path).unwrap();
+    ///
+    /// let loaded = Config::load_from_yaml_async(&path).await.unwrap();
+    /// assert_eq!(loaded.read().log_file_path, Config::default().log_file_path);
+    /// # });
+    /// ```
+    pub fn save_to_yaml(&self, path: &Path) -> Result<(), ConfigError> {
+        self.validate()?;
+        let config_string =
+            serde_yml::to_string(self).map_err(|e| {
+                ConfigError::FileWriteError(format!(
+                    "Failed to serialize config: {}",
+                    e
+                ))
+            })?;
+        fs::write(path, config_string).map_err(|e| {
+            ConfigError::FileWriteError(format!(
+                "Failed to write config file: {}",
+                e
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Loads and validates a configuration from a YAML file.
+    ///
+    /// As with TOML files, each entry of `profiles` starts from the base
+    /// configuration, with the values it sets layered on top.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::FileReadError` if the file cannot be read,
+    /// `ConfigError::MissingFieldError` if `version` is missing,
+    /// `ConfigError::VersionError` if the version is not supported, or any
+    /// parse or validation error.
+    pub async fn load_from_yaml_async(
+        path: &Path,
+    ) -> Result<Arc<RwLock<Config>>, ConfigError> {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| ConfigError::FileReadError(e.to_string()))?;
+        let config = Config::parse_yaml_str(&contents)?;
+        config.validate()?;
+        Ok(Arc::new(RwLock::new(config)))
+    }
+
+    /// Parses a YAML configuration string, checking its version.
+    fn parse_yaml_str(s: &str) -> Result<Config, ConfigError> {
+        let parse_error = |message: String| {
+            ConfigError::ConfigParseError(SourceConfigError::Message(
+                message,
+            ))
+        };
+        let yaml_error =
+            |e: serde_yml::Error| parse_error(e.to_string());
+
+        let value: serde_yml::Value =
+            serde_yml::from_str(s).map_err(yaml_error)?;
+        let mut base = match value {
+            serde_yml::Value::Mapping(mapping) => mapping,
+            _ => {
+                return Err(parse_error(
+                    "YAML configuration must be a mapping".to_string(),
+                ))
+            }
+        };
+        let version: String = match base.get("version") {
+            Some(version) => serde_yml::from_value(version.clone())
+                .map_err(yaml_error)?,
+            None => {
+                return Err(ConfigError::MissingFieldError(
+                    "version".to_string(),
+                ))
+            }
+        };
+        if version != CURRENT_CONFIG_VERSION {
+            return Err(ConfigError::VersionError(format!(
+                "Unsupported configuration version: {}",
+                version
+            )));
+        }
+
+        let sections = base.remove("profiles");
+        let mut config: Config = serde_yml::from_value(
+            serde_yml::Value::Mapping(base.clone()),
+        )
+        .map_err(yaml_error)?;
+        let sections = match sections {
+            None | Some(serde_yml::Value::Null) => return Ok(config),
+            Some(serde_yml::Value::Mapping(sections)) => sections,
+            Some(_) => {
+                return Err(parse_error(
+                    "YAML profiles must be a mapping".to_string(),
+                ))
+            }
+        };
+        for (name, section) in sections {
+            let name: String =
+                serde_yml::from_value(name).map_err(yaml_error)?;
+            let mut layered = base.clone();
+            match section {
+                serde_yml::Value::Mapping(section) => {
+                    for (key, value) in section {
+                        let _ = layered.insert(key, value);
+                    }
+                }
+                _ => {
+                    return Err(parse_error(format!(
+                        "YAML profile '{}' must be a mapping",
+                        name
+                    )))
+                }
+            }
+            let mut profile: Config = serde_yml::from_value(
+                serde_yml::Value::Mapping(layered),
+            )
+            .map_err(yaml_error)?;
+            profile.profile = name.clone();
+            let _ = config.profiles.insert(name, profile);
+        }
+        Ok(config)
+    }
+
+    /// Sets a value in the configuration based on the specified key.
+    pub fn set<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: T,
+    ) -> Result<(), ConfigError> {
+        let serialize_value =
+            |v: T| -> Result<serde_json::Value, ConfigError> {
+                serde_json::to_value(v).map_err(|e| {
+                    ConfigError::ValidationError(e.to_string())
+                })
+            };
+
+        match key {
+            "version" => {
+                self.version = serialize_value(value)?
+                    .as_str()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid version format".to_string(),
+                        )
+                    })?
+                    .to_string()
+            }
+            "profile" => {
+                self.profile = serialize_value(value)?
+                    .as_str()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid profile format".to_string(),
+                        )
+                    })?
+                    .to_string()
+            }
+            "log_file_path" => {
+                self.log_file_path =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|e| {
                             ConfigError::ConfigParseError(
                                 SourceConfigError::Message(
                                     e.to_string(),
@@ -460,109 +1673,506 @@ pub fn set<T: Serialize>(
                             )
                         })?
             }
-            _ => {
-                return Err(ConfigError::ValidationError(format!(
-                    "Unknown configuration key: {}",
-                    key
-                )))
+            "component_log_levels" => {
+                self.component_log_levels =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|e| {
+                            ConfigError::ConfigParseError(
+                                SourceConfigError::Message(
+                                    e.to_string(),
+                                ),
+                            )
+                        })?
             }
-        }
-        Ok(())
-    }
-
-    /// Validates the configuration settings.
-    pub fn validate(&self) -> Result<(), ConfigError> {
-        if self.version.trim().is_empty() {
-            return Err(ConfigError::ValidationError(
-                "Version cannot be empty".to_string(),
-            ));
-        }
-        if self.profile.trim().is_empty() {
-            return Err(ConfigError::ValidationError(
-                "Profile cannot be empty".to_string(),
-            ));
-        }
-        if self.log_file_path.as_os_str().is_empty() {
-            return Err(ConfigError::ValidationError(
-                "Log file path cannot be empty".to_string(),
-            ));
-        }
-        if let Some(rotation) = &self.log_rotation {
-            match rotation {
-                LogRotation::Size(size) if size.get() == 0 => {
-                    return Err(ConfigError::ValidationError(
-                        "Log rotation size must be greater than 0"
-                            .to_string(),
-                    ));
+            "compress_rotated_files" => {
+                self.compress_rotated_files = serialize_value(value)?
+                    .as_bool()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid compress_rotated_files value"
+                                .to_string(),
+                        )
+                    })?
+            }
+            "redact_patterns" => {
+                self.redact_patterns =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|e| {
+                            ConfigError::ConfigParseError(
+                                SourceConfigError::Message(
+                                    e.to_string(),
+                                ),
+                            )
+                        })?
+            }
+            "per_destination_format" => {
+                self.per_destination_format =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|e| {
+                            ConfigError::ConfigParseError(
+                                SourceConfigError::Message(
+                                    e.to_string(),
+                                ),
+                            )
+                        })?
+            }
+            "profiles" => {
+                self.profiles =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|e| {
+                            ConfigError::ConfigParseError(
+                                SourceConfigError::Message(
+                                    e.to_string(),
+                                ),
+                            )
+                        })?
+            }
+            "max_message_size" => {
+                self.max_message_size =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                                "Invalid max_message_size value"
+                                    .to_string(),
+                            )
+                        })?
+            }
+            "fallback_destination" => {
+                self.fallback_destination =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|e| {
+                            ConfigError::ConfigParseError(
+                                SourceConfigError::Message(
+                                    e.to_string(),
+                                ),
+                            )
+                        })?
+            }
+            "anonymize_session_ids" => {
+                self.anonymize_session_ids = serialize_value(value)?
+                    .as_bool()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid anonymize_session_ids value"
+                                .to_string(),
+                        )
+                    })?
+            }
+            "log_rotation_max_files" => {
+                self.log_rotation_max_files =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                                "Invalid log_rotation_max_files value"
+                                    .to_string(),
+                            )
+                        })?
+            }
+            "include_process_context" => {
+                self.include_process_context = serialize_value(value)?
+                    .as_bool()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid include_process_context value"
+                                .to_string(),
+                        )
+                    })?
+            }
+            "include_hostname" => {
+                self.include_hostname = serialize_value(value)?
+                    .as_bool()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid include_hostname value"
+                                .to_string(),
+                        )
+                    })?
+            }
+            "async_flush_interval_ms" => {
+                self.async_flush_interval_ms = serialize_value(value)?
+                    .as_u64()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid async_flush_interval_ms value"
+                                .to_string(),
+                        )
+                    })?
+            }
+            "colorize_stdout" => {
+                self.colorize_stdout = serialize_value(value)?
+                    .as_bool()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(
+                            "Invalid colorize_stdout value".to_string(),
+                        )
+                    })?
+            }
+            "max_queue_depth" => {
+                self.max_queue_depth =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                                "Invalid max_queue_depth value"
+                                    .to_string(),
+                            )
+                        })?
+            }
+            "structured_fields_separator" => {
+                self.structured_fields_separator =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                            "Invalid structured_fields_separator value"
+                                .to_string(),
+                        )
+                        })?
+            }
+            "log_level_color_map" => {
+                self.log_level_color_map =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                                "Invalid log_level_color_map value"
+                                    .to_string(),
+                            )
+                        })?
+            }
+            "log_archive_dir" => {
+                self.log_archive_dir =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                                "Invalid log_archive_dir value"
+                                    .to_string(),
+                            )
+                        })?
+            }
+            "max_log_age_days" => {
+                self.max_log_age_days =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                                "Invalid max_log_age_days value"
+                                    .to_string(),
+                            )
+                        })?
+            }
+            "output_format_aliases" => {
+                self.output_format_aliases =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                                "Invalid output_format_aliases value"
+                                    .to_string(),
+                            )
+                        })?
+            }
+            "rotation_naming_convention" => {
+                self.rotation_naming_convention =
+                    serde_json::from_value(serialize_value(value)?)
+                        .map_err(|_| {
+                            ConfigError::ValidationError(
+                            "Invalid rotation_naming_convention value"
+                                .to_string(),
+                        )
+                        })?
+            }
+            _ => {
+                return Err(ConfigError::ValidationError(format!(
+                    "Unknown configuration key: {}",
+                    key
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the configuration settings.
+    ///
+    /// Returns the first failure reported by `validate_all`.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match self.validate_all().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Validates the configuration settings, collecting every failure.
+    ///
+    /// An empty vector means the configuration is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::Config;
+    ///
+    /// let config = Config {
+    ///     version: String::new(),
+    ///     profile: String::new(),
+    ///     logging_destinations: Vec::new(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(config.validate_all().len(), 3);
+    /// ```
+    pub fn validate_all(&self) -> Vec<ConfigError> {
+        let invalid = |message: &str| {
+            ConfigError::ValidationError(message.to_string())
+        };
+        let mut errors = Vec::new();
+
+        if self.version.trim().is_empty() {
+            errors.push(invalid("Version cannot be empty"));
+        }
+        if self.profile.trim().is_empty() {
+            errors.push(invalid("Profile cannot be empty"));
+        }
+        if self.log_file_path.as_os_str().is_empty() {
+            errors.push(invalid("Log file path cannot be empty"));
+        }
+        if let Some(rotation) = &self.log_rotation {
+            match rotation {
+                LogRotation::Size(size) if size.get() == 0 => {
+                    errors.push(invalid(
+                        "Log rotation size must be greater than 0",
+                    ));
                 }
                 LogRotation::Time(time) if time.get() == 0 => {
-                    return Err(ConfigError::ValidationError(
-                        "Log rotation time must be greater than 0"
-                            .to_string(),
+                    errors.push(invalid(
+                        "Log rotation time must be greater than 0",
                     ));
                 }
                 LogRotation::Count(count) if *count == 0 => {
-                    return Err(ConfigError::ValidationError(
-                        "Log rotation count must be greater than 0"
-                            .to_string(),
+                    errors.push(invalid(
+                        "Log rotation count must be greater than 0",
                     ));
                 }
+                LogRotation::Cron(expression) => {
+                    if let Err(e) = parse_cron_schedule(expression) {
+                        errors.push(e);
+                    } else if rotation
+                        .next_trigger(Utc::now())
+                        .is_none()
+                    {
+                        errors.push(invalid(
+                            "Log rotation cron expression never triggers",
+                        ));
+                    }
+                }
                 _ => {}
             }
         }
         if self.log_format.trim().is_empty() {
-            return Err(ConfigError::ValidationError(
-                "Log format cannot be empty".to_string(),
-            ));
+            errors.push(invalid("Log format cannot be empty"));
+        } else if let Err(e) = self.resolve_format() {
+            errors.push(ConfigError::ValidationError(match e {
+                RlgError::UnsupportedFormat(reason) => format!(
+                    "Log format '{}' is not supported: {}",
+                    self.log_format, reason
+                ),
+                _ => format!(
+                    "Log format must be a format name, an alias or a template such as '%level - %message': '{}'",
+                    self.log_format
+                ),
+            }));
         }
         if self.logging_destinations.is_empty() {
-            return Err(ConfigError::ValidationError(
-                "At least one logging destination must be specified"
-                    .to_string(),
+            errors.push(invalid(
+                "At least one logging destination must be specified",
             ));
         }
         for destination in &self.logging_destinations {
-            if let LoggingDestination::Network(address) = destination {
-                self.validate_network_address(address)?;
+            if let Err(e) = self.validate_destination(destination) {
+                errors.push(e);
+            }
+        }
+        if let Some(fallback) = &self.fallback_destination {
+            if *fallback
+                == LoggingDestination::File(self.log_file_path.clone())
+            {
+                errors.push(invalid(
+                    "Fallback destination cannot be the log file",
+                ));
+            } else if let Err(e) = self.validate_destination(fallback) {
+                errors.push(ConfigError::ValidationError(format!(
+                    "Fallback destination: {}",
+                    e
+                )));
             }
         }
         for (key, value) in &self.env_vars {
             if key.trim().is_empty() {
-                return Err(ConfigError::ValidationError(
-                    "Environment variable key cannot be empty"
-                        .to_string(),
+                errors.push(invalid(
+                    "Environment variable key cannot be empty",
                 ));
             }
             if value.trim().is_empty() {
-                return Err(ConfigError::ValidationError(format!("Value for environment variable '{}' cannot be empty", key)));
+                errors.push(ConfigError::ValidationError(format!(
+                    "Value for environment variable '{}' cannot be empty",
+                    key
+                )));
             }
         }
-        if let LoggingDestination::File(path) =
-            &self.logging_destinations[0]
+        if self
+            .component_log_levels
+            .keys()
+            .any(|component| component.trim().is_empty())
         {
-            if let Some(parent_dir) = path.parent() {
-                fs::create_dir_all(parent_dir).map_err(|e| {
-                    ConfigError::ValidationError(format!(
-                        "Failed to create directory for log file: {}",
-                        e
-                    ))
-                })?;
+            errors.push(invalid(
+                "Component name for a log level override cannot be empty",
+            ));
+        }
+        if self.max_message_size == 0 {
+            errors.push(invalid(
+                "Max message size must be greater than 0",
+            ));
+        }
+        if self.log_rotation_max_files == 0 {
+            errors.push(invalid(
+                "Log rotation max files must be at least 1",
+            ));
+        }
+        if self.max_queue_depth == 0 {
+            errors.push(invalid(
+                "Max queue depth must be greater than 0",
+            ));
+        }
+        if matches!(self.structured_fields_separator, '=' | '\n' | '\r')
+        {
+            errors.push(invalid(
+                "Structured fields separator cannot be '=' or a line break",
+            ));
+        }
+        match (&self.log_archive_dir, self.max_log_age_days) {
+            (Some(_), None) | (None, Some(_)) => errors.push(invalid(
+                "Log archive directory and max log age days must be set together",
+            )),
+            (Some(dir), Some(_)) if dir.as_os_str().is_empty() => errors
+                .push(invalid("Log archive directory cannot be empty")),
+            (_, Some(0)) => errors.push(invalid(
+                "Max log age days must be greater than 0",
+            )),
+            _ => {}
+        }
+        for (alias, format) in &self.output_format_aliases {
+            if alias.trim().is_empty() {
+                errors.push(invalid("Format alias cannot be empty"));
+            } else if LogFormat::from_str(alias.trim()).is_ok() {
+                errors.push(ConfigError::ValidationError(format!(
+                    "Format alias '{}' for {} shadows a standard format",
+                    alias, format
+                )));
             }
-            OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(path)
-                .map_err(|e| {
+            if let Err(e) = Config::line_format(format.clone()) {
+                errors.push(ConfigError::ValidationError(format!(
+                    "Format alias '{}' is invalid: {}",
+                    alias, e
+                )));
+            }
+        }
+        for (level, color) in &self.log_level_color_map {
+            if !color.starts_with("\x1b[") || !color.ends_with('m') {
+                errors.push(ConfigError::ValidationError(format!(
+                    "Color for log level {} must be an ANSI escape code, not {:?}",
+                    level, color
+                )));
+            }
+        }
+        for pattern in &self.redact_patterns {
+            if let Err(e) = Regex::new(pattern) {
+                errors.push(ConfigError::ValidationError(format!(
+                    "Invalid redact pattern '{}': {}",
+                    pattern, e
+                )));
+            }
+        }
+        for (name, profile) in &self.profiles {
+            errors.extend(profile.validate_all().into_iter().map(
+                |e| {
                     ConfigError::ValidationError(format!(
-                        "Log file is not writable: {}",
-                        e
+                        "Profile '{}': {}",
+                        name, e
                     ))
-                })?;
+                },
+            ));
+        }
+        for destination in self.per_destination_format.keys() {
+            if !is_destination_key(destination) {
+                errors.push(ConfigError::ValidationError(format!(
+                    "Invalid destination '{}' in per_destination_format",
+                    destination
+                )));
+            }
         }
+        if let Some(LoggingDestination::File(path)) =
+            self.logging_destinations.first()
+        {
+            if let Err(e) = Self::check_log_file_writable(path) {
+                errors.push(e);
+            }
+        }
+        errors
+    }
+
+    /// Checks that the log file can be created and written to.
+    fn check_log_file_writable(path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|e| {
+                ConfigError::ValidationError(format!(
+                    "Failed to create directory for log file: {}",
+                    e
+                ))
+            })?;
+        }
+        // Appending keeps the existing entries of the log file.
+        OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| {
+                ConfigError::ValidationError(format!(
+                    "Log file is not writable: {}",
+                    e
+                ))
+            })?;
         Ok(())
     }
 
+    /// Validates the URL of an HTTP logging destination.
+    fn validate_http_url(url: &str) -> Result<(), ConfigError> {
+        let parsed = Url::parse(url).map_err(|e| {
+            ConfigError::InvalidUrl(format!("'{}': {}", url, e))
+        })?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(ConfigError::InvalidUrl(format!(
+                "'{}': scheme must be http or https",
+                url
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validates the address or URL of a logging destination.
+    fn validate_destination(
+        &self,
+        destination: &LoggingDestination,
+    ) -> Result<(), ConfigError> {
+        match destination {
+            #[allow(deprecated)]
+            LoggingDestination::Network(address) => {
+                self.validate_network_address(address)
+            }
+            LoggingDestination::Http { url, .. } => {
+                Self::validate_http_url(url)
+            }
+            LoggingDestination::UdpSyslog { addr }
+            | LoggingDestination::Tcp(addr)
+            | LoggingDestination::Udp(addr) => {
+                self.validate_network_address(&addr.to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Validates a network address.
     fn validate_network_address(
         &self,
@@ -574,7 +2184,13 @@ fn validate_network_address(
                     .to_string(),
             ));
         }
-        if address.parse::<SocketAddr>().is_ok() {
+        if let Ok(addr) = address.parse::<SocketAddr>() {
+            if addr.port() == 0 {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid network address '{}': port cannot be 0",
+                    address
+                )));
+            }
             return Ok(());
         }
         address
@@ -595,6 +2211,189 @@ fn validate_network_address(
         Ok(())
     }
 
+    /// Resolves `log_format` to the `LogFormat` entries are written in.
+    ///
+    /// A format name such as `json` or `GELF` resolves to that format.
+    /// Other names are looked up, case-insensitively, in
+    /// `output_format_aliases`, then in `Config::default_format_aliases`,
+    /// so that `j` resolves to `LogFormat::JSON`.
+    /// A printf-style template such as `%level - %message`, recognized by
+    /// its `%` placeholders, resolves to the default `LogFormat::CLF`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` if `log_format` is neither a
+    /// known format name, an alias nor a template, and
+    /// `RlgError::UnsupportedFormat` if it names a format that cannot be
+    /// written to log files, such as `LogFormat::GELF` or
+    /// `LogFormat::ApacheAccessLog`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, log_format::LogFormat};
+    ///
+    /// let mut config = Config::default();
+    /// assert_eq!(config.resolve_format().unwrap(), LogFormat::CLF);
+    /// config.log_format = "json".to_string();
+    /// assert_eq!(config.resolve_format().unwrap(), LogFormat::JSON);
+    /// config.log_format = "syslog".to_string();
+    /// assert_eq!(config.resolve_format().unwrap(), LogFormat::RFC5424);
+    /// config.log_format = "apache".to_string();
+    /// assert!(config.resolve_format().is_err());
+    /// config.log_format = "yaml".to_string();
+    /// assert!(config.resolve_format().is_err());
+    /// ```
+    pub fn resolve_format(&self) -> RlgResult<LogFormat> {
+        let format = self.log_format.trim();
+        let error = match LogFormat::from_str(format) {
+            Err(error) => error,
+            Ok(parsed) => return Self::line_format(parsed),
+        };
+        let lookup = |aliases: &HashMap<String, LogFormat>| {
+            aliases
+                .iter()
+                .find(|(alias, _)| {
+                    alias.trim().eq_ignore_ascii_case(format)
+                })
+                .map(|(_, aliased)| aliased.clone())
+        };
+        match lookup(&self.output_format_aliases)
+            .or_else(|| lookup(&Config::default_format_aliases()))
+        {
+            Some(aliased) => Self::line_format(aliased),
+            None if format.contains('%') => Ok(LogFormat::CLF),
+            None => Err(error),
+        }
+    }
+
+    /// Returns `format` if log entries can be written in it.
+    fn line_format(format: LogFormat) -> RlgResult<LogFormat> {
+        if format.is_line_format() {
+            Ok(format)
+        } else {
+            Err(RlgError::UnsupportedFormat(format!(
+                "{} cannot be written to log files",
+                format
+            )))
+        }
+    }
+
+    /// Returns the effective minimum log level for a component.
+    ///
+    /// A level configured for the component in `component_log_levels`
+    /// takes precedence over the global `log_level`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, log_level::LogLevel};
+    ///
+    /// let mut config = Config::default();
+    /// config
+    ///     .component_log_levels
+    ///     .insert("database".to_string(), LogLevel::DEBUG);
+    /// assert_eq!(config.effective_level_for("database"), LogLevel::DEBUG);
+    /// assert_eq!(config.effective_level_for("http"), LogLevel::INFO);
+    /// ```
+    pub fn effective_level_for(&self, component: &str) -> LogLevel {
+        self.component_log_levels
+            .get(component)
+            .copied()
+            .unwrap_or(self.log_level)
+    }
+
+    /// Returns the log format configured for `destination` in
+    /// `per_destination_format`, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::{Config, LoggingDestination};
+    /// use rlg::log_format::LogFormat;
+    ///
+    /// let mut config = Config::default();
+    /// config
+    ///     .per_destination_format
+    ///     .insert("stdout".to_string(), LogFormat::JSON);
+    /// assert_eq!(
+    ///     config.format_for(&LoggingDestination::Stdout),
+    ///     Some(LogFormat::JSON)
+    /// );
+    /// assert_eq!(config.format_for(&LoggingDestination::Stderr), None);
+    /// ```
+    pub fn format_for(
+        &self,
+        destination: &LoggingDestination,
+    ) -> Option<LogFormat> {
+        self.per_destination_format
+            .get(&destination.to_string())
+            .cloned()
+    }
+
+    /// Returns the default ANSI colors of the log levels, used for the
+    /// levels missing from `log_level_color_map`.
+    ///
+    /// Errors are red, warnings yellow, debug and trace entries cyan, and
+    /// the other levels white.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, log_level::LogLevel};
+    ///
+    /// let colors = Config::default_color_map();
+    /// assert_eq!(colors[&LogLevel::ERROR], "\x1b[31m");
+    /// assert_eq!(colors[&LogLevel::INFO], "\x1b[37m");
+    /// ```
+    pub fn default_color_map() -> HashMap<LogLevel, String> {
+        [
+            (LogLevel::ALL, "\x1b[37m"),
+            (LogLevel::NONE, "\x1b[37m"),
+            (LogLevel::DISABLED, "\x1b[37m"),
+            (LogLevel::DEBUG, "\x1b[36m"),
+            (LogLevel::TRACE, "\x1b[36m"),
+            (LogLevel::VERBOSE, "\x1b[37m"),
+            (LogLevel::INFO, "\x1b[37m"),
+            (LogLevel::WARN, "\x1b[33m"),
+            (LogLevel::ERROR, "\x1b[31m"),
+            (LogLevel::FATAL, "\x1b[31m"),
+            (LogLevel::CRITICAL, "\x1b[37m"),
+            (LogLevel::SECURITY, "\x1b[37m"),
+            (LogLevel::AUDIT, "\x1b[37m"),
+        ]
+        .into_iter()
+        .map(|(level, color)| (level, color.to_string()))
+        .collect()
+    }
+
+    /// Returns the default short-hand names of the log formats, used for
+    /// the names missing from `output_format_aliases`.
+    ///
+    /// Only formats that log entries can be written in have a default
+    /// alias.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{config::Config, log_format::LogFormat};
+    ///
+    /// let aliases = Config::default_format_aliases();
+    /// assert_eq!(aliases["j"], LogFormat::JSON);
+    /// assert_eq!(aliases["syslog"], LogFormat::RFC5424);
+    /// assert!(!aliases.contains_key("apache"));
+    /// ```
+    pub fn default_format_aliases() -> HashMap<String, LogFormat> {
+        [
+            ("c", LogFormat::CLF),
+            ("j", LogFormat::JSON),
+            ("syslog", LogFormat::RFC5424),
+        ]
+        .into_iter()
+        .map(|(alias, format)| (alias.to_string(), format))
+        .collect()
+    }
+
     /// Expands environment variables in the configuration values.
     pub fn expand_env_vars(&self) -> Config {
         let mut new_config = self.clone();
@@ -603,139 +2402,443 @@ pub fn expand_env_vars(&self) -> Config {
                 *value = env_value;
             }
         }
-        new_config
-    }
-
-    /// Hot-reloads configuration on file change.
-    #[allow(clippy::incompatible_msrv)]
-    pub async fn hot_reload_async(
-        config_path: &str,
-        config: Arc<RwLock<Config>>,
-    ) -> Result<mpsc::Sender<()>, ConfigError> {
-        let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
-        let (tx, mut rx) = mpsc::channel::<notify::Result<Event>>(100);
-
-        let mut watcher = notify::recommended_watcher(move |res| {
-            let _ = tx.blocking_send(res);
-        })?;
-        watcher.watch(
-            Path::new(config_path),
-            RecursiveMode::NonRecursive,
-        )?;
-
-        let config_path = config_path.to_string();
-        tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    Some(res) = rx.recv() => {
-                        match res {
-                            Ok(Event { kind, .. }) => match kind {
-                                EventKind::Modify(_) => {
-                                    info!("Configuration file changed, reloading...");
-                                    match Config::load_async(Some(&config_path)).await {
-                                        Ok(new_config) => {
-                                            let mut config_write = config.write();
-                                            *config_write = new_config.read().clone();
-                                            info!("Configuration reloaded successfully");
-                                        }
-                                        Err(e) => error!("Failed to reload configuration: {}", e),
-                                    }
-                                }
-                                EventKind::Create(_) => info!("Configuration file created"),
-                                EventKind::Remove(_) => warn!("Configuration file removed"),
-                                _ => {}
-                            },
-                            Err(e) => error!("Watch error: {:?}", e),
-                        }
-                    }
-                    _ = stop_rx.recv() => {
-                        info!("Stopping configuration hot reload");
-                        break;
-                    }
-                }
-            }
-        });
-        Ok(stop_tx)
-    }
-
-    /// Compares two configurations and returns the differences.
-    pub fn diff(
-        config1: &Config,
-        config2: &Config,
-    ) -> HashMap<String, String> {
-        let mut differences = HashMap::new();
-        if config1.version != config2.version {
+        new_config
+    }
+
+    /// Hot-reloads configuration on file change.
+    #[allow(clippy::incompatible_msrv)]
+    pub async fn hot_reload_async(
+        config_path: &str,
+        config: Arc<RwLock<Config>>,
+    ) -> Result<mpsc::Sender<()>, ConfigError> {
+        let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
+        let (tx, mut rx) = mpsc::channel::<notify::Result<Event>>(100);
+
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = tx.blocking_send(res);
+        })?;
+        watcher.watch(
+            Path::new(config_path),
+            RecursiveMode::NonRecursive,
+        )?;
+
+        let config_path = config_path.to_string();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    Some(res) = rx.recv() => {
+                        match res {
+                            Ok(Event { kind, .. }) => match kind {
+                                EventKind::Modify(_) => {
+                                    info!("Configuration file changed, reloading...");
+                                    match Config::load_async(Some(&config_path), None).await {
+                                        Ok(new_config) => {
+                                            let mut config_write = config.write();
+                                            *config_write = new_config.read().clone();
+                                            info!("Configuration reloaded successfully");
+                                        }
+                                        Err(e) => error!("Failed to reload configuration: {}", e),
+                                    }
+                                }
+                                EventKind::Create(_) => info!("Configuration file created"),
+                                EventKind::Remove(_) => warn!("Configuration file removed"),
+                                _ => {}
+                            },
+                            Err(e) => error!("Watch error: {:?}", e),
+                        }
+                    }
+                    _ = stop_rx.recv() => {
+                        info!("Stopping configuration hot reload");
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(stop_tx)
+    }
+
+    /// Compares two configurations and returns the differences.
+    pub fn diff(
+        config1: &Config,
+        config2: &Config,
+    ) -> HashMap<String, String> {
+        let mut differences = HashMap::new();
+        if config1.version != config2.version {
+            differences.insert(
+                "version".to_string(),
+                format!("{} -> {}", config1.version, config2.version),
+            );
+        }
+        if config1.profile != config2.profile {
+            differences.insert(
+                "profile".to_string(),
+                format!("{} -> {}", config1.profile, config2.profile),
+            );
+        }
+        if config1.log_file_path != config2.log_file_path {
+            differences.insert(
+                "log_file_path".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.log_file_path.display(),
+                    config2.log_file_path.display()
+                ),
+            );
+        }
+        if config1.log_level != config2.log_level {
+            differences.insert(
+                "log_level".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.log_level, config2.log_level
+                ),
+            );
+        }
+        if config1.log_rotation != config2.log_rotation {
+            differences.insert(
+                "log_rotation".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.log_rotation, config2.log_rotation
+                ),
+            );
+        }
+        if config1.log_format != config2.log_format {
+            differences.insert(
+                "log_format".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.log_format, config2.log_format
+                ),
+            );
+        }
+        if config1.logging_destinations != config2.logging_destinations
+        {
+            differences.insert(
+                "logging_destinations".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.logging_destinations,
+                    config2.logging_destinations
+                ),
+            );
+        }
+        if config1.env_vars != config2.env_vars {
+            differences.insert(
+                "env_vars".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.env_vars, config2.env_vars
+                ),
+            );
+        }
+        if config1.component_log_levels != config2.component_log_levels
+        {
+            differences.insert(
+                "component_log_levels".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.component_log_levels,
+                    config2.component_log_levels
+                ),
+            );
+        }
+        if config1.compress_rotated_files
+            != config2.compress_rotated_files
+        {
+            differences.insert(
+                "compress_rotated_files".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.compress_rotated_files,
+                    config2.compress_rotated_files
+                ),
+            );
+        }
+        if config1.redact_patterns != config2.redact_patterns {
+            differences.insert(
+                "redact_patterns".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.redact_patterns, config2.redact_patterns
+                ),
+            );
+        }
+        if config1.per_destination_format
+            != config2.per_destination_format
+        {
+            differences.insert(
+                "per_destination_format".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.per_destination_format,
+                    config2.per_destination_format
+                ),
+            );
+        }
+        if serde_json::to_value(&config1.profiles).ok()
+            != serde_json::to_value(&config2.profiles).ok()
+        {
+            let mut names1: Vec<&String> =
+                config1.profiles.keys().collect();
+            let mut names2: Vec<&String> =
+                config2.profiles.keys().collect();
+            names1.sort();
+            names2.sort();
+            differences.insert(
+                "profiles".to_string(),
+                format!("{:?} -> {:?}", names1, names2),
+            );
+        }
+        if config1.max_message_size != config2.max_message_size {
+            differences.insert(
+                "max_message_size".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.max_message_size, config2.max_message_size
+                ),
+            );
+        }
+        if config1.fallback_destination != config2.fallback_destination
+        {
+            differences.insert(
+                "fallback_destination".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.fallback_destination,
+                    config2.fallback_destination
+                ),
+            );
+        }
+        if config1.anonymize_session_ids
+            != config2.anonymize_session_ids
+        {
+            differences.insert(
+                "anonymize_session_ids".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.anonymize_session_ids,
+                    config2.anonymize_session_ids
+                ),
+            );
+        }
+        if config1.log_rotation_max_files
+            != config2.log_rotation_max_files
+        {
+            differences.insert(
+                "log_rotation_max_files".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.log_rotation_max_files,
+                    config2.log_rotation_max_files
+                ),
+            );
+        }
+        if config1.include_process_context
+            != config2.include_process_context
+        {
+            differences.insert(
+                "include_process_context".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.include_process_context,
+                    config2.include_process_context
+                ),
+            );
+        }
+        if config1.include_hostname != config2.include_hostname {
             differences.insert(
-                "version".to_string(),
-                format!("{} -> {}", config1.version, config2.version),
+                "include_hostname".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.include_hostname, config2.include_hostname
+                ),
             );
         }
-        if config1.profile != config2.profile {
+        if config1.async_flush_interval_ms
+            != config2.async_flush_interval_ms
+        {
             differences.insert(
-                "profile".to_string(),
-                format!("{} -> {}", config1.profile, config2.profile),
+                "async_flush_interval_ms".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.async_flush_interval_ms,
+                    config2.async_flush_interval_ms
+                ),
             );
         }
-        if config1.log_file_path != config2.log_file_path {
+        if config1.colorize_stdout != config2.colorize_stdout {
             differences.insert(
-                "log_file_path".to_string(),
+                "colorize_stdout".to_string(),
                 format!(
                     "{} -> {}",
-                    config1.log_file_path.display(),
-                    config2.log_file_path.display()
+                    config1.colorize_stdout, config2.colorize_stdout
                 ),
             );
         }
-        if config1.log_level != config2.log_level {
+        if config1.max_queue_depth != config2.max_queue_depth {
             differences.insert(
-                "log_level".to_string(),
+                "max_queue_depth".to_string(),
+                format!(
+                    "{} -> {}",
+                    config1.max_queue_depth, config2.max_queue_depth
+                ),
+            );
+        }
+        if config1.structured_fields_separator
+            != config2.structured_fields_separator
+        {
+            differences.insert(
+                "structured_fields_separator".to_string(),
                 format!(
                     "{:?} -> {:?}",
-                    config1.log_level, config2.log_level
+                    config1.structured_fields_separator,
+                    config2.structured_fields_separator
                 ),
             );
         }
-        if config1.log_rotation != config2.log_rotation {
+        if config1.log_level_color_map != config2.log_level_color_map {
             differences.insert(
-                "log_rotation".to_string(),
+                "log_level_color_map".to_string(),
                 format!(
                     "{:?} -> {:?}",
-                    config1.log_rotation, config2.log_rotation
+                    config1.log_level_color_map,
+                    config2.log_level_color_map
                 ),
             );
         }
-        if config1.log_format != config2.log_format {
+        if config1.log_archive_dir != config2.log_archive_dir {
             differences.insert(
-                "log_format".to_string(),
+                "log_archive_dir".to_string(),
                 format!(
-                    "{} -> {}",
-                    config1.log_format, config2.log_format
+                    "{:?} -> {:?}",
+                    config1.log_archive_dir, config2.log_archive_dir
                 ),
             );
         }
-        if config1.logging_destinations != config2.logging_destinations
+        if config1.max_log_age_days != config2.max_log_age_days {
+            differences.insert(
+                "max_log_age_days".to_string(),
+                format!(
+                    "{:?} -> {:?}",
+                    config1.max_log_age_days, config2.max_log_age_days
+                ),
+            );
+        }
+        if config1.output_format_aliases
+            != config2.output_format_aliases
         {
             differences.insert(
-                "logging_destinations".to_string(),
+                "output_format_aliases".to_string(),
                 format!(
                     "{:?} -> {:?}",
-                    config1.logging_destinations,
-                    config2.logging_destinations
+                    config1.output_format_aliases,
+                    config2.output_format_aliases
                 ),
             );
         }
-        if config1.env_vars != config2.env_vars {
+        if config1.rotation_naming_convention
+            != config2.rotation_naming_convention
+        {
             differences.insert(
-                "env_vars".to_string(),
+                "rotation_naming_convention".to_string(),
                 format!(
                     "{:?} -> {:?}",
-                    config1.env_vars, config2.env_vars
+                    config1.rotation_naming_convention,
+                    config2.rotation_naming_convention
                 ),
             );
         }
         differences
     }
 
+    /// Compares a configuration with its upgraded version and reports
+    /// what an upgrade from `old` to `new` involves.
+    ///
+    /// The report lists every changed field as found by `diff`, warns
+    /// about deprecated settings and unsupported versions in `new`, and
+    /// reports as errors the changes that cannot be applied, such as
+    /// switching to a cron rotation schedule that does not parse, along
+    /// with any other validation failure of `new`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::{Config, LogRotation};
+    ///
+    /// let old = Config::default();
+    /// let new = Config {
+    ///     log_rotation: Some(LogRotation::Cron("every day".to_string())),
+    ///     ..Config::default()
+    /// };
+    /// let report = Config::diff_and_migrate(&old, &new);
+    /// assert_eq!(report.changes.len(), 1);
+    /// assert!(report.errors[0].starts_with("log_rotation:"));
+    /// ```
+    pub fn diff_and_migrate(
+        old: &Config,
+        new: &Config,
+    ) -> MigrationReport {
+        let mut report = MigrationReport::default();
+
+        let mut differences: Vec<(String, String)> =
+            Config::diff(old, new).into_iter().collect();
+        differences.sort();
+        report.changes = differences
+            .into_iter()
+            .map(|(key, change)| format!("{}: {}", key, change))
+            .collect();
+
+        if new.version != CURRENT_CONFIG_VERSION {
+            report.warnings.push(format!(
+                "version: {} is not supported by this release, which expects {}",
+                new.version, CURRENT_CONFIG_VERSION
+            ));
+        }
+        #[allow(deprecated)]
+        for destination in new
+            .logging_destinations
+            .iter()
+            .chain(new.fallback_destination.iter())
+        {
+            if let LoggingDestination::Network(_) = destination {
+                report.warnings.push(format!(
+                    "logging_destinations: {} uses the deprecated Network destination; use Tcp or Udp instead",
+                    destination
+                ));
+            }
+        }
+        if old.log_rotation.is_some() && new.log_rotation.is_none() {
+            report.warnings.push(
+                "log_rotation: log rotation is disabled".to_string(),
+            );
+        }
+
+        let mut validated = new.clone();
+        if let Some(LogRotation::Cron(expression)) = &new.log_rotation {
+            if old.log_rotation != new.log_rotation {
+                if let Err(e) = parse_cron_schedule(expression) {
+                    let from = old
+                        .log_rotation
+                        .as_ref()
+                        .map_or("no rotation".to_string(), |r| {
+                            r.to_string()
+                        });
+                    report.errors.push(format!(
+                        "log_rotation: cannot switch from {} to cron: {}",
+                        from, e
+                    ));
+                    // Reported above with the rotation it replaces.
+                    validated.log_rotation = None;
+                }
+            }
+        }
+        report.errors.extend(
+            validated.validate_all().iter().map(ToString::to_string),
+        );
+        report
+    }
+
     /// Merges another configuration into the current configuration.
     pub fn merge(&self, other: &Config) -> Config {
         Config {
@@ -743,7 +2846,7 @@ pub fn merge(&self, other: &Config) -> Config {
             profile: other.profile.clone(),
             log_file_path: other.log_file_path.clone(),
             log_level: other.log_level,
-            log_rotation: other.log_rotation,
+            log_rotation: other.log_rotation.clone(),
             log_format: other.log_format.clone(),
             logging_destinations: other.logging_destinations.clone(),
             env_vars: self
@@ -752,10 +2855,465 @@ pub fn merge(&self, other: &Config) -> Config {
                 .chain(other.env_vars.iter())
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect(),
+            component_log_levels: self
+                .component_log_levels
+                .iter()
+                .chain(other.component_log_levels.iter())
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            compress_rotated_files: other.compress_rotated_files,
+            redact_patterns: other.redact_patterns.clone(),
+            per_destination_format: self
+                .per_destination_format
+                .iter()
+                .chain(other.per_destination_format.iter())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            profiles: self
+                .profiles
+                .iter()
+                .chain(other.profiles.iter())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            max_message_size: other.max_message_size,
+            fallback_destination: other.fallback_destination.clone(),
+            anonymize_session_ids: other.anonymize_session_ids,
+            log_rotation_max_files: other.log_rotation_max_files,
+            include_process_context: other.include_process_context,
+            include_hostname: other.include_hostname,
+            async_flush_interval_ms: other.async_flush_interval_ms,
+            colorize_stdout: other.colorize_stdout,
+            max_queue_depth: other.max_queue_depth,
+            structured_fields_separator: other
+                .structured_fields_separator,
+            log_level_color_map: self
+                .log_level_color_map
+                .iter()
+                .chain(other.log_level_color_map.iter())
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            log_archive_dir: other.log_archive_dir.clone(),
+            max_log_age_days: other.max_log_age_days,
+            output_format_aliases: self
+                .output_format_aliases
+                .iter()
+                .chain(other.output_format_aliases.iter())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            rotation_naming_convention: other
+                .rotation_naming_convention,
+        }
+    }
+
+    /// Merges two configurations with explicit precedence.
+    ///
+    /// - `MergePriority::Overlay` behaves like `base.merge(overlay)`.
+    /// - `MergePriority::Base` keeps every value of `base` that differs
+    ///   from `Config::default()`, taking the others from `overlay`.
+    /// - `MergePriority::Newer` lets the configuration with the higher
+    ///   `version` override the other one, as `Overlay` does. Versions
+    ///   are compared numerically, component by component; on a tie,
+    ///   `overlay` wins.
+    ///
+    /// Keys present in both `env_vars`, `component_log_levels`,
+    /// `per_destination_format`, `profiles`, `log_level_color_map` or
+    /// `output_format_aliases` take
+    /// the value of the winning configuration.
+    ///
+    /// `strategies` chooses how `log_rotation`, `logging_destinations`
+    /// and `env_vars` are combined, keyed by field name. Fields without a
+    /// strategy keep the behavior of `merge`: `env_vars` are appended,
+    /// the other two are replaced. With `FieldMergeStrategy::Append`,
+    /// destinations of both configurations are kept, without duplicates,
+    /// and a missing `log_rotation` falls back to the other
+    /// configuration's.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::{Config, FieldMergeStrategy, MergePriority};
+    /// use rlg::{LogLevel, LoggingDestination};
+    /// use std::collections::HashMap;
+    ///
+    /// let base = Config {
+    ///     log_level: LogLevel::WARN,
+    ///     ..Default::default()
+    /// };
+    /// let overlay = Config {
+    ///     log_level: LogLevel::DEBUG,
+    ///     logging_destinations: vec![LoggingDestination::Stdout],
+    ///     ..Default::default()
+    /// };
+    /// let strategies = HashMap::from([(
+    ///     "logging_destinations".to_string(),
+    ///     FieldMergeStrategy::Append,
+    /// )]);
+    ///
+    /// let merged = Config::merge_priority(&base, &overlay, MergePriority::Base, &strategies);
+    /// assert_eq!(merged.log_level, LogLevel::WARN);
+    /// assert_eq!(merged.logging_destinations.len(), 2);
+    /// ```
+    pub fn merge_priority(
+        base: &Config,
+        overlay: &Config,
+        priority: MergePriority,
+        strategies: &HashMap<String, FieldMergeStrategy>,
+    ) -> Config {
+        // `Newer` is `Overlay` with the newer configuration on top.
+        let (base, overlay) = match priority {
+            MergePriority::Newer
+                if compare_versions(
+                    &base.version,
+                    &overlay.version,
+                ) == Ordering::Greater =>
+            {
+                (overlay, base)
+            }
+            _ => (base, overlay),
+        };
+        let base_wins = priority == MergePriority::Base;
+        let defaults = Config::default();
+        let strategy = |field: &str, default: FieldMergeStrategy| {
+            strategies.get(field).copied().unwrap_or(default)
+        };
+        macro_rules! pick {
+            ($field:ident) => {
+                pick_field(
+                    &base.$field,
+                    &overlay.$field,
+                    &defaults.$field,
+                    base_wins,
+                )
+            };
+        }
+
+        let log_rotation =
+            match strategy("log_rotation", FieldMergeStrategy::Replace)
+            {
+                FieldMergeStrategy::Replace => pick!(log_rotation),
+                FieldMergeStrategy::Append => {
+                    let (winner, other) = if base_wins {
+                        (&base.log_rotation, &overlay.log_rotation)
+                    } else {
+                        (&overlay.log_rotation, &base.log_rotation)
+                    };
+                    winner.clone().or_else(|| other.clone())
+                }
+            };
+        let logging_destinations = match strategy(
+            "logging_destinations",
+            FieldMergeStrategy::Replace,
+        ) {
+            FieldMergeStrategy::Replace => pick!(logging_destinations),
+            FieldMergeStrategy::Append => {
+                let mut destinations =
+                    base.logging_destinations.clone();
+                for destination in &overlay.logging_destinations {
+                    if !destinations.contains(destination) {
+                        destinations.push(destination.clone());
+                    }
+                }
+                destinations
+            }
+        };
+        let env_vars =
+            match strategy("env_vars", FieldMergeStrategy::Append) {
+                FieldMergeStrategy::Replace => pick!(env_vars),
+                FieldMergeStrategy::Append => merge_maps(
+                    &base.env_vars,
+                    &overlay.env_vars,
+                    base_wins,
+                ),
+            };
+
+        Config {
+            version: pick!(version),
+            profile: pick!(profile),
+            log_file_path: pick!(log_file_path),
+            log_level: pick!(log_level),
+            log_rotation,
+            log_format: pick!(log_format),
+            logging_destinations,
+            env_vars,
+            component_log_levels: merge_maps(
+                &base.component_log_levels,
+                &overlay.component_log_levels,
+                base_wins,
+            ),
+            compress_rotated_files: pick!(compress_rotated_files),
+            redact_patterns: pick!(redact_patterns),
+            per_destination_format: merge_maps(
+                &base.per_destination_format,
+                &overlay.per_destination_format,
+                base_wins,
+            ),
+            profiles: merge_maps(
+                &base.profiles,
+                &overlay.profiles,
+                base_wins,
+            ),
+            max_message_size: pick!(max_message_size),
+            fallback_destination: pick!(fallback_destination),
+            anonymize_session_ids: pick!(anonymize_session_ids),
+            log_rotation_max_files: pick!(log_rotation_max_files),
+            include_process_context: pick!(include_process_context),
+            include_hostname: pick!(include_hostname),
+            async_flush_interval_ms: pick!(async_flush_interval_ms),
+            colorize_stdout: pick!(colorize_stdout),
+            max_queue_depth: pick!(max_queue_depth),
+            structured_fields_separator: pick!(
+                structured_fields_separator
+            ),
+            log_level_color_map: merge_maps(
+                &base.log_level_color_map,
+                &overlay.log_level_color_map,
+                base_wins,
+            ),
+            log_archive_dir: pick!(log_archive_dir),
+            max_log_age_days: pick!(max_log_age_days),
+            output_format_aliases: merge_maps(
+                &base.output_format_aliases,
+                &overlay.output_format_aliases,
+                base_wins,
+            ),
+            rotation_naming_convention: pick!(
+                rotation_naming_convention
+            ),
+        }
+    }
+
+    /// Starts a transaction applying several changes to a copy of this
+    /// configuration.
+    ///
+    /// Changes made with `ConfigTransaction::set` only affect the copy,
+    /// so a failure midway leaves this configuration untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::config::Config;
+    /// use rlg::LogLevel;
+    ///
+    /// let mut config = Config::default();
+    /// let mut transaction = config.begin_transaction();
+    /// transaction
+    ///     .set("log_level", "DEBUG")?
+    ///     .set("max_message_size", 1024)?;
+    /// config = transaction.commit()?;
+    /// assert_eq!(config.log_level, LogLevel::DEBUG);
+    /// assert_eq!(config.max_message_size, 1024);
+    /// # Ok::<(), rlg::config::ConfigError>(())
+    /// ```
+    pub fn begin_transaction(&self) -> ConfigTransaction {
+        ConfigTransaction {
+            snapshot: self.clone(),
+            draft: self.clone(),
+        }
+    }
+
+    /// Creates a logger that buffers entries and writes them to
+    /// `log_file_path` every `async_flush_interval_ms` milliseconds.
+    ///
+    /// See `BufferedLogger` for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `RlgError::IoError` if the log file cannot be opened,
+    /// or an `RlgError::Custom` if `async_flush_interval_ms` is not zero
+    /// and this is called outside a Tokio runtime.
+    pub fn create_buffered_logger(&self) -> RlgResult<BufferedLogger> {
+        BufferedLogger::new(self)
+    }
+}
+
+/// A set of configuration changes applied together.
+///
+/// Created by `Config::begin_transaction`. Changes are applied to a draft
+/// copy of the configuration, which `commit` validates and returns as a
+/// whole; `rollback` returns the configuration as it was when the
+/// transaction started.
+#[derive(Clone, Debug)]
+pub struct ConfigTransaction {
+    snapshot: Config,
+    draft: Config,
+}
+
+impl ConfigTransaction {
+    /// Sets a value in the draft configuration, as with `Config::set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConfigError` if the key is unknown or the value is
+    /// invalid for it. The draft is left unchanged.
+    pub fn set<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: T,
+    ) -> Result<&mut Self, ConfigError> {
+        self.draft.set(key, value)?;
+        Ok(self)
+    }
+
+    /// Validates the draft configuration and returns it.
+    ///
+    /// The transaction is kept on failure, so that it can still be
+    /// rolled back.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by `Config::validate` for the
+    /// draft.
+    pub fn commit(&self) -> Result<Config, ConfigError> {
+        self.draft.validate()?;
+        Ok(self.draft.clone())
+    }
+
+    /// Discards the changes and returns the configuration as it was when
+    /// the transaction started.
+    pub fn rollback(self) -> Config {
+        self.snapshot
+    }
+}
+
+/// How rotated log files are named by `utils::rotate_log_file`.
+///
+/// The examples are for a log file named `RLG.log`.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+pub enum RotationNamingConvention {
+    /// Numbered files, `RLG.log.1` being the newest.
+    #[default]
+    Numeric,
+    /// Files named after the rotation date, such as `RLG.2024-01-15.log`.
+    DateStamped,
+    /// Files named after the rotation time in milliseconds since the Unix
+    /// epoch, such as `RLG.1705312800000.log`.
+    TimestampMillis,
+}
+
+/// A source of configuration for `Config::load_layered_async`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ConfigSource {
+    /// The values of `Config::default()`.
+    Defaults,
+    /// A configuration file, in the format given by its extension.
+    File(PathBuf),
+    /// The `RLG_*` environment variables, as read by
+    /// `Config::load_from_env`.
+    EnvVars,
+    /// A TOML configuration string.
+    Str(&'static str),
+}
+
+/// Which configuration takes precedence in `Config::merge_priority`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MergePriority {
+    /// Values of the base configuration that differ from the defaults
+    /// win.
+    Base,
+    /// Values of the overlay configuration win, as with `Config::merge`.
+    Overlay,
+    /// Values of the configuration with the higher `version` win.
+    Newer,
+}
+
+/// How a collection field is combined by `Config::merge_priority`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FieldMergeStrategy {
+    /// The winning configuration's value replaces the other one.
+    Replace,
+    /// The values of both configurations are kept.
+    Append,
+}
+
+/// Returns the overlay value, or the base value if `base_wins` and it
+/// differs from the default.
+fn pick_field<T: Clone + PartialEq>(
+    base: &T,
+    overlay: &T,
+    default: &T,
+    base_wins: bool,
+) -> T {
+    if base_wins && base != default {
+        base.clone()
+    } else {
+        overlay.clone()
+    }
+}
+
+/// Returns the union of two maps, taking the value of the winning map for
+/// keys present in both.
+fn merge_maps<K: Clone + Eq + Hash, V: Clone>(
+    base: &HashMap<K, V>,
+    overlay: &HashMap<K, V>,
+    base_wins: bool,
+) -> HashMap<K, V> {
+    let (first, second) = if base_wins {
+        (overlay, base)
+    } else {
+        (base, overlay)
+    };
+    first
+        .iter()
+        .chain(second)
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// Compares two configuration versions component by component, numerically
+/// where both components are numbers.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('.');
+    let mut b_parts = b.split('.');
+    loop {
+        match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => {
+                let ordering =
+                    match (a.parse::<u64>(), b.parse::<u64>()) {
+                        (Ok(a), Ok(b)) => a.cmp(&b),
+                        _ => a.cmp(b),
+                    };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
         }
     }
 }
 
+/// What upgrading from one configuration to another involves, as
+/// reported by `Config::diff_and_migrate`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MigrationReport {
+    /// The changed fields, as `"<field>: <old> -> <new>"`.
+    pub changes: Vec<String>,
+    /// Settings of the new configuration that still work but need
+    /// attention, such as deprecated destinations.
+    pub warnings: Vec<String>,
+    /// Changes that cannot be applied, and other reasons the new
+    /// configuration is invalid.
+    pub errors: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Returns `true` if the migration has no errors.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
 impl TryFrom<env::Vars> for Config {
     type Error = ConfigError;
 
@@ -765,6 +3323,31 @@ fn try_from(vars: env::Vars) -> Result<Self, Self::Error> {
     }
 }
 
+impl fmt::Display for LoggingDestination {
+    /// Formats the destination as used for `per_destination_format` keys.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoggingDestination::File(path) => {
+                write!(f, "file:{}", path.display())
+            }
+            LoggingDestination::Stdout => write!(f, "stdout"),
+            LoggingDestination::Stderr => write!(f, "stderr"),
+            #[allow(deprecated)]
+            LoggingDestination::Network(address) => {
+                write!(f, "network:{}", address)
+            }
+            LoggingDestination::Http { url, .. } => {
+                write!(f, "http:{}", url)
+            }
+            LoggingDestination::UdpSyslog { addr } => {
+                write!(f, "udp-syslog:{}", addr)
+            }
+            LoggingDestination::Tcp(addr) => write!(f, "tcp:{}", addr),
+            LoggingDestination::Udp(addr) => write!(f, "udp:{}", addr),
+        }
+    }
+}
+
 impl fmt::Display for LogRotation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -778,6 +3361,9 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             LogRotation::Count(count) => {
                 write!(f, "Count: {} logs", count)
             }
+            LogRotation::Cron(expression) => {
+                write!(f, "cron:{}", expression)
+            }
         }
     }
 }
@@ -0,0 +1,178 @@
+// context.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Ambient log context for automatic session ID and component propagation.
+//!
+//! A `LogContext` set for a task is picked up by the logging macros such as
+//! `macro_info_log!`, so every entry emitted while handling a request shares
+//! the request's session ID without passing it through every function.
+//!
+//! Inside `with_log_context`, the context is local to the async task and
+//! follows it across `.await` points and worker threads. Outside of it, the
+//! context is stored per thread, which suits synchronous code. Async code
+//! running on a Tokio runtime must use `with_log_context`: a thread's
+//! context would be shared by every task the thread runs, so it is ignored
+//! inside a runtime.
+
+use std::{cell::RefCell, future::Future};
+use tokio::runtime::Handle;
+
+tokio::task_local! {
+    static TASK_CONTEXT: RefCell<Option<LogContext>>;
+}
+
+thread_local! {
+    static THREAD_CONTEXT: RefCell<Option<LogContext>> = RefCell::new(None);
+}
+
+/// Session and component information shared by related log entries.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct LogContext {
+    /// Session ID given to log entries created in this context.
+    pub session_id: String,
+    /// Component given to log entries that do not name one.
+    pub component: String,
+}
+
+impl LogContext {
+    /// Creates a new `LogContext`.
+    pub fn new(session_id: &str, component: &str) -> Self {
+        LogContext {
+            session_id: session_id.to_string(),
+            component: component.to_string(),
+        }
+    }
+}
+
+/// Runs `future` with its own task-local log context.
+///
+/// The context starts as `context` and can be changed inside the future
+/// with `set_log_context` and `clear_log_context` without affecting other
+/// tasks.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::context::{get_log_context, with_log_context, LogContext};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let context = LogContext::new("request-42", "http");
+/// with_log_context(context.clone(), async move {
+///     assert_eq!(get_log_context(), Some(context));
+/// })
+/// .await;
+/// # });
+/// ```
+pub async fn with_log_context<F: Future>(
+    context: LogContext,
+    future: F,
+) -> F::Output {
+    TASK_CONTEXT
+        .scope(RefCell::new(Some(context)), future)
+        .await
+}
+
+/// Sets the log context for the current task, or the current thread when
+/// called outside `with_log_context`.
+///
+/// # Panics
+///
+/// In debug builds, panics when called outside `with_log_context` but
+/// inside a Tokio runtime, as the context of the thread would leak into
+/// the other tasks it runs. Release builds ignore the context instead.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::context::{clear_log_context, set_log_context, LogContext};
+/// use rlg::macro_info_log;
+///
+/// set_log_context(LogContext::new("session-1", "auth"));
+/// let log = macro_info_log!("2024-08-29T12:00:00Z", "User login");
+/// assert_eq!(log.session_id, "session-1");
+/// assert_eq!(log.component, "auth");
+/// clear_log_context();
+/// ```
+pub fn set_log_context(context: LogContext) {
+    replace_log_context(Some(context));
+}
+
+/// Returns the log context of the current task or thread, if any.
+///
+/// Outside `with_log_context` but inside a Tokio runtime, this is always
+/// `None`: a context set on the thread before entering the runtime is not
+/// shared with its tasks.
+pub fn get_log_context() -> Option<LogContext> {
+    TASK_CONTEXT
+        .try_with(|cell| cell.borrow().clone())
+        .unwrap_or_else(|_| match Handle::try_current() {
+            Ok(_) => None,
+            Err(_) => THREAD_CONTEXT.with(|cell| cell.borrow().clone()),
+        })
+}
+
+/// Removes the log context of the current task or thread.
+///
+/// # Panics
+///
+/// Like `set_log_context`, panics in debug builds when called outside
+/// `with_log_context` but inside a Tokio runtime.
+pub fn clear_log_context() {
+    replace_log_context(None);
+}
+
+/// Replaces the current log context, returning the previous one.
+fn replace_log_context(
+    context: Option<LogContext>,
+) -> Option<LogContext> {
+    match TASK_CONTEXT.try_with(|cell| cell.replace(context.clone())) {
+        Ok(previous) => previous,
+        // Tasks of a runtime share its threads, and so their contexts.
+        Err(_) if Handle::try_current().is_ok() => {
+            if cfg!(debug_assertions) {
+                panic!("The log context of a task running on a Tokio runtime can only be changed within with_log_context");
+            }
+            None
+        }
+        Err(_) => THREAD_CONTEXT.with(|cell| cell.replace(context)),
+    }
+}
+
+/// Returns the session ID of the current log context, or a random one
+/// (see `random_session_id`).
+///
+/// Used by the logging macros.
+#[doc(hidden)]
+pub fn session_id_or_random() -> String {
+    get_log_context()
+        .map(|context| context.session_id)
+        .unwrap_or_else(random_session_id)
+}
+
+/// Returns a new random session ID: a UUID v4 with the
+/// `uuid_session_ids` feature, a number below 1,000,000,000 otherwise.
+#[cfg(feature = "uuid_session_ids")]
+fn random_session_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Returns a new random session ID: a UUID v4 with the
+/// `uuid_session_ids` feature, a number below 1,000,000,000 otherwise.
+#[cfg(not(feature = "uuid_session_ids"))]
+fn random_session_id() -> String {
+    vrd::random::Random::default()
+        .int(0, 1_000_000_000)
+        .to_string()
+}
+
+/// Returns the component of the current log context, or an empty string.
+///
+/// Used by the logging macros.
+#[doc(hidden)]
+pub fn component_or_default() -> String {
+    get_log_context()
+        .map(|context| context.component)
+        .unwrap_or_default()
+}
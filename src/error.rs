@@ -57,6 +57,39 @@ impl RlgError {
     pub fn custom<T: fmt::Display>(msg: T) -> Self {
         RlgError::Custom(msg.to_string())
     }
+
+    /// Returns whether the error is transient, so that retrying the
+    /// operation may succeed.
+    ///
+    /// Network errors and I/O errors caused by a timeout, a reset,
+    /// aborted or broken connection, or an operation that would block are
+    /// transient. Every other error, such as an invalid format or
+    /// configuration, is permanent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::error::RlgError;
+    /// use std::io;
+    ///
+    /// let timeout = RlgError::from(io::Error::from(io::ErrorKind::TimedOut));
+    /// assert!(timeout.is_transient());
+    /// assert!(!RlgError::custom("invalid entry").is_transient());
+    /// ```
+    pub fn is_transient(&self) -> bool {
+        match self {
+            RlgError::IoError(e) => matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::WouldBlock
+            ),
+            RlgError::NetworkError(_) => true,
+            _ => false,
+        }
+    }
 }
 
 /// Type alias for a Result with RlgError as the error type.
@@ -82,6 +115,41 @@ fn test_custom_error() {
         assert_eq!(err.to_string(), "Custom error message");
     }
 
+    #[test]
+    fn test_is_transient() {
+        for kind in [
+            io::ErrorKind::TimedOut,
+            io::ErrorKind::ConnectionReset,
+            io::ErrorKind::ConnectionAborted,
+            io::ErrorKind::BrokenPipe,
+            io::ErrorKind::WouldBlock,
+        ] {
+            assert!(
+                RlgError::from(io::Error::from(kind)).is_transient()
+            );
+        }
+        assert!(
+            RlgError::NetworkError("reset".to_string()).is_transient()
+        );
+
+        for err in [
+            RlgError::from(io::Error::from(io::ErrorKind::NotFound)),
+            RlgError::from(io::Error::from(
+                io::ErrorKind::PermissionDenied,
+            )),
+            RlgError::FormatParseError("bad".to_string()),
+            RlgError::LevelParseError("LOUD".to_string()),
+            RlgError::ConfigError(ConfigError::ValidationError(
+                "bad".to_string(),
+            )),
+            RlgError::FormattingError("bad".to_string()),
+            RlgError::UnsupportedFormat("xml".to_string()),
+            RlgError::custom("bad"),
+        ] {
+            assert!(!err.is_transient(), "{:?}", err);
+        }
+    }
+
     #[test]
     fn test_config_error_conversion() {
         let config_err =
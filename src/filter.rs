@@ -0,0 +1,128 @@
+// filter.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{log::Log, log_level::LogLevel};
+use regex::Regex;
+use std::{fmt, sync::Arc};
+
+/// A predicate deciding whether a log entry is accepted.
+type Predicate = Arc<dyn Fn(&Log) -> bool + Send + Sync>;
+
+/// A composable filter over log entries.
+///
+/// Filters are built from simple predicates on the level, component and
+/// description of an entry, and combined with `and`, `or` and `not`.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::filter::LogFilter;
+/// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+///
+/// let filter = LogFilter::level(LogLevel::WARN, LogLevel::CRITICAL)
+///     .and(LogFilter::component("db*"))
+///     .and(LogFilter::description_contains("timeout").not());
+///
+/// let log = Log::new("id", "2024-01-01", &LogLevel::ERROR, "db-pool", "connection lost", &LogFormat::CLF);
+/// assert!(log.matches(&filter));
+/// ```
+#[derive(Clone)]
+pub struct LogFilter {
+    predicate: Predicate,
+    description: String,
+}
+
+impl LogFilter {
+    /// Creates a filter from a predicate and a description used by `Debug`.
+    fn new<F>(description: String, predicate: F) -> Self
+    where
+        F: Fn(&Log) -> bool + Send + Sync + 'static,
+    {
+        LogFilter {
+            predicate: Arc::new(predicate),
+            description,
+        }
+    }
+
+    /// Accepts entries whose level is between `min` and `max`, inclusive.
+    pub fn level(min: LogLevel, max: LogLevel) -> Self {
+        let (low, high) = (min.to_numeric(), max.to_numeric());
+        LogFilter::new(
+            format!("level({}..={})", min, max),
+            move |log| (low..=high).contains(&log.level.to_numeric()),
+        )
+    }
+
+    /// Accepts entries whose component matches the glob `pattern`.
+    ///
+    /// In the pattern, `*` matches any sequence of characters and `?`
+    /// matches a single character; everything else matches literally.
+    pub fn component(pattern: &str) -> Self {
+        let regex = glob_to_regex(pattern);
+        LogFilter::new(
+            format!("component({:?})", pattern),
+            move |log| regex.is_match(&log.component),
+        )
+    }
+
+    /// Accepts entries whose description contains `substr`.
+    pub fn description_contains(substr: &str) -> Self {
+        let substr = substr.to_string();
+        LogFilter::new(
+            format!("description_contains({:?})", substr),
+            move |log| log.description.contains(&substr),
+        )
+    }
+
+    /// Accepts entries accepted by both this filter and `other`.
+    pub fn and(self, other: LogFilter) -> Self {
+        let description =
+            format!("({} and {})", self.description, other.description);
+        LogFilter::new(description, move |log| {
+            self.matches(log) && other.matches(log)
+        })
+    }
+
+    /// Accepts entries accepted by this filter or `other`.
+    pub fn or(self, other: LogFilter) -> Self {
+        let description =
+            format!("({} or {})", self.description, other.description);
+        LogFilter::new(description, move |log| {
+            self.matches(log) || other.matches(log)
+        })
+    }
+
+    /// Accepts entries rejected by this filter.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        let description = format!("not {}", self.description);
+        LogFilter::new(description, move |log| !self.matches(log))
+    }
+
+    /// Returns `true` if `log` is accepted by this filter.
+    pub fn matches(&self, log: &Log) -> bool {
+        (self.predicate)(log)
+    }
+}
+
+impl fmt::Debug for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LogFilter").field(&self.description).finish()
+    }
+}
+
+/// Translates a glob pattern into an anchored regular expression.
+fn glob_to_regex(pattern: &str) -> Regex {
+    let mut regex = String::from("(?s)^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped glob is a valid regex")
+}
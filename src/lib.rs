@@ -11,7 +11,7 @@
 //!
 //! ## Features
 //!
-//! - Multiple log levels: `ALL`, `DEBUG`, `DISABLED`, `ERROR`, `FATAL`, `INFO`, `NONE`, `TRACE`, `VERBOSE`, and `WARN`.
+//! - Multiple log levels: `ALL`, `AUDIT`, `CRITICAL`, `DEBUG`, `DISABLED`, `ERROR`, `FATAL`, `INFO`, `NONE`, `SECURITY`, `TRACE`, `VERBOSE`, and `WARN`.
 //! - Structured log formats for easy parsing and filtering.
 //! - Support for multiple output formats including:
 //!   - Common Event Format (CEF)
@@ -20,12 +20,15 @@
 //!   - JavaScript Object Notation (JSON)
 //!   - NCSA Common Log Format (CLF)
 //!   - W3C Extended Log File Format (W3C)
-//!   - Syslog Format
+//!   - Syslog Format (RFC 5424)
 //!   - Apache Access Log Format
 //!   - Logstash Format
 //!   - Log4j XML Format
 //!   - NDJSON (Newline Delimited JSON)
-//! - Configurable logging destinations (file, stdout, network).
+//!   - Comma-Separated Values (CSV)
+//!   - OpenTelemetry log records (OTLP JSON)
+//!   - Fluentd forward protocol (JSON)
+//! - Configurable logging destinations (file, stdout, stderr, TCP, UDP).
 //! - Log rotation support.
 //! - Asynchronous logging for improved performance.
 
@@ -43,16 +46,27 @@
 // Re-export commonly used items
 pub use config::Config;
 pub use config::{LogRotation, LoggingDestination};
-pub use log::Log;
+pub use log::{ClfParts, Log, LogBuilder};
 pub use log_format::LogFormat;
 pub use log_level::LogLevel;
 
+/// Buffered writing of log entries to the log file.
+pub mod buffered;
+pub use buffered::BufferedLogger;
+
 /// Configuration module for RustLogs.
 pub mod config;
 
+/// Task-local log context for session ID and component propagation.
+pub mod context;
+
 /// Core logging functionality.
 pub mod log;
 
+/// Composable filters for selecting log entries.
+pub mod filter;
+pub use filter::LogFilter;
+
 /// Log format definitions and implementations.
 pub mod log_format;
 
@@ -63,6 +77,26 @@
 #[macro_use]
 pub mod macros;
 
+/// Logging of panics through a panic hook.
+pub mod panic_hook;
+pub use panic_hook::install_panic_hook;
+
+/// Suppression of high-frequency duplicate log entries.
+pub mod rate_limit;
+
+/// Pluggable destinations for log entries.
+pub mod sink;
+#[cfg(feature = "reqwest")]
+pub use sink::HttpSink;
+pub use sink::{
+    BinarySink, FileSink, LogSink, RollingFileSink, StderrSink,
+    StdoutSink, TcpSink, UdpSink, UdpSyslogSink,
+};
+
+/// RAII timers that log the duration of an operation.
+pub mod timer;
+pub use timer::LogTimer;
+
 /// Error handling module
 pub mod error;
 pub use error::{RlgError, RlgResult};
@@ -3,17 +3,121 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::{Config, LogFormat, LogLevel, RlgError, RlgResult};
+#[cfg(feature = "reqwest")]
+use crate::sink::HttpSink;
+use crate::{
+    config::GLOBAL_CONFIG,
+    filter::LogFilter,
+    sink::{
+        append_to_file, FileSink, LogSink, StderrSink, StdoutSink,
+        TcpSink, UdpSink, UdpSyslogSink,
+    },
+    timer::LogTimer,
+    utils::{
+        apply_template, generate_timestamp, parse_datetime,
+        rotate_log_file,
+    },
+    Config, LogFormat, LogLevel, LoggingDestination, RlgError,
+    RlgResult,
+};
+use colored::{Color, Colorize};
 use dtt::datetime::DateTime;
+use futures::future::join_all;
 use hostname;
+use log::warn;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
     fmt::{self, Write as FmtWrite},
     io,
+    net::SocketAddr,
+    path::Path,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+use uuid::Uuid;
 use vrd::random::Random;
 
+/// Syslog facility used for RFC 5424 output (user-level messages).
+const RFC5424_FACILITY: u8 = 1;
+
+/// Structured-data ID used for RFC 5424 output.
+///
+/// 32473 is the private enterprise number reserved for documentation.
+const RFC5424_SD_ID: &str = "rlg@32473";
+
+/// Compiled regular expression for splitting RFC 3339 timestamps.
+static RFC3339_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?P<datetime>\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?P<fraction>\.\d+)?(?P<offset>Z|[+-]\d{2}:\d{2})$",
+    )
+    .unwrap()
+});
+
+/// Next sequence number assigned to a logged entry.
+static LOG_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Maximum number of error sources included by `Log::new_from_error`.
+const MAX_ERROR_SOURCE_DEPTH: usize = 5;
+
+/// Marker appended to descriptions shortened by
+/// `Log::ensure_max_description`.
+const TRUNCATED: &str = "…[truncated]";
+
+/// Replacement text for values masked by `Log::redact`.
+const REDACTED: &str = "[REDACTED]";
+
+/// Field holding the comma-separated tags set by `Log::with_tags`.
+const TAGS_FIELD: &str = "_tags";
+
+/// Key of the tags field in text formats such as CLF.
+const TAGS_TEXT_KEY: &str = "tags";
+
+/// Prefix of the fields copied by `Log::new_with_context_map`.
+const CONTEXT_FIELD_PREFIX: &str = "ctx.";
+
+/// Cache of compiled redaction patterns, keyed by pattern source.
+///
+/// Invalid patterns are cached as `None` so they are only compiled once.
+static REDACT_PATTERNS: Lazy<Mutex<HashMap<String, Option<Regex>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The steps of `Log::log_with_config` an entry has completed, so that
+/// `Log::log_with_retry` does not repeat them.
+#[derive(Debug, Default)]
+struct Delivery {
+    /// Whether the entry has been written to the log file.
+    file_written: bool,
+    /// The destinations the entry has been echoed to.
+    echoed: Vec<LoggingDestination>,
+}
+
+/// TCP sinks used by `Log::log_with_config`, keyed by server address, so
+/// that entries logged to the same server share one connection.
+///
+/// `Log::log()` drops the sinks of servers its configuration no longer
+/// lists, so that connections do not outlive a configuration reload.
+static TCP_SINKS: Lazy<Mutex<HashMap<SocketAddr, Arc<TcpSink>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Compiled regular expression for parsing log entries in CLF output.
+static CLF_ENTRY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^SessionID=(?P<session_id>.*?) Timestamp=(?P<time>.*?) Description=(?P<description>.*) Level=(?P<level>\S+) Component=(?P<component>\S*)(?P<fields>(?: .*)?)$",
+    )
+    .unwrap()
+});
+
 /// The `Log` struct provides an easy way to log a message to the console.
 /// It contains a set of defined fields to create a simple log message with a readable output format.
 #[derive(
@@ -39,6 +143,12 @@ pub struct Log {
     pub description: String,
     /// The format of the log message.
     pub format: LogFormat,
+    /// Extra key-value fields attached to the log entry.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    /// The process-wide sequence number, assigned when the entry is logged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
 }
 
 impl Default for Log {
@@ -50,110 +160,1845 @@ fn default() -> Log {
             component: String::default(),
             description: String::default(),
             format: LogFormat::CLF,
+            fields: BTreeMap::new(),
+            sequence: None,
+        }
+    }
+}
+
+impl Log {
+    /// Returns a `LogBuilder` for constructing a log entry field by field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log::Log;
+    ///
+    /// let log = Log::builder()
+    ///     .component("auth")
+    ///     .description("login attempt")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(log.component, "auth");
+    /// ```
+    pub fn builder() -> LogBuilder {
+        LogBuilder::default()
+    }
+
+    /// Logs a message asynchronously using a pre-allocated buffer to reduce memory allocation.
+    ///
+    /// This function formats the log message according to the specified log format and writes it to
+    /// both the log file and standard output. It ensures that the log file is flushed after every write
+    /// to guarantee data persistence.
+    ///
+    /// Entries below the effective log level for their component (see
+    /// `Config::effective_level_for`) are skipped, and values matching
+    /// `Config::redact_patterns` are masked (see `Log::redact`).
+    /// Descriptions longer than `Config::max_message_size` bytes are
+    /// truncated.
+    ///
+    /// The global configuration set by `Config::init_global` is used if
+    /// there is one, and the default configuration otherwise.
+    ///
+    /// # Returns
+    /// * `RlgResult<()>` - Result with `Ok(())` if the logging succeeds, or `RlgError` if any errors occur.
+    pub async fn log(&self) -> RlgResult<()> {
+        let config = Self::load_default_config().await?;
+        self.log_with_config(&config).await
+    }
+
+    /// Logs a message asynchronously like `log()`, retrying on transient
+    /// failures.
+    ///
+    /// The configuration is loaded again for every attempt, so failing to
+    /// load it (reported as an `RlgError::IoError`) is retried as well.
+    /// Writes failing with an `RlgError::IoError` or an
+    /// `RlgError::NetworkError` are retried up to `max_retries` times,
+    /// waiting `backoff_ms * attempt` milliseconds before each retry. Any
+    /// other error is returned at once.
+    ///
+    /// A retry resumes at the destination that failed: the entry is
+    /// numbered once, and is not written again to the log file or to the
+    /// destinations that already received it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error if every attempt fails.
+    pub async fn log_with_retry(
+        &self,
+        max_retries: u32,
+        backoff_ms: u64,
+    ) -> RlgResult<()> {
+        let entry = self.numbered();
+        let mut delivery = Delivery::default();
+        let mut attempt = 0;
+        loop {
+            let result = match Self::load_default_config().await {
+                Ok(config) => {
+                    entry.deliver(&config, &mut delivery).await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(
+                    RlgError::IoError(_) | RlgError::NetworkError(_),
+                ) if attempt < max_retries => {
+                    attempt += 1;
+                    let delay =
+                        backoff_ms.saturating_mul(u64::from(attempt));
+                    tokio::time::sleep(Duration::from_millis(delay))
+                        .await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Logs a message asynchronously using the given configuration.
+    ///
+    /// This behaves like `log()` but uses `config` instead of loading the
+    /// default configuration. The entry is always appended to
+    /// `config.log_file_path`, and is also written to standard output or
+    /// standard error when `config.logging_destinations` contains
+    /// `LoggingDestination::Stdout` or `LoggingDestination::Stderr`, and
+    /// posted to every `LoggingDestination::Http` endpoint when the
+    /// `reqwest` feature is enabled. Every `LoggingDestination::UdpSyslog`
+    /// server receives the entry as an RFC 5424 datagram. Entries sent to
+    /// the same `LoggingDestination::Tcp` server share one connection.
+    /// Each destination uses the format configured for it in
+    /// `config.per_destination_format`, falling back to `self.format`;
+    /// entries in `LogFormat::CLF` take the format `config.log_format`
+    /// names instead, if any (see `Config::resolve_format`).
+    /// Descriptions longer than `config.max_message_size` bytes are
+    /// truncated (see `Log::ensure_max_description`). Extra fields of CLF
+    /// entries written to files are separated by
+    /// `config.structured_fields_separator`.
+    /// Before the entry is written, `config.log_file_path` is rotated if
+    /// `config.log_rotation` says it is due (see `utils::rotate_log_file`).
+    /// If writing to `config.log_file_path` fails with an I/O error and
+    /// `config.fallback_destination` is set, the entry is written there
+    /// instead, with `[fallback: <error>] ` prepended to its description.
+    ///
+    /// # Returns
+    /// * `RlgResult<()>` - Result with `Ok(())` if the logging succeeds, or `RlgError` if any errors occur.
+    pub async fn log_with_config(
+        &self,
+        config: &Config,
+    ) -> RlgResult<()> {
+        self.deliver(config, &mut Delivery::default()).await
+    }
+
+    /// Returns the entry with a sequence number, assigning the next one
+    /// unless it already has one.
+    fn numbered(&self) -> Cow<'_, Log> {
+        if self.sequence.is_some() {
+            return Cow::Borrowed(self);
+        }
+        Cow::Owned(Log {
+            sequence: Some(
+                LOG_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+            ),
+            ..self.clone()
+        })
+    }
+
+    /// Returns the entry as `config` writes it, or `None` if it is below
+    /// the effective level for its component.
+    ///
+    /// The entry is numbered, takes the configured format, context fields
+    /// and redaction, and its description is truncated to
+    /// `config.max_message_size` bytes.
+    pub(crate) fn prepared_for(
+        &self,
+        config: &Config,
+    ) -> Option<Cow<'_, Log>> {
+        // Skip entries below the effective level for their component.
+        if self.level.to_numeric()
+            < config.effective_level_for(&self.component).to_numeric()
+        {
+            return None;
+        }
+
+        let mut entry = self.numbered();
+
+        // Entries in the default format use the configured format.
+        if entry.format == LogFormat::CLF {
+            match config.resolve_format() {
+                // Invalid formats are reported by `Config::validate`.
+                Ok(LogFormat::CLF) | Err(_) => {}
+                Ok(format) => {
+                    entry = Cow::Owned(Log {
+                        format,
+                        ..entry.into_owned()
+                    })
+                }
+            }
+        }
+
+        if config.include_process_context {
+            entry = Cow::Owned(entry.with_process_context());
+        }
+        if config.include_hostname {
+            entry = Cow::Owned(entry.with_hostname());
+        }
+        if config.anonymize_session_ids {
+            entry = Cow::Owned(entry.anonymize_session_id());
+        }
+
+        // Mask sensitive values before anything is written.
+        if !config.redact_patterns.is_empty() {
+            let patterns: Vec<&str> = config
+                .redact_patterns
+                .iter()
+                .map(String::as_str)
+                .collect();
+            entry = Cow::Owned(entry.redact(&patterns));
+        }
+        if entry.description.len() > config.max_message_size {
+            entry = Cow::Owned(
+                entry.ensure_max_description(config.max_message_size),
+            );
+        }
+        Some(entry)
+    }
+
+    /// Writes the entry with `config`, skipping the steps `delivery` says
+    /// are done and recording those that succeed.
+    async fn deliver(
+        &self,
+        config: &Config,
+        delivery: &mut Delivery,
+    ) -> RlgResult<()> {
+        let entry = match self.prepared_for(config) {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+
+        if !delivery.file_written {
+            entry.write_log_file(config).await?;
+            delivery.file_written = true;
+        }
+
+        // Echo the entry to the remaining destinations. Entries always go
+        // to `config.log_file_path`, and plain network destinations have
+        // no sink.
+        for destination in &config.logging_destinations {
+            match destination {
+                LoggingDestination::File(_) => {}
+                #[allow(deprecated)]
+                LoggingDestination::Network(_) => {}
+                #[cfg(not(feature = "reqwest"))]
+                LoggingDestination::Http { .. } => {}
+                _ if delivery.echoed.contains(destination) => {}
+                _ => {
+                    entry
+                        .write_to_destination(config, destination)
+                        .await?;
+                    delivery.echoed.push(destination.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the entry to `config.log_file_path`, rotating the file
+    /// first if it is due, or to `config.fallback_destination` if the
+    /// write fails with an I/O error.
+    async fn write_log_file(&self, config: &Config) -> RlgResult<()> {
+        // Start a new log file first if the rotation policy says so. A
+        // failed rotation must not lose the entry, which still goes to
+        // the current file.
+        if let Err(e) =
+            rotate_log_file(&config.log_file_path, config).await
+        {
+            warn!(
+                "Failed to rotate {}: {}",
+                config.log_file_path.display(),
+                e
+            );
+        }
+
+        let file =
+            LoggingDestination::File(config.log_file_path.clone());
+        match self.write_to_destination(config, &file).await {
+            Err(error @ RlgError::IoError(_)) => {
+                let fallback = match &config.fallback_destination {
+                    Some(fallback) => fallback,
+                    None => return Err(error),
+                };
+                // Keep the entry, noting why it was redirected.
+                let redirected = Log {
+                    description: format!(
+                        "[fallback: {}] {}",
+                        error, self.description
+                    ),
+                    ..self.clone()
+                };
+                if redirected
+                    .write_to_destination(config, fallback)
+                    .await
+                    .is_err()
+                {
+                    return Err(error);
+                }
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Writes the entry to `destination`, in the format configured for it.
+    async fn write_to_destination(
+        &self,
+        config: &Config,
+        destination: &LoggingDestination,
+    ) -> RlgResult<()> {
+        let entry = self.formatted_for(config, destination);
+        match destination {
+            LoggingDestination::File(path) => {
+                let sink = FileSink::new(path).with_fields_separator(
+                    config.structured_fields_separator,
+                );
+                entry.log_to(&sink).await
+            }
+            LoggingDestination::Stdout => {
+                let sink = StdoutSink::new(config.colorize_stdout)
+                    .with_colors(config.log_level_color_map.clone());
+                entry.log_to(&sink).await
+            }
+            LoggingDestination::Stderr => {
+                entry.log_to(&StderrSink).await
+            }
+            #[cfg(feature = "reqwest")]
+            LoggingDestination::Http { url, headers } => {
+                entry.log_to(&HttpSink::new(url, headers.clone())).await
+            }
+            // Syslog datagrams are always formatted as RFC 5424.
+            LoggingDestination::UdpSyslog { addr } => {
+                self.log_to(&UdpSyslogSink::new(*addr)).await
+            }
+            LoggingDestination::Tcp(addr) => {
+                let sink = TCP_SINKS
+                    .lock()
+                    .entry(*addr)
+                    .or_insert_with(|| Arc::new(TcpSink::new(*addr)))
+                    .clone();
+                entry.log_to(sink.as_ref()).await
+            }
+            LoggingDestination::Udp(addr) => {
+                entry.log_to(&UdpSink::new(*addr)).await
+            }
+            _ => Err(RlgError::NetworkError(format!(
+                "No sink is available for logging destination {}",
+                destination
+            ))),
+        }
+    }
+
+    /// Returns the entry in the format configured for `destination`, or
+    /// unchanged when `config.per_destination_format` has no override.
+    pub(crate) fn formatted_for(
+        &self,
+        config: &Config,
+        destination: &LoggingDestination,
+    ) -> Cow<'_, Log> {
+        match config.format_for(destination) {
+            Some(format) if format != self.format => Cow::Owned(Log {
+                format,
+                ..self.clone()
+            }),
+            _ => Cow::Borrowed(self),
+        }
+    }
+
+    /// Writes the log entry to the given sink.
+    ///
+    /// This is the preferred way to emit a log entry when the destination
+    /// is known; `log()` is a convenience wrapper that writes to a
+    /// `FileSink` for the configured log file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log::Log;
+    /// use rlg::sink::FileSink;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let sink = FileSink::new(dir.path().join("app.log"));
+    /// Log::default().log_to(&sink).await.unwrap();
+    /// # });
+    /// ```
+    pub async fn log_to(&self, sink: &dyn LogSink) -> RlgResult<()> {
+        sink.write(self).await
+    }
+
+    /// Writes the log entry to every sink in turn.
+    ///
+    /// A failing sink does not stop the entry from being written to the
+    /// remaining sinks.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered, after every sink has been tried.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log::Log;
+    /// use rlg::sink::{FileSink, StdoutSink};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let file = FileSink::new(dir.path().join("app.log"));
+    /// Log::default().log_to_all(&[&file, &StdoutSink::default()]).await.unwrap();
+    /// # });
+    /// ```
+    pub async fn log_to_all(
+        &self,
+        sinks: &[&dyn LogSink],
+    ) -> RlgResult<()> {
+        let mut result = Ok(());
+        for sink in sinks {
+            let outcome = sink.write(self).await;
+            if result.is_ok() {
+                result = outcome;
+            }
+        }
+        result
+    }
+
+    /// Writes the log entry to every sink concurrently.
+    ///
+    /// This behaves like `log_to_all()`, but the writes are polled together
+    /// rather than one after another, which lowers latency when several
+    /// slow destinations are involved.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failing sink, in the order given,
+    /// after every sink has been tried.
+    pub async fn log_to_all_concurrent(
+        &self,
+        sinks: &[Arc<dyn LogSink>],
+    ) -> RlgResult<()> {
+        join_all(sinks.iter().map(|sink| sink.write(self)))
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Logs several entries with a single write to the log file.
+    ///
+    /// All entries are formatted up front, then written with one `write_all`
+    /// call followed by a single flush, which is considerably faster than
+    /// calling `log()` for every entry. The log file path and the separator
+    /// of extra CLF fields are read from the default configuration, exactly
+    /// as `log()` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::UnsupportedFormat` without touching the log file if
+    /// any entry uses a format that cannot be written to a file, or an
+    /// `RlgError::IoError` if the file cannot be written.
+    pub async fn log_batch(entries: &[Log]) -> RlgResult<()> {
+        let config = Self::load_default_config().await?;
+        let buffer = Self::format_batch(
+            entries,
+            config.structured_fields_separator,
+        )?;
+        append_to_file(&config.log_file_path, buffer.as_bytes()).await
+    }
+
+    /// Logs several entries with a single write to the given file.
+    ///
+    /// This behaves like `log_batch()` but appends to `path` instead of the
+    /// log file configured by default, separating extra CLF fields with
+    /// spaces.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::UnsupportedFormat` without touching the file if
+    /// any entry uses a format that cannot be written to a file, or an
+    /// `RlgError::IoError` if the file cannot be written.
+    pub async fn log_batch_to_path<P: AsRef<Path>>(
+        path: P,
+        entries: &[Log],
+    ) -> RlgResult<()> {
+        let buffer = Self::format_batch(entries, ' ')?;
+        append_to_file(path.as_ref(), buffer.as_bytes()).await
+    }
+
+    /// Formats a batch of entries into a single buffer, separating the
+    /// extra fields of CLF entries with `fields_separator`.
+    fn format_batch(
+        entries: &[Log],
+        fields_separator: char,
+    ) -> RlgResult<String> {
+        let mut buffer = String::with_capacity(256 * entries.len());
+        for entry in entries {
+            entry.write_entry_with_separator(
+                &mut buffer,
+                fields_separator,
+            )?;
+        }
+        Ok(buffer)
+    }
+
+    /// Formats the entry as a single line for a log sink.
+    ///
+    /// Formats that cannot be written as a line are replaced by an
+    /// `Unsupported format` marker.
+    pub(crate) fn to_line(&self) -> RlgResult<String> {
+        self.to_line_with_separator(' ')
+    }
+
+    /// Formats the entry as a single line for a log sink, separating the
+    /// extra fields of CLF entries with `fields_separator`.
+    pub(crate) fn to_line_with_separator(
+        &self,
+        fields_separator: char,
+    ) -> RlgResult<String> {
+        let mut line = String::with_capacity(256);
+        match self
+            .write_entry_with_separator(&mut line, fields_separator)
+        {
+            // Handle unsupported formats
+            Err(RlgError::UnsupportedFormat(_)) => {
+                line.push_str("Unsupported format\n")
+            }
+            result => result?,
+        }
+        Ok(line)
+    }
+
+    /// Appends the entry, formatted as a log file line, to `buffer`,
+    /// separating the extra fields of CLF entries with `fields_separator`.
+    pub(crate) fn write_entry_with_separator(
+        &self,
+        buffer: &mut String,
+        fields_separator: char,
+    ) -> RlgResult<()> {
+        let write_result = match &self.format {
+            LogFormat::CLF => writeln!(
+                buffer,
+                "SessionID={} Timestamp={} Description={} Level={} Component={} Format=CLF{}{}",
+                self.session_id, self.time, self.description, self.level, self.component, self.text_fields(fields_separator), self.text_sequence(fields_separator)
+            ),
+            format if format.is_line_format() => {
+                writeln!(buffer, "{}", self)
+            }
+            _ => {
+                return Err(RlgError::UnsupportedFormat(
+                    self.format.to_string(),
+                ))
+            }
+        };
+
+        write_result.map_err(|e| {
+            RlgError::FormattingError(format!(
+                "Formatting error: {}",
+                e
+            ))
+        })
+    }
+
+    /// Loads the global configuration set by `Config::init_global`, or
+    /// the default configuration if there is none.
+    async fn load_default_config() -> RlgResult<Config> {
+        let config = match GLOBAL_CONFIG.get() {
+            Some(config) => config.read().clone(),
+            None => Config::load_async(None::<&str>, None)
+                .await
+                .map_err(|e| {
+                    RlgError::IoError(io::Error::new(
+                        io::ErrorKind::Other,
+                        e,
+                    ))
+                })?
+                .read()
+                .clone(),
+        };
+        // Close the connections to servers removed by a reload.
+        TCP_SINKS.lock().retain(|addr, _| {
+            config
+                .logging_destinations
+                .iter()
+                .chain(&config.fallback_destination)
+                .any(|destination| {
+                    matches!(destination, LoggingDestination::Tcp(a) if a == addr)
+                })
+        });
+        Ok(config)
+    }
+
+    /// Creates a new log entry with provided details.
+    pub fn new(
+        session_id: &str,
+        time: &str,
+        level: &LogLevel,
+        component: &str,
+        description: &str,
+        format: &LogFormat,
+    ) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            time: time.to_string(),
+            level: *level,
+            component: component.to_string(),
+            description: description.to_string(),
+            format: format.clone(),
+            fields: BTreeMap::new(),
+            sequence: None,
+        }
+    }
+
+    /// Creates a new log entry whose session ID is a random UUID v4.
+    ///
+    /// Unlike the random integers used by default, UUIDs are unlikely to
+    /// collide across many sessions. With the `uuid_session_ids` feature,
+    /// the logging macros such as `macro_info_log!` use UUID session IDs
+    /// too when no log context is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new_with_uuid_session("2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// assert_eq!(log.session_id.len(), 36);
+    /// ```
+    pub fn new_with_uuid_session(
+        time: &str,
+        level: &LogLevel,
+        component: &str,
+        description: &str,
+        format: &LogFormat,
+    ) -> Self {
+        Log::new(
+            &Uuid::new_v4().to_string(),
+            time,
+            level,
+            component,
+            description,
+            format,
+        )
+    }
+
+    /// Creates a new log entry carrying every entry of a context map as
+    /// an extra field.
+    ///
+    /// Keys are prefixed with `ctx.` so that context entries cannot
+    /// collide with other fields, such as `_tags`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    /// use std::collections::HashMap;
+    ///
+    /// let context = HashMap::from([("request_id".to_string(), "abc".to_string())]);
+    /// let log = Log::new_with_context_map("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF, &context);
+    /// assert_eq!(log.fields["ctx.request_id"], "abc");
+    /// ```
+    pub fn new_with_context_map(
+        session_id: &str,
+        time: &str,
+        level: &LogLevel,
+        component: &str,
+        description: &str,
+        format: &LogFormat,
+        context: &HashMap<String, String>,
+    ) -> Self {
+        let mut log = Log::new(
+            session_id,
+            time,
+            level,
+            component,
+            description,
+            format,
+        );
+        log.fields.extend(context.iter().map(|(key, value)| {
+            (format!("{}{}", CONTEXT_FIELD_PREFIX, key), value.clone())
+        }));
+        log
+    }
+
+    /// Starts timing an operation, returning a guard that logs its
+    /// duration.
+    ///
+    /// When the returned `LogTimer` is dropped, an `INFO` entry for
+    /// `component` with the description `"<description> completed in
+    /// <elapsed_ms>ms"` is logged asynchronously with `log()`. Call
+    /// `LogTimer::abort` to drop the timer without logging.
+    pub fn new_timed(component: &str, description: &str) -> LogTimer {
+        LogTimer::new(component, description)
+    }
+
+    /// Creates a new log entry with a given sequence number.
+    ///
+    /// Entries logged with `log()` are numbered automatically; this is for
+    /// entries whose sequence number is already known, such as in tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new_with_sequence(7, "id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// assert_eq!(log.sequence_number(), Some(7));
+    /// assert!(log.to_string().ends_with(" seq=7"));
+    /// ```
+    pub fn new_with_sequence(
+        seq: u64,
+        session_id: &str,
+        time: &str,
+        level: &LogLevel,
+        component: &str,
+        description: &str,
+        format: &LogFormat,
+    ) -> Self {
+        Log {
+            sequence: Some(seq),
+            ..Log::new(
+                session_id,
+                time,
+                level,
+                component,
+                description,
+                format,
+            )
+        }
+    }
+
+    /// Returns the sequence number of the entry.
+    ///
+    /// Every entry written by `log()` or `log_with_config()` is numbered
+    /// from a process-wide counter, so the numbers give the order in which
+    /// entries were logged, even across components. Returns `None` for
+    /// entries that have not been logged.
+    pub fn sequence_number(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// Creates a new CLF log entry describing `err`.
+    ///
+    /// The description is the `Display` output of `err`, followed by
+    /// ` caused by: <source>` for each error in its `source()` chain, up to
+    /// a depth of five. The session ID is random and the time is the
+    /// current timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_level::LogLevel};
+    /// use std::io;
+    ///
+    /// let err = io::Error::new(io::ErrorKind::NotFound, "config.toml missing");
+    /// let log = Log::new_from_error(&err, LogLevel::ERROR, "config");
+    /// assert_eq!(log.description, "config.toml missing");
+    /// ```
+    pub fn new_from_error(
+        err: &dyn std::error::Error,
+        level: LogLevel,
+        component: &str,
+    ) -> Self {
+        let mut description = err.to_string();
+        let mut source = err.source();
+        for _ in 0..MAX_ERROR_SOURCE_DEPTH {
+            match source {
+                Some(cause) => {
+                    let _ =
+                        write!(description, " caused by: {}", cause);
+                    source = cause.source();
+                }
+                None => break,
+            }
+        }
+
+        Log::new(
+            &Random::default().int(0, 1_000_000_000).to_string(),
+            &generate_timestamp(),
+            &level,
+            component,
+            &description,
+            &LogFormat::CLF,
+        )
+    }
+
+    /// Creates a new CLF log entry whose description is `template` with
+    /// its `%{key}` placeholders substituted from `params` (see
+    /// `utils::apply_template`). The session ID is random and the time is
+    /// the current timestamp.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormattingError` if a placeholder of `template`
+    /// has no value in `params` or is not closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_level::LogLevel};
+    /// use std::collections::HashMap;
+    ///
+    /// let params = HashMap::from([("user", "alice"), ("ip", "10.0.0.1")]);
+    /// let log = Log::new_with_format_template(
+    ///     "User %{user} logged in from %{ip}",
+    ///     &params,
+    ///     &LogLevel::INFO,
+    ///     "auth",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(log.description, "User alice logged in from 10.0.0.1");
+    /// ```
+    pub fn new_with_format_template(
+        template: &str,
+        params: &HashMap<&str, &str>,
+        level: &LogLevel,
+        component: &str,
+    ) -> RlgResult<Self> {
+        Ok(Log::new(
+            &Random::default().int(0, 1_000_000_000).to_string(),
+            &generate_timestamp(),
+            level,
+            component,
+            &apply_template(template, params)?,
+            &LogFormat::CLF,
+        ))
+    }
+
+    /// Creates a log entry for a sub-operation of `parent`.
+    ///
+    /// The entry has the session ID, component, level and format of
+    /// `parent`, the current timestamp, and the given description.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let parent = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// let child = Log::new_child(&parent, "query database");
+    /// assert_eq!(child.session_id, "id");
+    /// assert_eq!(child.component, "api");
+    /// ```
+    pub fn new_child(parent: &Log, description: &str) -> Self {
+        Log::new_child_with_level(parent, parent.level, description)
+    }
+
+    /// Creates a log entry for a sub-operation of `parent` with the given
+    /// level.
+    ///
+    /// Like `new_child`, but the level of `parent` is replaced by `level`.
+    pub fn new_child_with_level(
+        parent: &Log,
+        level: LogLevel,
+        description: &str,
+    ) -> Self {
+        Log::new(
+            &parent.session_id,
+            &generate_timestamp(),
+            &level,
+            &parent.component,
+            description,
+            &parent.format,
+        )
+    }
+
+    /// Parses a log entry from the CLF output produced by `Display`.
+    ///
+    /// Extra fields appended as `key=value` pairs are restored into
+    /// `fields`. The `Format=CLF` marker written by `log()` is accepted and
+    /// ignored, so lines read back from a log file can be parsed too, and a
+    /// trailing numeric `seq=<n>` pair is restored as the sequence number.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` if the input is not a CLF log
+    /// entry or contains an invalid log level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// assert_eq!(Log::from_clf_str(&log.to_string()).unwrap(), log);
+    /// ```
+    pub fn from_clf_str(s: &str) -> RlgResult<Log> {
+        let captures = CLF_ENTRY_REGEX
+            .captures(s.trim_end())
+            .ok_or_else(|| {
+                RlgError::FormatParseError(format!(
+                    "Invalid CLF log entry: '{}'",
+                    s
+                ))
+            })?;
+
+        let mut log = Log::new(
+            &captures["session_id"],
+            &captures["time"],
+            &parse_level(&captures["level"])?,
+            &captures["component"],
+            &captures["description"],
+            &LogFormat::CLF,
+        );
+
+        let mut fields = &captures["fields"];
+        if let Some((rest, seq)) = fields.rsplit_once(" seq=") {
+            if let Ok(seq) = seq.parse::<u64>() {
+                log.sequence = Some(seq);
+                fields = rest;
+            }
+        }
+
+        let mut tokens = fields.split(' ').skip(1).peekable();
+        if tokens.peek() == Some(&"Format=CLF") {
+            let _ = tokens.next();
+        }
+        let mut last_key: Option<String> = None;
+        for token in tokens {
+            match token.split_once('=') {
+                Some((key, value)) if !key.is_empty() => {
+                    let key = match key {
+                        TAGS_TEXT_KEY => TAGS_FIELD,
+                        key => key,
+                    };
+                    log.fields
+                        .insert(key.to_string(), value.to_string());
+                    last_key = Some(key.to_string());
+                }
+                // A token without a key continues the previous value.
+                _ => match last_key
+                    .as_ref()
+                    .and_then(|key| log.fields.get_mut(key))
+                {
+                    Some(value) => {
+                        value.push(' ');
+                        value.push_str(token);
+                    }
+                    None => {
+                        return Err(RlgError::FormatParseError(
+                            format!("Invalid CLF field: '{}'", token),
+                        ))
+                    }
+                },
+            }
+        }
+        Ok(log)
+    }
+
+    /// Returns the values of the fields of the CLF output produced by
+    /// `Display`, one string per field.
+    ///
+    /// The parts can be changed individually and turned back into an
+    /// entry with `ClfParts::to_log`, without formatting and parsing the
+    /// whole line. Extra fields and the sequence number are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// let parts = log.into_clf_parts();
+    /// assert_eq!(parts.level, "INFO");
+    /// assert_eq!(parts.to_log(LogFormat::CLF).unwrap(), log);
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_clf_parts(&self) -> ClfParts {
+        ClfParts {
+            session_id: self.session_id.clone(),
+            timestamp: self.time.clone(),
+            description: self.description.clone(),
+            level: self.level.to_string(),
+            component: self.component.clone(),
+        }
+    }
+
+    /// Parses a log entry from the JSON output produced by `Display`.
+    ///
+    /// Members other than the standard `SessionID`, `Timestamp`, `Level`,
+    /// `Component`, `Description`, `Format` and `Sequence` keys are
+    /// restored into `fields`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` if the input is not a JSON
+    /// object, a mandatory key is missing, or a value is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::JSON);
+    /// assert_eq!(Log::from_json_str(&log.to_string()).unwrap(), log);
+    /// ```
+    pub fn from_json_str(s: &str) -> RlgResult<Log> {
+        let value: serde_json::Value = serde_json::from_str(s)
+            .map_err(|e| {
+                RlgError::FormatParseError(format!(
+                    "Invalid JSON log entry: {}",
+                    e
+                ))
+            })?;
+        let object = value.as_object().ok_or_else(|| {
+            RlgError::FormatParseError(
+                "JSON log entry must be an object".to_string(),
+            )
+        })?;
+
+        let get = |key: &str| -> RlgResult<&str> {
+            object.get(key).and_then(|v| v.as_str()).ok_or_else(|| {
+                RlgError::FormatParseError(format!(
+                    "Missing or invalid '{}' in JSON log entry",
+                    key
+                ))
+            })
+        };
+        let format = match object.get("Format") {
+            Some(_) => LogFormat::from_str(get("Format")?)?,
+            None => LogFormat::JSON,
+        };
+
+        let mut log = Log::new(
+            get("SessionID")?,
+            get("Timestamp")?,
+            &parse_level(get("Level")?)?,
+            get("Component")?,
+            get("Description")?,
+            &format,
+        );
+        if let Some(seq) = object.get("Sequence") {
+            log.sequence = Some(seq.as_u64().ok_or_else(|| {
+                RlgError::FormatParseError(
+                    "Missing or invalid 'Sequence' in JSON log entry"
+                        .to_string(),
+                )
+            })?);
+        }
+        for (key, value) in object {
+            if matches!(
+                key.as_str(),
+                "SessionID"
+                    | "Timestamp"
+                    | "Level"
+                    | "Component"
+                    | "Description"
+                    | "Format"
+                    | "Sequence"
+            ) {
+                continue;
+            }
+            log.fields
+                .insert(key.clone(), json_field_string(key, value));
+        }
+
+        Ok(log)
+    }
+
+    /// Parses a log entry from the GELF output produced by `Display`.
+    ///
+    /// `short_message` becomes the description, `timestamp` the time,
+    /// `session_id` the session ID and `level` the log level. The
+    /// component is read from `component`, or from `host` when absent.
+    /// A numeric `sequence` is restored as the sequence number, and
+    /// members other than these and `version` are restored into `fields`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` if the input is not a JSON
+    /// object, a mandatory key is missing, or a value is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::GELF);
+    /// assert_eq!(Log::from_gelf_str(&log.to_string()).unwrap(), log);
+    /// ```
+    pub fn from_gelf_str(s: &str) -> RlgResult<Log> {
+        let value: serde_json::Value = serde_json::from_str(s)
+            .map_err(|e| {
+                RlgError::FormatParseError(format!(
+                    "Invalid GELF log entry: {}",
+                    e
+                ))
+            })?;
+        let object = value.as_object().ok_or_else(|| {
+            RlgError::FormatParseError(
+                "GELF log entry must be an object".to_string(),
+            )
+        })?;
+
+        let get = |key: &str| -> RlgResult<&str> {
+            object.get(key).and_then(|v| v.as_str()).ok_or_else(|| {
+                RlgError::FormatParseError(format!(
+                    "Missing or invalid '{}' in GELF log entry",
+                    key
+                ))
+            })
+        };
+        let component = match object.get("component") {
+            Some(_) => get("component")?,
+            None => get("host")?,
+        };
+
+        let mut log = Log::new(
+            get("session_id")?,
+            get("timestamp")?,
+            &parse_level(get("level")?)?,
+            component,
+            get("short_message")?,
+            &LogFormat::GELF,
+        );
+        if let Some(seq) = object.get("sequence") {
+            log.sequence = Some(seq.as_u64().ok_or_else(|| {
+                RlgError::FormatParseError(
+                    "Missing or invalid 'sequence' in GELF log entry"
+                        .to_string(),
+                )
+            })?);
+        }
+        for (key, value) in object {
+            if matches!(
+                key.as_str(),
+                "version"
+                    | "host"
+                    | "short_message"
+                    | "level"
+                    | "timestamp"
+                    | "component"
+                    | "session_id"
+                    | "sequence"
+            ) {
+                continue;
+            }
+            log.fields
+                .insert(key.clone(), json_field_string(key, value));
+        }
+
+        Ok(log)
+    }
+
+    /// Returns the entry as a JSON object, without going through its
+    /// string representation.
+    ///
+    /// The object has the same members as the `LogFormat::JSON` output:
+    /// the `SessionID`, `Timestamp`, `Level`, `Component`, `Description`
+    /// and `Format` keys, the `Sequence` key if the entry is numbered, and
+    /// one member per extra field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::JSON)
+    ///     .with_field("user_id", "42");
+    /// let value = log.to_json_value();
+    /// assert_eq!(value["Level"], "INFO");
+    /// assert_eq!(value["user_id"], "42");
+    /// ```
+    pub fn to_json_value(&self) -> serde_json::Value {
+        let mut object = serde_json::Map::new();
+        for (key, value) in [
+            ("SessionID", self.session_id.clone()),
+            ("Timestamp", self.time.clone()),
+            ("Level", self.level.to_string()),
+            ("Component", self.component.clone()),
+            ("Description", self.description.clone()),
+            ("Format", self.format.to_string()),
+        ] {
+            object.insert(
+                key.to_string(),
+                serde_json::Value::from(value),
+            );
+        }
+        for (key, value) in &self.fields {
+            object.insert(key.clone(), json_field_value(key, value));
+        }
+        if let Some(seq) = self.sequence {
+            object.insert(
+                "Sequence".to_string(),
+                serde_json::Value::from(seq),
+            );
+        }
+        serde_json::Value::Object(object)
+    }
+
+    /// Overlays the string members of a JSON object onto `fields`.
+    ///
+    /// Members whose value is not a string are ignored, and existing
+    /// fields with the same key are replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` if `extra` is not a JSON
+    /// object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let mut log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::JSON);
+    /// log.merge_json(serde_json::json!({ "region": "eu-west-1", "retries": 3 })).unwrap();
+    /// assert_eq!(log.fields["region"], "eu-west-1");
+    /// assert!(!log.fields.contains_key("retries"));
+    /// ```
+    pub fn merge_json(
+        &mut self,
+        extra: serde_json::Value,
+    ) -> RlgResult<()> {
+        let object = match extra {
+            serde_json::Value::Object(object) => object,
+            other => {
+                return Err(RlgError::FormatParseError(format!(
+                    "Expected a JSON object to merge, found: {}",
+                    other
+                )))
+            }
+        };
+        for (key, value) in object {
+            if let serde_json::Value::String(value) = value {
+                self.fields.insert(key, value);
+            }
         }
+        Ok(())
     }
-}
 
-impl Log {
-    /// Logs a message asynchronously using a pre-allocated buffer to reduce memory allocation.
+    /// Encodes the log entry as MessagePack.
     ///
-    /// This function formats the log message according to the specified log format and writes it to
-    /// both the log file and standard output. It ensures that the log file is flushed after every write
-    /// to guarantee data persistence.
+    /// This is a compact transport encoding for network and binary storage,
+    /// independent of the display `format` of the entry.
     ///
-    /// # Returns
-    /// * `RlgResult<()>` - Result with `Ok(())` if the logging succeeds, or `RlgError` if any errors occur.
-    pub async fn log(&self) -> RlgResult<()> {
-        let mut log_message = String::with_capacity(256);
-
-        // Format the log message based on the specified log format.
-        let write_result = match self.format {
-        LogFormat::CLF => writeln!(
-            log_message,
-            "SessionID={} Timestamp={} Description={} Level={} Component={} Format=CLF",
-            self.session_id, self.time, self.description, self.level, self.component
-        ),
-        LogFormat::JSON => writeln!(
-            log_message,
-            "{{\"SessionID\":\"{}\",\"Timestamp\":\"{}\",\"Level\":\"{}\",\"Component\":\"{}\",\"Description\":\"{}\",\"Format\":\"JSON\"}}",
-            self.session_id, self.time, self.level, self.component, self.description
-        ),
-        LogFormat::CEF => writeln!(
-            log_message,
-            "CEF:0|{}|{}|{}|{}|{}|CEF",
-            self.session_id, self.time, self.level, self.component, self.description
-        ),
-        _ => writeln!(log_message, "Unsupported format"),  // Handle unsupported formats
-    };
-
-        write_result.map_err(|e| {
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormattingError` if the entry cannot be encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// let bytes = log.to_bytes().unwrap();
+    /// assert_eq!(Log::from_bytes(&bytes).unwrap(), log);
+    /// ```
+    pub fn to_bytes(&self) -> RlgResult<Vec<u8>> {
+        rmp_serde::to_vec_named(self).map_err(|e| {
             RlgError::FormattingError(format!(
-                "Formatting error: {}",
+                "Failed to encode log entry as MessagePack: {}",
                 e
             ))
-        })?;
+        })
+    }
 
-        // Extract the log file path from the configuration.
-        let log_file_path;
-        {
-            let config = Config::load_async(None::<&str>)
-                .await
-                .map_err(|e| {
-                    RlgError::IoError(io::Error::new(
-                        io::ErrorKind::Other,
-                        e,
-                    ))
-                })?;
-            log_file_path = config.read().log_file_path.clone();
-        }
+    /// Decodes a log entry from the MessagePack produced by `to_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` if the bytes are not a valid
+    /// MessagePack log entry.
+    pub fn from_bytes(bytes: &[u8]) -> RlgResult<Log> {
+        rmp_serde::from_slice(bytes).map_err(|e| {
+            RlgError::FormatParseError(format!(
+                "Invalid MessagePack log entry: {}",
+                e
+            ))
+        })
+    }
 
-        // Open the log file for appending, or create it if it does not exist.
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_file_path)
-            .await
-            .map_err(|e| {
-                RlgError::IoError(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Failed to open log file: {}", e),
-                ))
-            })?;
+    /// Returns `true` if the log entry is accepted by `filter`.
+    ///
+    /// This is a convenience wrapper around `LogFilter::matches`.
+    pub fn matches(&self, filter: &LogFilter) -> bool {
+        filter.matches(self)
+    }
 
-        file.write_all(log_message.as_bytes()).await.map_err(|e| {
-            RlgError::IoError(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to write to log file: {}", e),
-            ))
-        })?;
+    /// Returns a copy of the log entry with its format changed to `target`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormattingError` if the entry cannot be rendered
+    /// in `target`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// let json = log.convert_format(LogFormat::JSON).unwrap();
+    /// assert_eq!(json.format, LogFormat::JSON);
+    /// assert_eq!(json.description, log.description);
+    /// ```
+    pub fn convert_format(&self, target: LogFormat) -> RlgResult<Log> {
+        let converted = Log {
+            format: target.clone(),
+            ..self.clone()
+        };
+        let _ = converted.render(target)?;
+        Ok(converted)
+    }
 
-        file.flush().await.map_err(|e| {
-            RlgError::IoError(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to flush log file: {}", e),
+    /// Formats the log entry in `format` without changing `self.format`.
+    ///
+    /// Extra fields are rendered in the style of the target format, so
+    /// converting structured output such as GELF to CLF flattens them into
+    /// `key=value` pairs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormattingError` if the entry cannot be rendered
+    /// in `format`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// let json = log.render(LogFormat::JSON).unwrap();
+    /// assert_eq!(Log::from_json_str(&json).unwrap().description, "request");
+    /// assert_eq!(log.format, LogFormat::CLF);
+    /// ```
+    pub fn render(&self, format: LogFormat) -> RlgResult<String> {
+        let mut output = String::with_capacity(256);
+        let log = Log {
+            format,
+            ..self.clone()
+        };
+        write!(output, "{}", log).map_err(|_| {
+            RlgError::FormattingError(format!(
+                "Failed to render log entry as {}",
+                log.format
             ))
         })?;
+        Ok(output)
+    }
 
-        Ok(())
+    /// Formats the entry as a multi-line block for reading in a terminal.
+    ///
+    /// The first line holds the level, timestamp and component, the
+    /// description follows on its own line, and the session ID and extra
+    /// fields are listed below it. When stdout is a terminal, the level is
+    /// colored by severity (see `Config::default_color_map`), the
+    /// timestamp is gray and the component is blue; otherwise plain text
+    /// is returned.
+    /// The `NO_COLOR` and `CLICOLOR_FORCE` environment variables and
+    /// `colored::control::set_override` take precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// colored::control::set_override(false);
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::WARN, "api", "slow request", &LogFormat::CLF);
+    /// let pretty = log.to_pretty_string();
+    /// assert!(pretty.starts_with("WARN 2024-01-01 api\n"));
+    /// assert!(pretty.contains("\n  slow request\n"));
+    /// ```
+    pub fn to_pretty_string(&self) -> String {
+        self.to_pretty_string_with_colors(&HashMap::new())
     }
 
-    /// Creates a new log entry with provided details.
-    pub fn new(
-        session_id: &str,
-        time: &str,
-        level: &LogLevel,
-        component: &str,
-        description: &str,
-        format: &LogFormat,
-    ) -> Self {
-        Self {
-            session_id: session_id.to_string(),
-            time: time.to_string(),
-            level: *level,
-            component: component.to_string(),
-            description: description.to_string(),
-            format: *format,
+    /// Formats the entry as `to_pretty_string` does, coloring the level
+    /// with the ANSI escape code `colors` maps it to.
+    ///
+    /// Levels missing from `colors`, or all levels if it is empty, take
+    /// their color from `Config::default_color_map`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    /// use std::collections::HashMap;
+    ///
+    /// colored::control::set_override(true);
+    /// let colors = HashMap::from([(LogLevel::INFO, "\x1b[32m".to_string())]);
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// assert!(log.to_pretty_string_with_colors(&colors).starts_with("\x1b[32mINFO\x1b[0m "));
+    /// ```
+    pub fn to_pretty_string_with_colors(
+        &self,
+        colors: &HashMap<LogLevel, String>,
+    ) -> String {
+        let colorize =
+            colored::control::SHOULD_COLORIZE.should_colorize();
+        let paint = |text: &str, color: Color| {
+            if colorize {
+                text.color(color).to_string()
+            } else {
+                text.to_string()
+            }
+        };
+        let level = if colorize {
+            let code = colors
+                .get(&self.level)
+                .cloned()
+                .or_else(|| {
+                    Config::default_color_map().remove(&self.level)
+                })
+                .unwrap_or_default();
+            format!("{}{}\x1b[0m", code, self.level)
+        } else {
+            self.level.to_string()
+        };
+
+        let mut output = format!(
+            "{} {} {}\n  {}\n  SessionID={}",
+            level,
+            paint(&self.time, Color::BrightBlack),
+            paint(&self.component, Color::Blue),
+            self.description,
+            self.session_id
+        );
+        for (key, value) in &self.fields {
+            let _ = write!(output, "\n  {}={}", key, value);
+        }
+        if let Some(sequence) = self.sequence {
+            let _ = write!(output, "\n  Sequence={}", sequence);
+        }
+        output
+    }
+
+    /// Returns a copy of the entry with its session ID anonymized.
+    ///
+    /// The session ID is replaced by the first 16 hex digits of its
+    /// SHA-256 hash, so entries from the same session can still be
+    /// correlated without storing the session ID itself. `log()` applies
+    /// this automatically when `Config::anonymize_session_ids` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("user-42", "2024-01-01", &LogLevel::INFO, "auth", "login", &LogFormat::CLF);
+    /// let anonymized = log.anonymize_session_id();
+    /// assert_eq!(anonymized.session_id.len(), 16);
+    /// assert_eq!(anonymized.session_id, log.anonymize_session_id().session_id);
+    /// ```
+    pub fn anonymize_session_id(&self) -> Log {
+        let digest =
+            format!("{:x}", Sha256::digest(self.session_id.as_bytes()));
+        Log {
+            session_id: digest[..16].to_string(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the severity of the entry as a number from `0.0` to `1.0`,
+    /// for alerting thresholds.
+    ///
+    /// The score is the `LogLevel::base_severity` of the level, raised by
+    /// up to `0.05` for descriptions of up to 1024 bytes or more, so that
+    /// the level always dominates. It never exceeds `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("1", "2024-01-01", &LogLevel::ERROR, "db", "timeout", &LogFormat::CLF);
+    /// let score = log.severity_score();
+    /// assert!(score > 0.8 && score < 0.85);
+    /// ```
+    pub fn severity_score(&self) -> f64 {
+        let length = self.description.len().min(1024) as f64 / 1024.0;
+        (self.level.base_severity() + 0.05 * length).min(1.0)
+    }
+
+    /// Returns a content-based identifier for deduplicating entries.
+    ///
+    /// The identifier is the hex-encoded SHA-256 hash of the level,
+    /// component and description. The session ID, timestamp, format and
+    /// extra fields are not included, so the same message logged by
+    /// different sessions has the same identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let first = Log::new("1", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF);
+    /// let second = Log::new("2", "2024-01-02", &LogLevel::INFO, "api", "request", &LogFormat::JSON);
+    /// assert_eq!(first.hash_id(), second.hash_id());
+    /// assert_eq!(first.hash_id().len(), 64);
+    /// ```
+    pub fn hash_id(&self) -> String {
+        let mut hasher = Sha256::new();
+        // Separate the fields so that moving characters between adjacent
+        // fields changes the hash.
+        for part in [
+            self.level.to_string().as_str(),
+            &self.component,
+            &self.description,
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Attaches an extra key-value field to the log entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF)
+    ///     .with_field("user_id", "42");
+    /// assert!(log.to_string().ends_with(" user_id=42"));
+    /// ```
+    pub fn with_field(mut self, key: &str, value: &str) -> Self {
+        self.fields.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Attaches several extra key-value fields to the log entry.
+    pub fn with_fields(mut self, pairs: &[(&str, &str)]) -> Self {
+        for (key, value) in pairs {
+            self.fields.insert(key.to_string(), value.to_string());
+        }
+        self
+    }
+
+    /// Attaches free-form tags to the log entry, replacing any tags it
+    /// already has.
+    ///
+    /// The tags are stored comma-separated in the `_tags` field, so they
+    /// must not contain commas. JSON-based formats render them as an
+    /// array, and text formats such as CLF as a `tags=a,b` pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF)
+    ///     .with_tags(&["billing", "eu"]);
+    /// assert_eq!(log.tags(), ["billing", "eu"]);
+    /// assert!(log.to_string().ends_with(" tags=billing,eu"));
+    /// ```
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.fields.insert(TAGS_FIELD.to_string(), tags.join(","));
+        self
+    }
+
+    /// Returns the tags attached with `Log::with_tags`.
+    pub fn tags(&self) -> Vec<&str> {
+        self.fields
+            .get(TAGS_FIELD)
+            .map(|tags| split_tags(tags).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns a copy of the log entry with the process ID and the name
+    /// of the current thread attached.
+    ///
+    /// The `pid` field holds `std::process::id()` and is rendered as a
+    /// number in JSON formats; the `thread` field holds the thread name,
+    /// or `unnamed` for threads without one. `log()` applies this
+    /// automatically when `Config::include_process_context` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF)
+    ///     .with_process_context();
+    /// assert_eq!(log.fields["pid"], std::process::id().to_string());
+    /// ```
+    pub fn with_process_context(&self) -> Log {
+        let thread = std::thread::current();
+        self.clone()
+            .with_field("pid", &std::process::id().to_string())
+            .with_field("thread", thread.name().unwrap_or("unnamed"))
+    }
+
+    /// Returns a copy of the log entry with the hostname of the machine
+    /// attached as the `hostname` field.
+    ///
+    /// The entry is returned unchanged if the hostname cannot be
+    /// determined. `log()` applies this automatically when
+    /// `Config::include_hostname` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    ///
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &LogFormat::CLF)
+    ///     .with_hostname();
+    /// assert!(log.to_string().contains(" hostname="));
+    /// ```
+    pub fn with_hostname(&self) -> Log {
+        match hostname::get() {
+            Ok(host) => self
+                .clone()
+                .with_field("hostname", &host.to_string_lossy()),
+            Err(_) => self.clone(),
+        }
+    }
+
+    /// Returns a copy of the log entry with sensitive values masked.
+    ///
+    /// Every substring of `description` or of an extra field value that
+    /// matches one of the regular expressions in `patterns` is replaced
+    /// with `[REDACTED]`. Patterns that are not valid regular expressions
+    /// are ignored; `Config::validate` rejects them up front.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log::Log;
+    ///
+    /// let log = Log::default()
+    ///     .with_field("email", "jane@example.com");
+    /// let log = Log {
+    ///     description: "password=hunter2 accepted".to_string(),
+    ///     ..log
+    /// };
+    ///
+    /// let redacted = log.redact(&[r"password=\S+", r"[\w.+-]+@[\w-]+\.[\w.]+"]);
+    /// assert_eq!(redacted.description, "[REDACTED] accepted");
+    /// assert_eq!(redacted.fields["email"], "[REDACTED]");
+    /// ```
+    pub fn redact(&self, patterns: &[&str]) -> Log {
+        let regexes: Vec<Regex> = {
+            let mut cache = REDACT_PATTERNS.lock();
+            patterns
+                .iter()
+                .filter_map(|pattern| {
+                    cache
+                        .entry((*pattern).to_string())
+                        .or_insert_with(|| Regex::new(pattern).ok())
+                        .clone()
+                })
+                .collect()
+        };
+        let mask = |text: &str| {
+            regexes.iter().fold(text.to_string(), |text, regex| {
+                regex.replace_all(&text, REDACTED).into_owned()
+            })
+        };
+
+        Log {
+            description: mask(&self.description),
+            fields: self
+                .fields
+                .iter()
+                .map(|(key, value)| (key.clone(), mask(value)))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the log entry whose description is at most `max`
+    /// bytes long.
+    ///
+    /// Longer descriptions are cut at a character boundary and end with
+    /// `…[truncated]`, the marker counting towards `max`. If `max` is too
+    /// small to hold the marker, the description is cut without it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log::Log;
+    ///
+    /// let log = Log {
+    ///     description: "x".repeat(100),
+    ///     ..Log::default()
+    /// };
+    /// let truncated = log.ensure_max_description(32);
+    /// assert_eq!(truncated.description.len(), 32);
+    /// assert!(truncated.description.ends_with("…[truncated]"));
+    /// ```
+    pub fn ensure_max_description(&self, max: usize) -> Log {
+        if self.description.len() <= max {
+            return self.clone();
+        }
+        let (limit, marker) = match max.checked_sub(TRUNCATED.len()) {
+            Some(limit) => (limit, TRUNCATED),
+            None => (max, ""),
+        };
+        let end = self
+            .description
+            .char_indices()
+            .map(|(index, _)| index)
+            .take_while(|&index| index <= limit)
+            .last()
+            .unwrap_or(0);
+
+        let mut description = String::with_capacity(end + marker.len());
+        description.push_str(&self.description[..end]);
+        description.push_str(marker);
+        Log {
+            description,
+            ..self.clone()
+        }
+    }
+
+    /// Renders the session ID and extra fields as an RFC 5424
+    /// structured-data element.
+    fn rfc5424_structured_data(&self) -> String {
+        let mut element = format!("[{} sessionID=\"", RFC5424_SD_ID);
+        push_rfc5424_param_value(&mut element, &self.session_id);
+        element.push('"');
+        for (key, value) in &self.fields {
+            let name: String = key
+                .chars()
+                .filter(|c| {
+                    c.is_ascii_graphic()
+                        && !matches!(c, '=' | ']' | '"')
+                })
+                .take(32)
+                .collect();
+            if name.is_empty() {
+                continue;
+            }
+            element.push(' ');
+            element.push_str(&name);
+            element.push_str("=\"");
+            push_rfc5424_param_value(&mut element, value);
+            element.push('"');
+        }
+        element.push(']');
+        element
+    }
+
+    /// Renders the extra fields as `key=value` pairs for text formats, each
+    /// preceded by `separator`.
+    fn text_fields(&self, separator: char) -> String {
+        self.fields
+            .iter()
+            .map(|(key, value)| {
+                let key = match key.as_str() {
+                    TAGS_FIELD => TAGS_TEXT_KEY,
+                    key => key,
+                };
+                format!("{}{}={}", separator, key, value)
+            })
+            .collect()
+    }
+
+    /// Renders the sequence number as a trailing `seq=<n>` pair preceded by
+    /// `separator`, if set.
+    fn text_sequence(&self, separator: char) -> String {
+        self.sequence
+            .map(|seq| format!("{}seq={}", separator, seq))
+            .unwrap_or_default()
+    }
+
+    /// Renders the sequence number as an additional member `key` of a
+    /// JSON object, if set.
+    fn json_sequence(&self, key: &str, pretty: bool) -> String {
+        match self.sequence {
+            Some(seq) if pretty => {
+                format!(",\n                    \"{}\": {}", key, seq)
+            }
+            Some(seq) => format!(",\"{}\":{}", key, seq),
+            None => String::new(),
+        }
+    }
+
+    /// Renders the extra fields as additional members of a JSON object.
+    fn json_fields(&self, pretty: bool) -> String {
+        self.fields
+            .iter()
+            .map(|(key, value)| {
+                let value = json_field_value(key, value);
+                let key = serde_json::Value::from(key.as_str());
+                if pretty {
+                    format!(",\n                    {}: {}", key, value)
+                } else {
+                    format!(",{}:{}", key, value)
+                }
+            })
+            .collect()
+    }
+
+    /// Builds an OpenTelemetry log record in the OTLP JSON encoding.
+    ///
+    /// The `trace_id` and `span_id` fields, as set by
+    /// `macro_log_with_span!`, become the record's `traceId` and `spanId`;
+    /// the session ID, component and remaining fields become attributes.
+    fn otel_record(&self) -> serde_json::Value {
+        let time_unix_nano =
+            chrono::DateTime::parse_from_rfc3339(&self.time)
+                .ok()
+                .and_then(|time| time.timestamp_nanos_opt())
+                .unwrap_or(0);
+        let attribute = |key: &str, value: &str| {
+            serde_json::json!({
+                "key": key,
+                "value": { "stringValue": value },
+            })
+        };
+        let mut attributes = vec![
+            attribute("session_id", &self.session_id),
+            attribute("component", &self.component),
+        ];
+        attributes.extend(
+            self.fields
+                .iter()
+                .filter(|(key, _)| {
+                    !matches!(key.as_str(), "trace_id" | "span_id")
+                })
+                .map(|(key, value)| attribute(key, value)),
+        );
+
+        let mut record = serde_json::json!({
+            "timeUnixNano": time_unix_nano.to_string(),
+            "severityNumber": self.level.to_syslog_priority(),
+            "severityText": self.level.to_string(),
+            "body": { "stringValue": self.description },
+            "attributes": attributes,
+        });
+        for (field, key) in
+            [("trace_id", "traceId"), ("span_id", "spanId")]
+        {
+            if let Some(id) = self.fields.get(field) {
+                record[key] = serde_json::Value::from(id.as_str());
+            }
+        }
+        record
+    }
+
+    /// Builds a Fluentd forward protocol entry, `[tag, time, record]`.
+    ///
+    /// The tag is the component and the time is in Unix seconds, or 0 if
+    /// the entry's time cannot be parsed. The record holds the level,
+    /// session ID and description, followed by the extra fields.
+    fn fluentd_entry(&self) -> serde_json::Value {
+        let time = parse_datetime(&self.time)
+            .map_or(0, |time| time.unix_timestamp());
+        let mut record = serde_json::Map::new();
+        record.insert(
+            "level".to_string(),
+            serde_json::Value::from(self.level.to_string()),
+        );
+        record.insert(
+            "session_id".to_string(),
+            serde_json::Value::from(self.session_id.as_str()),
+        );
+        record.insert(
+            "message".to_string(),
+            serde_json::Value::from(self.description.as_str()),
+        );
+        for (key, value) in &self.fields {
+            record.insert(key.clone(), json_field_value(key, value));
         }
+        serde_json::json!([self.component, time, record])
     }
 
     /// Writes a log entry to the log file using the provided details.
@@ -163,7 +2008,7 @@ pub async fn write_log_entry(
         message: &str,
         log_format: LogFormat,
     ) -> RlgResult<()> {
-        let config = Config::load_async(None::<&str>).await?;
+        let config = Config::load_async(None::<&str>, None).await?;
 
         // Open or create the log file
         let log_file_path = config.read().log_file_path.clone();
@@ -219,33 +2064,271 @@ pub async fn write_log_entry(
     }
 }
 
+/// A builder for `Log` entries.
+///
+/// Unset fields fall back to sensible defaults when `build()` is called:
+/// the level defaults to `LogLevel::INFO`, the format to `LogFormat::CLF`,
+/// and the session ID and timestamp are generated automatically.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LogBuilder {
+    session_id: Option<String>,
+    time: Option<String>,
+    level: Option<LogLevel>,
+    component: Option<String>,
+    description: Option<String>,
+    format: Option<LogFormat>,
+}
+
+impl LogBuilder {
+    /// Sets the session ID of the log entry.
+    pub fn session_id(&mut self, session_id: &str) -> &mut LogBuilder {
+        self.session_id = Some(session_id.to_string());
+        self
+    }
+
+    /// Sets the timestamp of the log entry.
+    pub fn time(&mut self, time: &str) -> &mut LogBuilder {
+        self.time = Some(time.to_string());
+        self
+    }
+
+    /// Sets the log level of the log entry.
+    pub fn level(&mut self, level: LogLevel) -> &mut LogBuilder {
+        self.level = Some(level);
+        self
+    }
+
+    /// Sets the component that generated the log entry.
+    pub fn component(&mut self, component: &str) -> &mut LogBuilder {
+        self.component = Some(component.to_string());
+        self
+    }
+
+    /// Sets the description of the log entry.
+    pub fn description(
+        &mut self,
+        description: &str,
+    ) -> &mut LogBuilder {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets the format of the log entry.
+    pub fn format(&mut self, format: LogFormat) -> &mut LogBuilder {
+        self.format = Some(format);
+        self
+    }
+
+    /// Builds the `Log` entry, filling in defaults for unset fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `RlgError` if the component or description is missing
+    /// or empty, or if the session ID or timestamp was explicitly set to
+    /// an empty string.
+    pub fn build(&self) -> RlgResult<Log> {
+        let session_id = match &self.session_id {
+            Some(session_id) => required("session_id", session_id)?,
+            None => Random::default().int(0, 1_000_000_000).to_string(),
+        };
+        let time = match &self.time {
+            Some(time) => required("time", time)?,
+            None => generate_timestamp(),
+        };
+        let component = required(
+            "component",
+            self.component.as_deref().unwrap_or_default(),
+        )?;
+        let description = required(
+            "description",
+            self.description.as_deref().unwrap_or_default(),
+        )?;
+
+        Ok(Log {
+            session_id,
+            time,
+            level: self.level.unwrap_or(LogLevel::INFO),
+            component,
+            description,
+            format: self.format.clone().unwrap_or(LogFormat::CLF),
+            fields: BTreeMap::new(),
+            sequence: None,
+        })
+    }
+}
+
+/// The fields of the CLF output of a `Log`, as returned by
+/// `Log::into_clf_parts`.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ClfParts {
+    /// The value of the `SessionID` field.
+    pub session_id: String,
+    /// The value of the `Timestamp` field.
+    pub timestamp: String,
+    /// The value of the `Description` field.
+    pub description: String,
+    /// The value of the `Level` field.
+    pub level: String,
+    /// The value of the `Component` field.
+    pub component: String,
+}
+
+impl ClfParts {
+    /// Creates a log entry in `format` from the parts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` if `level` is not a valid log
+    /// level.
+    pub fn to_log(&self, format: LogFormat) -> RlgResult<Log> {
+        Ok(Log::new(
+            &self.session_id,
+            &self.timestamp,
+            &parse_level(&self.level)?,
+            &self.component,
+            &self.description,
+            &format,
+        ))
+    }
+}
+
+/// Ensures a mandatory builder field is not empty.
+fn required(field: &str, value: &str) -> RlgResult<String> {
+    if value.trim().is_empty() {
+        return Err(RlgError::custom(format!(
+            "Log field '{}' cannot be empty",
+            field
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// Formats a timestamp for an RFC 5424 header.
+///
+/// Fractional seconds are truncated to the six digits RFC 5424 allows, and
+/// timestamps that are not RFC 3339 are replaced by the NILVALUE `-`.
+fn rfc5424_timestamp(time: &str) -> String {
+    match RFC3339_REGEX.captures(time) {
+        Some(caps) => {
+            let fraction =
+                caps.name("fraction").map_or("", |fraction| {
+                    &fraction.as_str()[..fraction.as_str().len().min(7)]
+                });
+            format!(
+                "{}{}{}",
+                &caps["datetime"], fraction, &caps["offset"]
+            )
+        }
+        None => "-".to_string(),
+    }
+}
+
+/// Formats a value for an RFC 5424 header field.
+///
+/// Characters outside printable US-ASCII are dropped, the value is limited
+/// to `max_len` characters, and empty values become the NILVALUE `-`.
+fn rfc5424_header_field(value: &str, max_len: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(char::is_ascii_graphic)
+        .take(max_len)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+/// Appends `value` to `buffer`, escaping it as an RFC 5424 parameter value.
+fn push_rfc5424_param_value(buffer: &mut String, value: &str) {
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            buffer.push('\\');
+        }
+        buffer.push(c);
+    }
+}
+
+/// Converts an extra field to a JSON value.
+///
+/// The `pid` field set by `Log::with_process_context` is a number and the
+/// tags set by `Log::with_tags` are an array of strings; every other field
+/// is a string.
+fn json_field_value(key: &str, value: &str) -> serde_json::Value {
+    match (key, value.parse::<u32>()) {
+        ("pid", Ok(pid)) => serde_json::Value::from(pid),
+        (TAGS_FIELD, _) => serde_json::Value::from(
+            split_tags(value).collect::<Vec<_>>(),
+        ),
+        _ => serde_json::Value::from(value),
+    }
+}
+
+/// Converts a JSON member back to an extra field, reversing
+/// `json_field_value`.
+fn json_field_string(key: &str, value: &serde_json::Value) -> String {
+    match (key, value) {
+        (_, serde_json::Value::String(value)) => value.clone(),
+        (TAGS_FIELD, serde_json::Value::Array(tags))
+            if tags.iter().all(serde_json::Value::is_string) =>
+        {
+            tags.iter()
+                .filter_map(serde_json::Value::as_str)
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+        (_, other) => other.to_string(),
+    }
+}
+
+/// Splits the comma-separated tags of the `_tags` field.
+fn split_tags(tags: &str) -> impl Iterator<Item = &str> {
+    tags.split(',').filter(|tag| !tag.is_empty())
+}
+
+/// Quotes a value as an RFC 4180 field, doubling inner double quotes.
+fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// Parses a log level, reporting failures as format parse errors.
+fn parse_level(level: &str) -> RlgResult<LogLevel> {
+    LogLevel::from_str(level).map_err(|e| {
+        RlgError::FormatParseError(format!(
+            "Invalid log level in log entry: {}",
+            e
+        ))
+    })
+}
+
 impl fmt::Display for Log {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.format {
+        match &self.format {
             LogFormat::CLF => write!(
                 f,
-                "SessionID={} Timestamp={} Description={} Level={} Component={}",
-                self.session_id, self.time, self.description, self.level, self.component
+                "SessionID={} Timestamp={} Description={} Level={} Component={}{}{}",
+                self.session_id, self.time, self.description, self.level, self.component, self.text_fields(' '), self.text_sequence(' ')
             ),
             LogFormat::JSON => write!(
                 f,
-                "{{\"SessionID\":\"{}\",\"Timestamp\":\"{}\",\"Level\":\"{}\",\"Component\":\"{}\",\"Description\":\"{}\",\"Format\":\"JSON\"}}",
-                self.session_id, self.time, self.level, self.component, self.description
+                "{{\"SessionID\":\"{}\",\"Timestamp\":\"{}\",\"Level\":\"{}\",\"Component\":\"{}\",\"Description\":\"{}\",\"Format\":\"JSON\"{}{}}}",
+                self.session_id, self.time, self.level, self.component, self.description, self.json_fields(false), self.json_sequence("Sequence", false)
             ),
             LogFormat::CEF => write!(
                 f,
-                "CEF:0|{}|{}|{}|{}|{}|CEF",
-                self.session_id, self.time, self.level, self.component, self.description
+                "CEF:0|{}|{}|{}|{}|{}|CEF{}",
+                self.session_id, self.time, self.level, self.component, self.description, self.text_fields(' ')
             ),
             LogFormat::ELF => write!(
                 f,
-                "ELF:0|{}|{}|{}|{}|{}|ELF",
-                self.session_id, self.time, self.level, self.component, self.description
+                "ELF:0|{}|{}|{}|{}|{}|ELF{}",
+                self.session_id, self.time, self.level, self.component, self.description, self.text_fields(' ')
             ),
             LogFormat::W3C => write!(
                 f,
-                "W3C:0|{}|{}|{}|{}|{}|W3C",
-                self.session_id, self.time, self.level, self.component, self.description
+                "W3C:0|{}|{}|{}|{}|{}|W3C{}",
+                self.session_id, self.time, self.level, self.component, self.description, self.text_fields(' ')
             ),
             LogFormat::GELF => write!(
                 f,
@@ -256,18 +2339,19 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                     "level": "{:?}",
                     "timestamp": "{}",
                     "component": "{}",
-                    "session_id": "{}"
+                    "session_id": "{}"{}{}
                 }}"#,
-                self.component, self.description, self.level, self.time, self.component, self.session_id
+                self.component, self.description, self.level, self.time, self.component, self.session_id, self.json_fields(true), self.json_sequence("sequence", true)
             ),
             LogFormat::ApacheAccessLog => write!(
                 f,
-                "{} - - [{}] \"{}\" {} {}",
+                "{} - - [{}] \"{}\" {} {}{}",
                 hostname::get().map_err(|_| fmt::Error)?.to_string_lossy(),
                 self.time,
                 self.description,
                 self.level,
-                self.component
+                self.component,
+                self.text_fields(' ')
             ),
             LogFormat::Logstash => write!(
                 f,
@@ -275,9 +2359,9 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                     "@timestamp": "{}",
                     "level": "{}",
                     "component": "{}",
-                    "message": "{}"
+                    "message": "{}"{}{}
                 }}"#,
-                self.time, self.level, self.component, self.description
+                self.time, self.level, self.component, self.description, self.json_fields(true), self.json_sequence("sequence", true)
             ),
             LogFormat::Log4jXML => write!(
                 f,
@@ -290,10 +2374,42 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                     "timestamp": "{}",
                     "level": "{}",
                     "component": "{}",
-                    "message": "{}"
+                    "message": "{}"{}
                 }}"#,
-                self.time, self.level, self.component, self.description
+                self.time, self.level, self.component, self.description, self.json_fields(true)
+            ),
+            LogFormat::RFC5424 => write!(
+                f,
+                "<{}>1 {} {} {} {} - {} {}",
+                RFC5424_FACILITY * 8 + self.level.to_syslog_priority(),
+                rfc5424_timestamp(&self.time),
+                rfc5424_header_field(
+                    &hostname::get()
+                        .map(|host| host.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    255
+                ),
+                rfc5424_header_field(&self.component, 48),
+                std::process::id(),
+                self.rfc5424_structured_data(),
+                self.description
+            ),
+            LogFormat::CSV => write!(
+                f,
+                "{},{},{},{},{}",
+                csv_field(&self.session_id),
+                csv_field(&self.time),
+                csv_field(&self.level.to_string()),
+                csv_field(&self.component),
+                csv_field(&self.description)
             ),
+            LogFormat::OpenTelemetry => {
+                write!(f, "{}", self.otel_record())
+            }
+            LogFormat::Fluentd => {
+                write!(f, "{}", self.fluentd_entry())
+            }
+            LogFormat::Custom(render) => f.write_str(&render(self)),
         }
     }
 }
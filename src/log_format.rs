@@ -4,18 +4,57 @@
 // SPDX-License-Identifier: MIT
 
 use crate::error::{RlgError, RlgResult};
+use crate::log::Log;
 use crate::utils::sanitize_log_message;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+use std::sync::Arc;
+
+/// The named fields of a CLF line, in order, each with the pattern
+/// matching it and the separator before it.
+const CLF_FIELD_PATTERNS: [(&str, &str); 9] = [
+    ("host", r"(?P<host>\S+)"),
+    ("ident", r" (?P<ident>\S+)"),
+    ("user", r" (?P<user>\S+)"),
+    ("time", r" \[(?P<time>[^\]]+)\]"),
+    ("method", r#" "(?P<method>\S+)"#),
+    ("path", r" (?P<path>\S+)"),
+    ("protocol", r#" (?P<protocol>\S+)""#),
+    ("status", r" (?P<status>\d{3})"),
+    ("size", r" (?P<size>\d+|-)"),
+];
+
+/// The fields of a CEF header, in order.
+const CEF_HEADER_FIELDS: [&str; 7] = [
+    "version",
+    "device vendor",
+    "device product",
+    "device version",
+    "signature ID",
+    "name",
+    "severity",
+];
 
 /// Compiled regular expressions for log format validation.
 static CLF_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-    r#"^(?P<host>\S+) (?P<ident>\S+) (?P<user>\S+) \[(?P<time>[^\]]+)\] "(?P<method>\S+) (?P<path>\S+) (?P<protocol>\S+)" (?P<status>\d{3}) (?P<size>\d+|-)$"#
-).unwrap()
+    Regex::new(&format!("^{}$", clf_pattern(CLF_FIELD_PATTERNS.len())))
+        .unwrap()
+});
+
+/// Matches the first `n + 1` CLF fields at index `n`, each ending at
+/// whitespace or at the end of the line.
+static CLF_PREFIX_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    (1..=CLF_FIELD_PATTERNS.len())
+        .map(|fields| {
+            Regex::new(&format!(r"^{}(?:\s|$)", clf_pattern(fields)))
+                .unwrap()
+        })
+        .collect()
 });
 
 static CEF_REGEX: Lazy<Regex> = Lazy::new(|| {
@@ -33,6 +72,28 @@
     .unwrap()
 });
 
+static RFC5424_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^<(?:\d|[1-9]\d|1[0-8]\d|19[01])>[1-9]\d{0,2} (?:-|\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d{1,6})?(?:Z|[+-]\d{2}:\d{2})) [!-~]{1,255} [!-~]{1,48} [!-~]{1,128} [!-~]{1,32} (?:-|(?:\[[!#-<>-\\^-~]{1,32}(?: [!#-<>-\\^-~]{1,32}="(?:[^"\\\]]|\\["\\\]])*")*\])+)(?: .*)?$"#,
+    )
+    .unwrap()
+});
+
+/// Column names of the CSV format.
+const CSV_HEADER: &str = "session_id,time,level,component,description";
+
+/// Number of columns in a CSV log entry.
+const CSV_FIELD_COUNT: usize = 5;
+
+/// Keys every OpenTelemetry log record written by `Log` contains.
+const OTEL_REQUIRED_KEYS: [&str; 5] = [
+    "timeUnixNano",
+    "severityNumber",
+    "severityText",
+    "body",
+    "attributes",
+];
+
 /// An enumeration of the different log formats that can be used.
 ///
 /// # Variants
@@ -46,6 +107,15 @@
 /// * `Logstash` - Logstash JSON format.
 /// * `Log4jXML` - Log4j's XML format.
 /// * `NDJSON` - Newline Delimited JSON.
+/// * `RFC5424` - The Syslog Protocol (RFC 5424).
+/// * `CSV` - Comma-Separated Values (RFC 4180).
+/// * `OpenTelemetry` - OpenTelemetry log records (OTLP JSON).
+/// * `Fluentd` - Fluentd forward protocol entries (JSON).
+/// * `Custom` - A user-defined format, rendered by a function.
+///
+/// Because of `Custom`, `LogFormat` is `Clone` but not `Copy`. Two custom
+/// formats are equal only if they share the same function, and custom
+/// formats are neither serialized nor deserialized.
 ///
 /// # Examples
 /// ```
@@ -54,18 +124,7 @@
 /// assert_eq!(format, LogFormat::CLF);
 /// ```
 #[non_exhaustive]
-#[derive(
-    Clone,
-    Copy,
-    Debug,
-    Deserialize,
-    Eq,
-    Hash,
-    Ord,
-    PartialEq,
-    PartialOrd,
-    Serialize,
-)]
+#[derive(Clone, Deserialize, Serialize)]
 pub enum LogFormat {
     /// Common Log Format.
     CLF,
@@ -87,6 +146,22 @@ pub enum LogFormat {
     Log4jXML,
     /// Newline Delimited JSON.
     NDJSON,
+    /// The Syslog Protocol (RFC 5424).
+    RFC5424,
+    /// Comma-Separated Values (RFC 4180), with the columns named by
+    /// `LogFormat::header`. Extra log fields are not included.
+    CSV,
+    /// OpenTelemetry log records in the OTLP JSON encoding, with the
+    /// `timeUnixNano`, `severityNumber`, `severityText`, `body` and
+    /// `attributes` keys.
+    OpenTelemetry,
+    /// Fluentd forward protocol entries, as `[tag, time, record]` JSON
+    /// arrays with the component as tag and the time in Unix seconds.
+    Fluentd,
+    /// A user-defined format: the function renders each log entry. See
+    /// `LogFormat::new_custom`.
+    #[serde(skip)]
+    Custom(Arc<dyn Fn(&Log) -> String + Send + Sync>),
 }
 
 impl FromStr for LogFormat {
@@ -104,6 +179,10 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "logstash" => Ok(LogFormat::Logstash),
             "log4jxml" => Ok(LogFormat::Log4jXML),
             "ndjson" => Ok(LogFormat::NDJSON),
+            "rfc5424" => Ok(LogFormat::RFC5424),
+            "csv" => Ok(LogFormat::CSV),
+            "opentelemetry" | "otel" => Ok(LogFormat::OpenTelemetry),
+            "fluentd" => Ok(LogFormat::Fluentd),
             _ => Err(RlgError::FormatParseError(format!(
                 "Unknown log format: {}",
                 s
@@ -113,6 +192,73 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
 }
 
 impl LogFormat {
+    /// Creates a custom format that renders each log entry with `render`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+    /// let format = LogFormat::new_custom(|log: &Log| {
+    ///     format!("{}: {}", log.component, log.description)
+    /// });
+    /// let log = Log::new("id", "2024-01-01", &LogLevel::INFO, "api", "request", &format);
+    /// assert_eq!(log.to_string(), "api: request");
+    /// ```
+    pub fn new_custom(
+        render: impl Fn(&Log) -> String + Send + Sync + 'static,
+    ) -> LogFormat {
+        LogFormat::Custom(Arc::new(render))
+    }
+
+    /// Returns whether entries in this format can be written to log files
+    /// and sinks, one line per entry.
+    pub(crate) fn is_line_format(&self) -> bool {
+        matches!(
+            self,
+            LogFormat::CLF
+                | LogFormat::JSON
+                | LogFormat::CEF
+                | LogFormat::RFC5424
+                | LogFormat::CSV
+                | LogFormat::OpenTelemetry
+                | LogFormat::Fluentd
+                | LogFormat::Custom(_)
+        )
+    }
+
+    /// Returns the position of the variant in declaration order and its
+    /// name, used to compare, hash and debug-print formats.
+    fn variant(&self) -> (u8, &'static str) {
+        match self {
+            LogFormat::CLF => (0, "CLF"),
+            LogFormat::JSON => (1, "JSON"),
+            LogFormat::CEF => (2, "CEF"),
+            LogFormat::ELF => (3, "ELF"),
+            LogFormat::W3C => (4, "W3C"),
+            LogFormat::GELF => (5, "GELF"),
+            LogFormat::ApacheAccessLog => (6, "ApacheAccessLog"),
+            LogFormat::Logstash => (7, "Logstash"),
+            LogFormat::Log4jXML => (8, "Log4jXML"),
+            LogFormat::NDJSON => (9, "NDJSON"),
+            LogFormat::RFC5424 => (10, "RFC5424"),
+            LogFormat::CSV => (11, "CSV"),
+            LogFormat::OpenTelemetry => (12, "OpenTelemetry"),
+            LogFormat::Fluentd => (13, "Fluentd"),
+            LogFormat::Custom(_) => (14, "Custom"),
+        }
+    }
+
+    /// Returns the address of the rendering function of a custom format,
+    /// which identifies it, or 0 for the built-in formats.
+    fn render_address(&self) -> usize {
+        match self {
+            LogFormat::Custom(render) => {
+                Arc::as_ptr(render).cast::<()>() as usize
+            }
+            _ => 0,
+        }
+    }
+
     /// Validates if a given string adheres to a particular log format.
     ///
     /// # Arguments
@@ -131,25 +277,170 @@ impl LogFormat {
     /// assert!(is_valid);
     /// ```
     pub fn validate(&self, input: &str) -> bool {
+        self.validate_strict(input).is_ok()
+    }
+
+    /// Validates if a given string adheres to a particular log format,
+    /// explaining why it does not.
+    ///
+    /// This accepts the same entries as `validate`. On failure, the error
+    /// names the problem: the first CLF field that does not match, the
+    /// JSON parse error, the missing CEF delimiter, and so on.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RlgError::FormatParseError` with the reason if the input
+    /// does not match the log format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rlg::log_format::LogFormat;
+    /// let error = LogFormat::CLF
+    ///     .validate_strict("127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] \"GET / HTTP/1.0\" OK 2326")
+    ///     .unwrap_err();
+    /// assert!(error.to_string().contains("'status'"));
+    /// ```
+    pub fn validate_strict(&self, input: &str) -> RlgResult<()> {
+        let invalid = |reason: String| -> RlgResult<()> {
+            Err(RlgError::FormatParseError(format!(
+                "Invalid {} log entry: {}",
+                self, reason
+            )))
+        };
+        let parse_json = |input: &str| {
+            serde_json::from_str::<serde_json::Value>(input).map_err(
+                |e| {
+                    RlgError::FormatParseError(format!(
+                        "Invalid {} log entry: {}",
+                        self, e
+                    ))
+                },
+            )
+        };
         match self {
             LogFormat::CLF | LogFormat::ApacheAccessLog => {
-                CLF_REGEX.is_match(input)
+                if CLF_REGEX.is_match(input) {
+                    Ok(())
+                } else {
+                    invalid(clf_mismatch(input))
+                }
             }
             LogFormat::JSON
             | LogFormat::Logstash
-            | LogFormat::NDJSON => {
-                serde_json::from_str::<serde_json::Value>(input).is_ok()
+            | LogFormat::NDJSON
+            | LogFormat::GELF => parse_json(input).map(|_| ()),
+            LogFormat::CEF => {
+                if CEF_REGEX.is_match(input) {
+                    Ok(())
+                } else {
+                    invalid(cef_mismatch(input))
+                }
             }
-            LogFormat::CEF => CEF_REGEX.is_match(input),
             LogFormat::ELF | LogFormat::W3C => {
-                W3C_REGEX.is_match(input)
-            }
-            LogFormat::GELF => {
-                serde_json::from_str::<serde_json::Value>(input).is_ok()
+                if W3C_REGEX.is_match(input) {
+                    Ok(())
+                } else if !input.starts_with("#Fields:") {
+                    invalid(
+                        "missing the '#Fields:' directive".to_string(),
+                    )
+                } else {
+                    invalid(
+                        "expected a single entry line after the '#Fields:' directive"
+                            .to_string(),
+                    )
+                }
             }
             LogFormat::Log4jXML => {
-                input.trim_start().starts_with("<log4j:event")
+                if input.trim_start().starts_with("<log4j:event") {
+                    Ok(())
+                } else {
+                    invalid(
+                        "missing the '<log4j:event' element"
+                            .to_string(),
+                    )
+                }
+            }
+            LogFormat::RFC5424 => {
+                if RFC5424_REGEX.is_match(input) {
+                    Ok(())
+                } else if !input.starts_with('<') {
+                    invalid("missing the '<PRI>' priority".to_string())
+                } else {
+                    invalid(
+                        "the header or structured data does not follow RFC 5424"
+                            .to_string(),
+                    )
+                }
+            }
+            LogFormat::CSV => {
+                match parse_csv_record(input.trim_end_matches(['\r', '\n']))
+                {
+                    None => invalid(
+                        "a quoted field is unterminated or followed by text"
+                            .to_string(),
+                    ),
+                    Some(fields) if fields.len() != CSV_FIELD_COUNT => {
+                        invalid(format!(
+                            "expected {} fields, found {}",
+                            CSV_FIELD_COUNT,
+                            fields.len()
+                        ))
+                    }
+                    Some(_) => Ok(()),
+                }
             }
+            LogFormat::OpenTelemetry => {
+                let record = parse_json(input)?;
+                match OTEL_REQUIRED_KEYS
+                    .iter()
+                    .find(|key| record.get(key).is_none())
+                {
+                    Some(key) => {
+                        invalid(format!("missing the '{}' key", key))
+                    }
+                    None => Ok(()),
+                }
+            }
+            LogFormat::Fluentd => match parse_json(input)?.as_array() {
+                Some(entry)
+                    if matches!(
+                        entry.as_slice(),
+                        [tag, time, record]
+                            if tag.is_string()
+                                && time.is_number()
+                                && record.is_object()
+                    ) =>
+                {
+                    Ok(())
+                }
+                _ => invalid(
+                    "expected a [tag, time, record] array".to_string(),
+                ),
+            },
+            LogFormat::Custom(_) => Ok(()),
+        }
+    }
+
+    /// Returns the header line for formats that have one.
+    ///
+    /// Only `CSV` has a header, naming the columns written for each log
+    /// entry; every other format returns an empty string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rlg::log_format::LogFormat;
+    /// assert_eq!(
+    ///     LogFormat::CSV.header(),
+    ///     "session_id,time,level,component,description"
+    /// );
+    /// assert_eq!(LogFormat::JSON.header(), "");
+    /// ```
+    pub fn header(&self) -> &'static str {
+        match self {
+            LogFormat::CSV => CSV_HEADER,
+            _ => "",
         }
     }
 
@@ -177,11 +468,16 @@ pub fn format_log(&self, entry: &str) -> RlgResult<String> {
             | LogFormat::CEF
             | LogFormat::ELF
             | LogFormat::W3C
-            | LogFormat::Log4jXML => Ok(sanitized_entry),
+            | LogFormat::Log4jXML
+            | LogFormat::RFC5424
+            | LogFormat::CSV
+            | LogFormat::Custom(_) => Ok(sanitized_entry),
             LogFormat::JSON
             | LogFormat::Logstash
             | LogFormat::NDJSON
-            | LogFormat::GELF => serde_json::to_string_pretty(
+            | LogFormat::GELF
+            | LogFormat::OpenTelemetry
+            | LogFormat::Fluentd => serde_json::to_string_pretty(
                 &serde_json::from_str::<serde_json::Value>(
                     &sanitized_entry,
                 )
@@ -202,6 +498,99 @@ pub fn format_log(&self, entry: &str) -> RlgResult<String> {
     }
 }
 
+/// Returns the pattern matching the first `fields` fields of a CLF line.
+fn clf_pattern(fields: usize) -> String {
+    CLF_FIELD_PATTERNS[..fields]
+        .iter()
+        .map(|(_, pattern)| *pattern)
+        .collect()
+}
+
+/// Describes why a line is not a CLF entry, naming the first field that
+/// does not match.
+fn clf_mismatch(input: &str) -> String {
+    for ((name, _), prefix) in
+        CLF_FIELD_PATTERNS.iter().zip(CLF_PREFIX_REGEXES.iter())
+    {
+        if !prefix.is_match(input) {
+            return format!("the '{}' field does not match", name);
+        }
+    }
+    "unexpected text after the 'size' field".to_string()
+}
+
+/// Describes why a line is not a CEF entry.
+fn cef_mismatch(input: &str) -> String {
+    let header = match input.strip_prefix("CEF:") {
+        Some(header) => header,
+        None => return "missing the 'CEF:' prefix".to_string(),
+    };
+    let fields: Vec<&str> =
+        header.splitn(CEF_HEADER_FIELDS.len() + 1, '|').collect();
+    if fields.len() <= CEF_HEADER_FIELDS.len() {
+        return format!(
+            "missing the '|' delimiter after the {} field",
+            CEF_HEADER_FIELDS[fields.len() - 1]
+        );
+    }
+    if fields[0].is_empty()
+        || !fields[0].chars().all(|c| c.is_ascii_digit())
+    {
+        return format!("the version '{}' is not a number", fields[0]);
+    }
+    match CEF_HEADER_FIELDS
+        .iter()
+        .zip(&fields)
+        .find(|(_, field)| field.is_empty())
+    {
+        Some((name, _)) => format!("the {} field is empty", name),
+        None => "the extension contains a line break".to_string(),
+    }
+}
+
+/// Splits an RFC 4180 record into its fields.
+///
+/// Returns `None` if a quoted field is not terminated or is followed by
+/// anything other than a comma.
+fn parse_csv_record(input: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        let mut field = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '"' if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    '"' => break,
+                    c => field.push(c),
+                }
+            }
+            if !matches!(chars.peek(), None | Some(',')) {
+                return None;
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == ',' {
+                    break;
+                }
+                if c == '"' {
+                    return None;
+                }
+                field.push(c);
+                chars.next();
+            }
+        }
+        fields.push(field);
+        if chars.next().is_none() {
+            return Some(fields);
+        }
+    }
+}
+
 impl fmt::Display for LogFormat {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
@@ -215,11 +604,56 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             LogFormat::Logstash => "Logstash",
             LogFormat::Log4jXML => "Log4j XML",
             LogFormat::NDJSON => "NDJSON",
+            LogFormat::RFC5424 => "RFC5424",
+            LogFormat::CSV => "CSV",
+            LogFormat::OpenTelemetry => "OpenTelemetry",
+            LogFormat::Fluentd => "Fluentd",
+            LogFormat::Custom(_) => "Custom",
         };
         write!(f, "{}", s)
     }
 }
 
+impl fmt::Debug for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Custom(_) => f
+                .debug_tuple("Custom")
+                .field(&format_args!("{:#x}", self.render_address()))
+                .finish(),
+            _ => f.write_str(self.variant().1),
+        }
+    }
+}
+
+impl PartialEq for LogFormat {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for LogFormat {}
+
+impl PartialOrd for LogFormat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LogFormat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.variant().0, self.render_address())
+            .cmp(&(other.variant().0, other.render_address()))
+    }
+}
+
+impl Hash for LogFormat {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.variant().0.hash(state);
+        self.render_address().hash(state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
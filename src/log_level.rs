@@ -40,6 +40,10 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 impl Error for ParseLogLevelError {}
 
 /// An enumeration of the different levels that a log message can have, ordered by severity.
+///
+/// `SECURITY` and `AUDIT` sit above `CRITICAL`, so that security and audit
+/// events pass any threshold below them and can be filtered separately by
+/// SIEM tools.
 #[derive(
     Clone,
     Copy,
@@ -75,6 +79,12 @@ pub enum LogLevel {
     FATAL,
     /// `CRITICAL`: Critical conditions, often requiring immediate attention.
     CRITICAL,
+    /// `SECURITY`: Security-relevant events, such as failed authentication
+    /// or access violations, intended for SIEM tooling.
+    SECURITY,
+    /// `AUDIT`: Audit trail events that must always be recorded, such as
+    /// changes to permissions or configuration.
+    AUDIT,
 }
 
 impl LogLevel {
@@ -100,6 +110,51 @@ pub fn includes(self, other: LogLevel) -> bool {
         }
     }
 
+    /// Checks if the log level lies within the inclusive range from `min`
+    /// to `max`, comparing numeric values.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The lowest log level of the range.
+    /// * `max` - The highest log level of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// assert!(LogLevel::INFO.between(LogLevel::DEBUG, LogLevel::ERROR));
+    /// assert!(!LogLevel::FATAL.between(LogLevel::DEBUG, LogLevel::ERROR));
+    /// ```
+    pub fn between(self, min: LogLevel, max: LogLevel) -> bool {
+        self.to_numeric() >= min.to_numeric()
+            && self.to_numeric() <= max.to_numeric()
+    }
+
+    /// Restricts the log level to the inclusive range from `min` to
+    /// `max`, returning the nearest boundary when it lies outside.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The lowest log level of the range.
+    /// * `max` - The highest log level of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// assert_eq!(LogLevel::FATAL.clamp(LogLevel::DEBUG, LogLevel::ERROR), LogLevel::ERROR);
+    /// assert_eq!(LogLevel::INFO.clamp(LogLevel::DEBUG, LogLevel::ERROR), LogLevel::INFO);
+    /// ```
+    pub fn clamp(self, min: LogLevel, max: LogLevel) -> LogLevel {
+        if self.to_numeric() < min.to_numeric() {
+            min
+        } else if self.to_numeric() > max.to_numeric() {
+            max
+        } else {
+            self
+        }
+    }
+
     /// Converts the log level to its corresponding numeric value, similar to syslog severity levels.
     ///
     /// # Examples
@@ -122,6 +177,8 @@ pub fn to_numeric(self) -> u8 {
             LogLevel::ERROR => 8,
             LogLevel::FATAL => 9,
             LogLevel::CRITICAL => 10,
+            LogLevel::SECURITY => 11,
+            LogLevel::AUDIT => 12,
         }
     }
 
@@ -151,11 +208,172 @@ pub fn from_numeric(value: u8) -> Option<Self> {
             8 => Some(LogLevel::ERROR),
             9 => Some(LogLevel::FATAL),
             10 => Some(LogLevel::CRITICAL),
+            11 => Some(LogLevel::SECURITY),
+            12 => Some(LogLevel::AUDIT),
             _ => None,
         }
     }
+
+    /// Converts the log level to a syslog severity, from 0 (emergency) to
+    /// 7 (debug), as used in syslog and RFC 5424 priorities.
+    ///
+    /// Levels finer than `INFO`, and the `ALL`, `NONE` and `DISABLED`
+    /// markers, map to debug. `SECURITY` and `AUDIT` map to alert so that
+    /// syslog servers never filter them out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// assert_eq!(LogLevel::FATAL.to_syslog_priority(), 0);
+    /// assert_eq!(LogLevel::ERROR.to_syslog_priority(), 3);
+    /// assert_eq!(LogLevel::TRACE.to_syslog_priority(), 7);
+    /// ```
+    pub fn to_syslog_priority(self) -> u8 {
+        match self {
+            LogLevel::FATAL => 0,
+            LogLevel::SECURITY | LogLevel::AUDIT => 1,
+            LogLevel::CRITICAL => 2,
+            LogLevel::ERROR => 3,
+            LogLevel::WARN => 4,
+            LogLevel::INFO => 6,
+            LogLevel::ALL
+            | LogLevel::NONE
+            | LogLevel::DISABLED
+            | LogLevel::DEBUG
+            | LogLevel::TRACE
+            | LogLevel::VERBOSE => 7,
+        }
+    }
+
+    /// Creates a `LogLevel` from a syslog severity, from 0 (emergency) to
+    /// 7 (debug).
+    ///
+    /// Severities shared by several levels map to the most common one, and
+    /// notice (5) maps to `INFO`. Returns `None` for values above 7.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The syslog severity to convert.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// assert_eq!(LogLevel::from_syslog_priority(4), Some(LogLevel::WARN));
+    /// assert_eq!(LogLevel::from_syslog_priority(8), None);
+    /// ```
+    pub fn from_syslog_priority(priority: u8) -> Option<Self> {
+        match priority {
+            0 => Some(LogLevel::FATAL),
+            1 => Some(LogLevel::SECURITY),
+            2 => Some(LogLevel::CRITICAL),
+            3 => Some(LogLevel::ERROR),
+            4 => Some(LogLevel::WARN),
+            5 | 6 => Some(LogLevel::INFO),
+            7 => Some(LogLevel::DEBUG),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over every log level in ascending numeric order,
+    /// as defined by `to_numeric()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// let levels: Vec<LogLevel> = LogLevel::iter().collect();
+    /// assert_eq!(levels.first(), Some(&LogLevel::ALL));
+    /// assert_eq!(levels.last(), Some(&LogLevel::AUDIT));
+    /// ```
+    pub fn iter() -> impl Iterator<Item = LogLevel> {
+        (0..=u8::MAX)
+            .map(LogLevel::from_numeric)
+            .take_while(Option::is_some)
+            .flatten()
+    }
+
+    /// Returns the number of log levels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// assert_eq!(LogLevel::count(), LogLevel::iter().count());
+    /// ```
+    pub fn count() -> usize {
+        LogLevel::iter().count()
+    }
+
+    /// Returns the name of the level wrapped in ANSI color codes, for
+    /// terminal output.
+    ///
+    /// `FATAL` and `CRITICAL` are bold red, `ERROR` red, `WARN` yellow,
+    /// `INFO` green, `DEBUG`, `TRACE` and `VERBOSE` cyan, `SECURITY` and
+    /// `AUDIT` magenta, and `ALL`, `NONE` and `DISABLED` white. The codes
+    /// are always included; check `has_ansi_support()` first when the
+    /// output may not be a terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// assert_eq!(LogLevel::ERROR.display_ansi(), "\x1b[31mERROR\x1b[0m");
+    /// ```
+    pub fn display_ansi(&self) -> &'static str {
+        ANSI_LEVEL_NAMES[usize::from(self.to_numeric())]
+    }
+
+    /// Returns whether standard output supports ANSI colors.
+    ///
+    /// This is the case when stdout is a terminal, unless the `NO_COLOR`
+    /// or `CLICOLOR` environment variables disable colors;
+    /// `CLICOLOR_FORCE` and `colored::control::set_override` take
+    /// precedence.
+    pub fn has_ansi_support() -> bool {
+        colored::control::SHOULD_COLORIZE.should_colorize()
+    }
+
+    /// Returns the severity of the level as a number from `0.0` for
+    /// `ALL` to `1.0` for `CRITICAL`, for alerting thresholds.
+    ///
+    /// Levels in between are spaced linearly by their numeric value.
+    /// `SECURITY` and `AUDIT`, which rank above `CRITICAL` as categories
+    /// rather than severities, score `1.0` as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlg::log_level::LogLevel;
+    /// assert_eq!(LogLevel::ALL.base_severity(), 0.0);
+    /// assert_eq!(LogLevel::ERROR.base_severity(), 0.8);
+    /// assert_eq!(LogLevel::CRITICAL.base_severity(), 1.0);
+    /// ```
+    pub fn base_severity(self) -> f64 {
+        let critical = f64::from(LogLevel::CRITICAL.to_numeric());
+        (f64::from(self.to_numeric()) / critical).min(1.0)
+    }
 }
 
+/// The names of the log levels wrapped in ANSI color codes, indexed by
+/// `LogLevel::to_numeric`.
+const ANSI_LEVEL_NAMES: [&str; 13] = [
+    "\x1b[37mALL\x1b[0m",
+    "\x1b[37mNONE\x1b[0m",
+    "\x1b[37mDISABLED\x1b[0m",
+    "\x1b[36mDEBUG\x1b[0m",
+    "\x1b[36mTRACE\x1b[0m",
+    "\x1b[36mVERBOSE\x1b[0m",
+    "\x1b[32mINFO\x1b[0m",
+    "\x1b[33mWARN\x1b[0m",
+    "\x1b[31mERROR\x1b[0m",
+    "\x1b[1;31mFATAL\x1b[0m",
+    "\x1b[1;31mCRITICAL\x1b[0m",
+    "\x1b[35mSECURITY\x1b[0m",
+    "\x1b[35mAUDIT\x1b[0m",
+];
+
 impl FromStr for LogLevel {
     type Err = ParseLogLevelError;
 
@@ -168,10 +386,13 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "TRACE" => Ok(LogLevel::TRACE),
             "VERBOSE" => Ok(LogLevel::VERBOSE),
             "INFO" => Ok(LogLevel::INFO),
-            "WARN" => Ok(LogLevel::WARN),
+            // `WARNING` is the name used by Python's `logging`, among others.
+            "WARN" | "WARNING" => Ok(LogLevel::WARN),
             "ERROR" => Ok(LogLevel::ERROR),
             "FATAL" => Ok(LogLevel::FATAL),
             "CRITICAL" => Ok(LogLevel::CRITICAL),
+            "SECURITY" => Ok(LogLevel::SECURITY),
+            "AUDIT" => Ok(LogLevel::AUDIT),
             _ => Err(ParseLogLevelError::new(s)),
         }
     }
@@ -199,6 +420,8 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             LogLevel::ERROR => "ERROR",
             LogLevel::FATAL => "FATAL",
             LogLevel::CRITICAL => "CRITICAL",
+            LogLevel::SECURITY => "SECURITY",
+            LogLevel::AUDIT => "AUDIT",
         };
         write!(f, "{}", level_str)
     }
@@ -41,12 +41,80 @@ macro_rules! macro_log {
     };
 }
 
+/// This macro creates a log entry whose description starts with the source
+/// file and line number of the call site, such as `src/main.rs:42 message`.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, the timestamp is the current
+/// time, and the log format defaults to CLF.
+///
+/// # Parameters
+/// - `level`: The severity level of the log.
+/// - `component`: The system component that generated the log.
+/// - `description`: A textual description of the log event.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_log_here, log_level::LogLevel};
+/// let log = macro_log_here!(&LogLevel::DEBUG, "app", "cache miss");
+/// assert!(log.description.starts_with(file!()));
+/// ```
+/// Usage:
+/// let log = macro_log_here!(level, component, description);
+#[macro_export]
+#[doc = "Macro to create a log tagged with the caller's file and line"]
+macro_rules! macro_log_here {
+    ($level:expr, $component:expr, $description:expr) => {
+        $crate::log::Log::new(
+            &$crate::context::session_id_or_random(),
+            &$crate::utils::generate_timestamp(),
+            $level,
+            $component,
+            &format!("{}:{} {}", file!(), line!(), $description),
+            &$crate::log_format::LogFormat::CLF,
+        )
+    };
+}
+
+/// This macro asynchronously logs an error at the `ERROR` level.
+/// The description is built by `Log::new_from_error` from the error and
+/// its chain of sources. It returns the result of the logging operation.
+///
+/// # Parameters
+/// - `component`: The system component that generated the log.
+/// - `err`: The error to be logged.
+///
+/// # Example
+/// ```
+/// use rlg::macro_log_error;
+/// let err = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+/// async {
+/// let result = macro_log_error!("storage", err);
+/// };
+/// ```
+/// Usage:
+/// let result = macro_log_error!(component, err);
+#[macro_export]
+#[doc = "Async log an error and its sources"]
+macro_rules! macro_log_error {
+    ($component:expr, $err:expr) => {
+        $crate::log::Log::new_from_error(
+            &$err,
+            $crate::log_level::LogLevel::ERROR,
+            $component,
+        )
+        .log()
+        .await
+    };
+}
+
 /// This macro creates an `INFO` level log entry with a default session ID and format.
-/// The session ID is generated randomly and the log format defaults to CLF.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, and the log format defaults to CLF.
 ///
 /// # Parameters
 /// - `time`: The timestamp of the log entry.
-/// - `component`: The system component that generated the log.
+/// - `component`: The system component that generated the log. When
+///   omitted, the component of the current log context is used.
 /// - `description`: A textual description of the log event.
 ///
 /// # Example
@@ -56,14 +124,13 @@ macro_rules! macro_log {
 /// ```
 /// Usage:
 /// let log = macro_info_log!(time, component, description);
+/// let log = macro_info_log!(time, description);
 #[macro_export]
 #[doc = "Macro for info log with default session id and format"]
 macro_rules! macro_info_log {
     ($time:expr, $component:expr, $description:expr) => {
         $crate::log::Log::new(
-            &vrd::random::Random::default()
-                .int(0, 1_000_000_000)
-                .to_string(),
+            &$crate::context::session_id_or_random(),
             $time,
             &$crate::log_level::LogLevel::INFO,
             $component,
@@ -71,6 +138,16 @@ macro_rules! macro_info_log {
             &$crate::log_format::LogFormat::CLF,
         )
     };
+    ($time:expr, $description:expr) => {
+        $crate::log::Log::new(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::INFO,
+            &$crate::context::component_or_default(),
+            $description,
+            &$crate::log_format::LogFormat::CLF,
+        )
+    };
 }
 
 /// This macro asynchronously logs a message to a file.
@@ -101,12 +178,76 @@ macro_rules! macro_log_to_file {
     }};
 }
 
+/// This macro asynchronously writes a log entry to several sinks with
+/// `Log::log_to_all`.
+///
+/// It awaits the writes, so it can only be used in an async context.
+/// Destinations are either a bracketed list of sink references, boxes or
+/// `Arc`s, or an expression with an `iter()` method yielding them, such as
+/// a `Vec<Box<dyn LogSink>>`. An empty bracketed list is rejected at
+/// compile time.
+///
+/// # Parameters
+/// - `destinations`: The sinks to write the entry to.
+/// - `log`: The log entry to be written.
+///
+/// # Returns
+/// The result of `Log::log_to_all`.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_info_log, macro_log_to_multiple};
+/// use rlg::sink::{FileSink, StdoutSink};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let file = FileSink::new(dir.path().join("app.log"));
+/// let log = macro_info_log!("2022-01-01", "app", "message");
+/// macro_log_to_multiple!([&file, &StdoutSink::default()], log).unwrap();
+/// # });
+/// ```
+///
+/// ```compile_fail
+/// use rlg::{macro_info_log, macro_log_to_multiple};
+///
+/// let log = macro_info_log!("2022-01-01", "app", "message");
+/// async {
+///     let _ = macro_log_to_multiple!([], log);
+/// };
+/// ```
+/// Usage:
+/// let result = macro_log_to_multiple!([&sink1, &sink2], log);
+/// let result = macro_log_to_multiple!(sinks, log);
+#[macro_export]
+#[doc = "Async log message to several sinks"]
+macro_rules! macro_log_to_multiple {
+    ([], $log:expr) => {
+        compile_error!("macro_log_to_multiple! needs at least one destination")
+    };
+    ([$($sink:expr),+ $(,)?], $log:expr) => {{
+        let log = $log;
+        log.log_to_all(&[$(&*$sink as &dyn $crate::sink::LogSink),+])
+            .await
+    }};
+    ($destinations:expr, $log:expr) => {{
+        let log = $log;
+        let sinks: ::std::vec::Vec<&dyn $crate::sink::LogSink> =
+            $destinations
+                .iter()
+                .map(|sink| &**sink as &dyn $crate::sink::LogSink)
+                .collect();
+        log.log_to_all(&sinks).await
+    }};
+}
+
 /// This macro creates a `WARN` level log entry with a default session ID and format.
-/// The session ID is generated randomly and the log format defaults to CLF.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, and the log format defaults to CLF.
 ///
 /// # Parameters
 /// - `time`: The timestamp of the log entry.
-/// - `component`: The system component that generated the log.
+/// - `component`: The system component that generated the log. When
+///   omitted, the component of the current log context is used.
 /// - `description`: A textual description of the log event.
 ///
 /// # Example
@@ -118,14 +259,13 @@ macro_rules! macro_log_to_file {
 /// ```
 /// Usage:
 /// let log = macro_warn_log!(time, component, description);
+/// let log = macro_warn_log!(time, description);
 #[macro_export]
 #[doc = "Macro for warn log with default session id and format"]
 macro_rules! macro_warn_log {
     ($time:expr, $component:expr, $description:expr) => {
         $crate::macro_log!(
-            &vrd::random::Random::default()
-                .int(0, 1_000_000_000)
-                .to_string(),
+            &$crate::context::session_id_or_random(),
             $time,
             &$crate::log_level::LogLevel::WARN,
             $component,
@@ -133,14 +273,26 @@ macro_rules! macro_warn_log {
             &$crate::log_format::LogFormat::CLF
         )
     };
+    ($time:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::WARN,
+            &$crate::context::component_or_default(),
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
 }
 
 /// This macro creates an `ERROR` level log entry with a default session ID and format.
-/// The session ID is generated randomly and the log format defaults to CLF.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, and the log format defaults to CLF.
 ///
 /// # Parameters
 /// - `time`: The timestamp of the log entry.
-/// - `component`: The system component that generated the log.
+/// - `component`: The system component that generated the log. When
+///   omitted, the component of the current log context is used.
 /// - `description`: A textual description of the log event.
 ///
 /// # Example
@@ -152,14 +304,13 @@ macro_rules! macro_warn_log {
 /// ```
 /// Usage:
 /// let log = macro_error_log!(time, component, description);
+/// let log = macro_error_log!(time, description);
 #[macro_export]
 #[doc = "Macro for error log with default session id and format"]
 macro_rules! macro_error_log {
     ($time:expr, $component:expr, $description:expr) => {
         $crate::macro_log!(
-            &vrd::random::Random::default()
-                .int(0, 1_000_000_000)
-                .to_string(),
+            &$crate::context::session_id_or_random(),
             $time,
             &$crate::log_level::LogLevel::ERROR,
             $component,
@@ -167,14 +318,26 @@ macro_rules! macro_error_log {
             &$crate::log_format::LogFormat::CLF
         )
     };
+    ($time:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::ERROR,
+            &$crate::context::component_or_default(),
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
 }
 
 /// This macro creates a `TRACE` level log entry with a default session ID and format.
-/// The session ID is generated randomly and the log format defaults to CLF.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, and the log format defaults to CLF.
 ///
 /// # Parameters
 /// - `time`: The timestamp of the log entry.
-/// - `component`: The system component that generated the log.
+/// - `component`: The system component that generated the log. When
+///   omitted, the component of the current log context is used.
 /// - `description`: A textual description of the log event.
 ///
 /// # Example
@@ -186,14 +349,13 @@ macro_rules! macro_error_log {
 /// ```
 /// Usage:
 /// let log = macro_trace_log!(time, component, description);
+/// let log = macro_trace_log!(time, description);
 #[macro_export]
 #[doc = "Macro for trace log with default session id and format"]
 macro_rules! macro_trace_log {
     ($time:expr, $component:expr, $description:expr) => {
         $crate::macro_log!(
-            &vrd::random::Random::default()
-                .int(0, 1_000_000_000)
-                .to_string(),
+            &$crate::context::session_id_or_random(),
             $time,
             &$crate::log_level::LogLevel::TRACE,
             $component,
@@ -201,14 +363,26 @@ macro_rules! macro_trace_log {
             &$crate::log_format::LogFormat::CLF
         )
     };
+    ($time:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::TRACE,
+            &$crate::context::component_or_default(),
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
 }
 
 /// This macro creates a `FATAL` level log entry with a default session ID and format.
-/// The session ID is generated randomly and the log format defaults to CLF.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, and the log format defaults to CLF.
 ///
 /// # Parameters
 /// - `time`: The timestamp of the log entry.
-/// - `component`: The system component that generated the log.
+/// - `component`: The system component that generated the log. When
+///   omitted, the component of the current log context is used.
 /// - `description`: A textual description of the log event.
 ///
 /// # Example
@@ -220,14 +394,13 @@ macro_rules! macro_trace_log {
 /// ```
 /// Usage:
 /// let log = macro_fatal_log!(time, component, description);
+/// let log = macro_fatal_log!(time, description);
 #[macro_export]
 #[doc = "Macro for fatal log with default session id and format"]
 macro_rules! macro_fatal_log {
     ($time:expr, $component:expr, $description:expr) => {
         $crate::macro_log!(
-            &vrd::random::Random::default()
-                .int(0, 1_000_000_000)
-                .to_string(),
+            &$crate::context::session_id_or_random(),
             $time,
             &$crate::log_level::LogLevel::FATAL,
             $component,
@@ -235,6 +408,108 @@ macro_rules! macro_fatal_log {
             &$crate::log_format::LogFormat::CLF
         )
     };
+    ($time:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::FATAL,
+            &$crate::context::component_or_default(),
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
+}
+
+/// This macro creates a `CRITICAL` level log entry with a default session ID and format.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, and the log format defaults to CLF.
+///
+/// # Parameters
+/// - `time`: The timestamp of the log entry.
+/// - `component`: The system component that generated the log. When
+///   omitted, the component of the current log context is used.
+/// - `description`: A textual description of the log event.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_critical_log, macro_log};
+/// use rlg::log_level::LogLevel;
+/// use rlg::log_format::LogFormat;
+/// let log = macro_critical_log!("2024-08-29T12:00:00Z", "Storage", "Disk full");
+/// assert_eq!(log.level, LogLevel::CRITICAL);
+/// ```
+/// Usage:
+/// let log = macro_critical_log!(time, component, description);
+/// let log = macro_critical_log!(time, description);
+#[macro_export]
+#[doc = "Macro for critical log with default session id and format"]
+macro_rules! macro_critical_log {
+    ($time:expr, $component:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::CRITICAL,
+            $component,
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
+    ($time:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::CRITICAL,
+            &$crate::context::component_or_default(),
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
+}
+
+/// This macro creates a `VERBOSE` level log entry with a default session ID and format.
+/// The session ID is taken from the current log context (see
+/// `rlg::context`) or generated randomly, and the log format defaults to CLF.
+///
+/// # Parameters
+/// - `time`: The timestamp of the log entry.
+/// - `component`: The system component that generated the log. When
+///   omitted, the component of the current log context is used.
+/// - `description`: A textual description of the log event.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_verbose_log, macro_log};
+/// use rlg::log_level::LogLevel;
+/// use rlg::log_format::LogFormat;
+/// let log = macro_verbose_log!("2024-08-29T12:00:00Z", "Cache", "Cache entry refreshed");
+/// assert_eq!(log.level, LogLevel::VERBOSE);
+/// ```
+/// Usage:
+/// let log = macro_verbose_log!(time, component, description);
+/// let log = macro_verbose_log!(time, description);
+#[macro_export]
+#[doc = "Macro for verbose log with default session id and format"]
+macro_rules! macro_verbose_log {
+    ($time:expr, $component:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::VERBOSE,
+            $component,
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
+    ($time:expr, $description:expr) => {
+        $crate::macro_log!(
+            &$crate::context::session_id_or_random(),
+            $time,
+            &$crate::log_level::LogLevel::VERBOSE,
+            &$crate::context::component_or_default(),
+            $description,
+            &$crate::log_format::LogFormat::CLF
+        )
+    };
 }
 
 // ========================
@@ -305,6 +580,77 @@ macro_rules! macro_log_with_metadata {
     }};
 }
 
+/// This macro attaches distributed tracing IDs to a log entry.
+/// The W3C TraceContext `trace_id` and `span_id` are added as extra fields,
+/// so they appear as top-level keys in JSON output and as `key=value`
+/// pairs in CLF output.
+///
+/// # Parameters
+/// - `span_id`: The ID of the current span.
+/// - `trace_id`: The ID of the trace the span belongs to.
+/// - `log`: The log entry to correlate with the span.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_info_log, macro_log_with_span};
+/// let log = macro_info_log!("2022-01-01", "app", "message");
+/// let log = macro_log_with_span!(
+///     "00f067aa0ba902b7",
+///     "4bf92f3577b34da6a3ce929d0e0e4736",
+///     log
+/// );
+/// assert_eq!(log.fields["span_id"], "00f067aa0ba902b7");
+/// ```
+/// Usage:
+/// let log = macro_log_with_span!(span_id, trace_id, log);
+#[macro_export]
+#[doc = "Attach tracing span and trace IDs to a log entry"]
+macro_rules! macro_log_with_span {
+    ($span_id:expr, $trace_id:expr, $log:expr) => {
+        $log.with_fields(&[
+            ("trace_id", $trace_id),
+            ("span_id", $span_id),
+        ])
+    };
+}
+
+/// This macro creates a log entry with extra key-value fields.
+/// Each `key = value` pair is attached with `Log::with_field`, using the
+/// key as written and the `Display` output of the value. The session ID
+/// and timestamp are generated as with `macro_log_here!`, and the log
+/// format defaults to CLF.
+///
+/// # Parameters
+/// - `level`: The severity level of the log.
+/// - `component`: The system component that generated the log.
+/// - `description`: A textual description of the log event.
+/// - `key = value`: Any number of extra fields.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_log_structured, log_level::LogLevel};
+/// let log = macro_log_structured!(LogLevel::INFO, "db", "query complete", rows = 42, table = "users");
+/// assert_eq!(log.fields["rows"], "42");
+/// assert_eq!(log.fields["table"], "users");
+/// ```
+/// Usage:
+/// let log = macro_log_structured!(level, component, description, key1 = value1, key2 = value2);
+#[macro_export]
+#[doc = "Macro to create a log with extra key-value fields"]
+macro_rules! macro_log_structured {
+    ($level:expr, $component:expr, $description:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::log::Log::new(
+            &$crate::context::session_id_or_random(),
+            &$crate::utils::generate_timestamp(),
+            &$level,
+            $component,
+            $description,
+            &$crate::log_format::LogFormat::CLF,
+        )
+        $(.with_field(stringify!($key), &$value.to_string()))*
+    };
+}
+
 // =========================
 // Macros for Log Conditions
 // =========================
@@ -334,6 +680,170 @@ macro_rules! macro_log_if {
     };
 }
 
+/// This macro logs a message only if the configuration lets its level
+/// through, checking the level before the log entry is created.
+/// Unlike `macro_log_if!`, no `Log` is built and `description` is not
+/// evaluated for entries that would be filtered out. Entries are kept
+/// under the same rule as `Log::log_with_config`: `level` must not be
+/// below the effective level for `component` (see
+/// `Config::effective_level_for`). Kept entries are written with
+/// `log_with_config`, using a copy of the configuration taken when the
+/// check passes.
+///
+/// Must be used in an async context.
+///
+/// # Parameters
+/// - `config`: The configuration, as an `Arc<RwLock<Config>>`.
+/// - `level`: The severity level of the log.
+/// - `component`: The system component that generated the log.
+/// - `description`: A textual description of the log event.
+///
+/// # Returns
+/// A `RlgResult<()>`, which is `Ok(())` if the entry was filtered out or
+/// logged.
+///
+/// # Example
+/// ```
+/// use rlg::{config::Config, log_level::LogLevel, macro_log_if_level};
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let config = Config::load_async(None::<&str>, None).await.unwrap();
+/// config.write().log_file_path = dir.path().join("app.log");
+/// config.write().log_level = LogLevel::ERROR;
+/// macro_log_if_level!(config, LogLevel::DEBUG, "app", &format!("{} items", 3)).unwrap();
+/// assert!(!dir.path().join("app.log").exists());
+/// # });
+/// ```
+/// Usage:
+/// macro_log_if_level!(config, level, component, description)?;
+#[macro_export]
+#[doc = "Log only if the configured level lets the entry through"]
+macro_rules! macro_log_if_level {
+    ($config:expr, $level:expr, $component:expr, $description:expr) => {{
+        let level: $crate::log_level::LogLevel = $level;
+        let component: &str = $component;
+        let config = {
+            let config = $config.read();
+            if level.to_numeric()
+                < config.effective_level_for(component).to_numeric()
+            {
+                None
+            } else {
+                Some(config.clone())
+            }
+        };
+        match config {
+            Some(config) => {
+                $crate::macro_log_structured!(
+                    level,
+                    component,
+                    $description
+                )
+                .log_with_config(&config)
+                .await
+            }
+            None => Ok(()),
+        }
+    }};
+}
+
+/// This macro suppresses high-frequency duplicates of a log entry.
+/// At most `max_per_second` entries with the same component and description
+/// are let through per second; the rest are counted, and the next entry let
+/// through has `(suppressed N times)` appended to its description.
+///
+/// # Parameters
+/// - `max_per_second`: The number of identical entries allowed per second.
+/// - `log`: The log entry to be rate limited.
+///
+/// # Returns
+/// `Some(log)` if the entry should be written, or `None` if it was
+/// suppressed.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_log_rate_limited, macro_warn_log};
+/// for _ in 0..100 {
+///     let log = macro_warn_log!("2022-01-01", "cache", "miss");
+///     if let Some(log) = macro_log_rate_limited!(1, log) {
+///         println!("{}", log);
+///     }
+/// }
+/// ```
+/// Usage:
+/// let log = macro_log_rate_limited!(max_per_second, log);
+#[macro_export]
+#[doc = "Rate limit identical log entries"]
+macro_rules! macro_log_rate_limited {
+    ($max_per_second:expr, $log:expr) => {
+        $crate::rate_limit::check_global($max_per_second, $log)
+    };
+}
+
+/// This macro logs a message only the first time it is seen.
+/// Entries are identified by `Log::hash_id`, so an entry is skipped if one
+/// with the same level, component and description was already logged by
+/// this macro during the lifetime of the process.
+///
+/// # Parameters
+/// - `log`: The log entry to be logged once.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_info_log, macro_log_once};
+/// for _ in 0..3 {
+///     let log = macro_info_log!("2022-01-01", "app", "printed once");
+///     macro_log_once!(log);
+/// }
+/// ```
+/// Usage:
+/// macro_log_once!(log);
+#[macro_export]
+#[doc = "Log an entry only the first time it is seen"]
+macro_rules! macro_log_once {
+    ($log:expr) => {
+        if let Some(log) = $crate::rate_limit::check_once($log) {
+            $crate::macro_print_log!(log);
+        }
+    };
+}
+
+/// This macro samples log entries, letting through one entry every `n`
+/// invocations.
+/// Each call site has its own counter, starting at zero, so the first
+/// invocation is let through, then the `n + 1`th, and so on. Nothing is let
+/// through if `n` is zero.
+///
+/// # Parameters
+/// - `n`: The sampling interval.
+/// - `log`: The log entry to be sampled.
+///
+/// # Returns
+/// `Some(log)` if the entry should be written, or `None` if it was
+/// skipped.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_info_log, macro_log_every_n};
+/// for _ in 0..100 {
+///     let log = macro_info_log!("2022-01-01", "worker", "tick");
+///     if let Some(log) = macro_log_every_n!(10, log) {
+///         println!("{}", log);
+///     }
+/// }
+/// ```
+/// Usage:
+/// let log = macro_log_every_n!(n, log);
+#[macro_export]
+#[doc = "Let through one log entry every n invocations"]
+macro_rules! macro_log_every_n {
+    ($n:expr, $log:expr) => {{
+        static COUNTER: ::std::sync::atomic::AtomicU64 =
+            ::std::sync::atomic::AtomicU64::new(0);
+        $crate::rate_limit::check_every_n(&COUNTER, $n, $log)
+    }};
+}
+
 /// This macro conditionally logs a debug message if the `debug_enabled` feature flag is set.
 ///
 /// # Parameters
@@ -392,3 +902,25 @@ macro_rules! macro_print_log {
         println!("{}", $log.description);
     };
 }
+
+/// This macro prints a log entry to the standard output (stdout) as a
+/// multi-line block, colored when stdout is a terminal.
+///
+/// # Parameters
+/// - `log`: The log entry to be printed.
+///
+/// # Example
+/// ```
+/// use rlg::{macro_print_log_pretty,macro_info_log};
+/// let log = macro_info_log!("2022-01-01", "app", "message");
+/// macro_print_log_pretty!(log);
+/// ```
+/// Usage:
+/// macro_print_log_pretty!(log);
+#[macro_export]
+#[doc = "Print a pretty multi-line log to stdout"]
+macro_rules! macro_print_log_pretty {
+    ($log:expr) => {
+        println!("{}", $log.to_pretty_string());
+    };
+}
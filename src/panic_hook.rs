@@ -0,0 +1,101 @@
+// panic_hook.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    config::Config, context::session_id_or_random, log::Log,
+    utils::generate_timestamp, LogFormat, LogLevel,
+};
+use parking_lot::RwLock;
+use std::{panic, sync::Arc, thread};
+use tokio::runtime::{Builder, Handle};
+
+/// Installs a panic hook that records panics in the log.
+///
+/// When a thread panics, a `FATAL` entry from the `panic` component
+/// describing the panic message and location is written with
+/// `Log::log_with_config`, using the configuration current at the time
+/// of the panic. The entry is written before the panic continues: the
+/// previously installed hook, which by default prints the panic to
+/// stderr, is called afterwards.
+///
+/// The entry is written on a temporary Tokio runtime. If the panicking
+/// thread is already running a runtime, the temporary one runs on a
+/// separate thread so that it does not block the current one.
+///
+/// No entry is written if the configuration is locked for writing when
+/// the panic occurs, as the panicking thread may be the one holding the
+/// lock.
+///
+/// # Examples
+///
+/// ```
+/// use parking_lot::RwLock;
+/// use rlg::{config::Config, install_panic_hook};
+/// use std::sync::Arc;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let config = Config {
+///     log_file_path: dir.path().join("app.log"),
+///     ..Default::default()
+/// };
+/// install_panic_hook(Arc::new(RwLock::new(config)));
+///
+/// let _ = std::panic::catch_unwind(|| panic!("boom"));
+/// let content = std::fs::read_to_string(dir.path().join("app.log")).unwrap();
+/// assert!(content.contains("Level=FATAL"));
+/// # let _ = std::panic::take_hook();
+/// ```
+pub fn install_panic_hook(config: Arc<RwLock<Config>>) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| {
+                payload.downcast_ref::<String>().map(String::as_str)
+            })
+            .unwrap_or("Box<dyn Any>");
+        let description = match info.location() {
+            Some(location) => {
+                format!("panicked at {}: {}", location, message)
+            }
+            None => format!("panicked: {}", message),
+        };
+        let log = Log::new(
+            &session_id_or_random(),
+            &generate_timestamp(),
+            &LogLevel::FATAL,
+            "panic",
+            &description,
+            &LogFormat::CLF,
+        );
+        // Waiting for the lock would deadlock if this thread holds it.
+        if let Some(config) = config.try_read() {
+            write_blocking(log, config.clone());
+        }
+        previous(info);
+    }));
+}
+
+/// Writes `log` with `config`, blocking until the write completes.
+///
+/// Errors are ignored: the panic is still reported by the previous hook.
+fn write_blocking(log: Log, config: Config) {
+    let write = move || {
+        if let Ok(runtime) =
+            Builder::new_current_thread().enable_all().build()
+        {
+            let _ = runtime.block_on(log.log_with_config(&config));
+        }
+    };
+    match Handle::try_current() {
+        // A runtime cannot be blocked on from within another one.
+        Ok(_) => {
+            let _ = thread::spawn(write).join();
+        }
+        Err(_) => write(),
+    }
+}
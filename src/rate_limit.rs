@@ -0,0 +1,147 @@
+// rate_limit.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Suppression of high-frequency duplicate log entries.
+//!
+//! A `RateLimiter` lets at most a given number of identical entries
+//! through per second, where entries are identical when they share their
+//! component and description. Suppressed entries are counted, and the next
+//! entry let through reports how many were dropped.
+//!
+//! `check_once` lets each entry through only the first time its
+//! `Log::hash_id` is seen in the process, and `check_every_n` lets one
+//! entry through every `n` invocations of a call site.
+
+use crate::log::Log;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The length of the sliding window used to count entries.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// The limiter shared by `macro_log_rate_limited!`.
+static GLOBAL_LIMITER: Lazy<RateLimiter> = Lazy::new(RateLimiter::new);
+
+/// The `Log::hash_id` values already let through by `macro_log_once!`.
+static SEEN_HASH_IDS: Lazy<Mutex<HashSet<String>>> =
+    Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Recent entries and suppressed count for one component and description.
+#[derive(Debug, Default)]
+struct WindowState {
+    allowed: VecDeque<Instant>,
+    suppressed: u64,
+}
+
+/// A sliding-window rate limiter for log entries.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::rate_limit::RateLimiter;
+/// use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+///
+/// let limiter = RateLimiter::new();
+/// let log = Log::new("id", "2024-01-01", &LogLevel::WARN, "cache", "miss", &LogFormat::CLF);
+/// assert!(limiter.check(1, log.clone()).is_some());
+/// assert!(limiter.check(1, log).is_none());
+/// ```
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    windows: Mutex<HashMap<(String, String), WindowState>>,
+}
+
+impl RateLimiter {
+    /// Creates a new `RateLimiter` with no recorded entries.
+    pub fn new() -> Self {
+        RateLimiter::default()
+    }
+
+    /// Decides whether `log` may be written.
+    ///
+    /// Returns `None` if `max_per_second` entries with the same component
+    /// and description were already let through during the last second.
+    /// Otherwise returns the entry, with `(suppressed N times)` appended to
+    /// its description if `N` entries were suppressed since the previous
+    /// one was let through.
+    pub fn check(
+        &self,
+        max_per_second: u64,
+        mut log: Log,
+    ) -> Option<Log> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock();
+        let state = windows
+            .entry((log.component.clone(), log.description.clone()))
+            .or_default();
+
+        while state
+            .allowed
+            .front()
+            .map_or(false, |&time| now.duration_since(time) >= WINDOW)
+        {
+            let _ = state.allowed.pop_front();
+        }
+
+        if state.allowed.len() as u64 >= max_per_second {
+            state.suppressed += 1;
+            return None;
+        }
+
+        state.allowed.push_back(now);
+        if state.suppressed > 0 {
+            log.description = format!(
+                "{} (suppressed {} times)",
+                log.description, state.suppressed
+            );
+            state.suppressed = 0;
+        }
+        Some(log)
+    }
+}
+
+/// Checks `log` against the limiter shared by `macro_log_rate_limited!`.
+///
+/// Used by `macro_log_rate_limited!`.
+#[doc(hidden)]
+pub fn check_global(max_per_second: u64, log: Log) -> Option<Log> {
+    GLOBAL_LIMITER.check(max_per_second, log)
+}
+
+/// Returns `log` if its `Log::hash_id` has not been seen before in this
+/// process, or `None` if it has.
+///
+/// Used by `macro_log_once!`.
+#[doc(hidden)]
+pub fn check_once(log: Log) -> Option<Log> {
+    if SEEN_HASH_IDS.lock().insert(log.hash_id()) {
+        Some(log)
+    } else {
+        None
+    }
+}
+
+/// Increments `counter` and returns `log` if its previous value is a
+/// multiple of `n`, or `None` otherwise. Returns `None` if `n` is zero.
+///
+/// Used by `macro_log_every_n!`.
+#[doc(hidden)]
+pub fn check_every_n(
+    counter: &AtomicU64,
+    n: u64,
+    log: Log,
+) -> Option<Log> {
+    let count = counter.fetch_add(1, Ordering::Relaxed);
+    if n != 0 && count % n == 0 {
+        Some(log)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,531 @@
+// sink.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::RlgError, log::Log, LogFormat, LogLevel, RlgResult,
+};
+use async_trait::async_trait;
+#[cfg(feature = "reqwest")]
+use std::collections::BTreeMap;
+use std::{
+    collections::HashMap,
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    fs::OpenOptions,
+    io::{AsyncWrite, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    sync::Mutex,
+};
+
+/// A file sink that rotates its file itself.
+pub mod rolling;
+pub use rolling::RollingFileSink;
+
+/// Maximum length in bytes of a syslog message sent over UDP.
+const SYSLOG_UDP_MAX_LEN: usize = 1024;
+
+/// Number of times `TcpSink` reconnects before giving up on an entry.
+const TCP_MAX_RETRIES: u32 = 3;
+
+/// Delay before the first reconnection attempt of `TcpSink`, doubled for
+/// each further attempt.
+const TCP_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// A destination that formatted log entries can be written to.
+///
+/// Implement this trait to send log entries somewhere other than the
+/// built-in file, stdout and stderr sinks, for example an in-memory buffer
+/// in tests.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::log::Log;
+/// use rlg::sink::{LogSink, StdoutSink};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let log = Log::default();
+/// log.log_to(&StdoutSink::default()).await.unwrap();
+/// # });
+/// ```
+#[async_trait]
+pub trait LogSink: Send + Sync {
+    /// Writes a single log entry to the sink.
+    async fn write(&self, log: &Log) -> RlgResult<()>;
+
+    /// Flushes any buffered entries to the underlying destination.
+    async fn flush(&self) -> RlgResult<()>;
+}
+
+/// A sink that appends log entries to a file.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FileSink {
+    path: PathBuf,
+    fields_separator: char,
+}
+
+impl FileSink {
+    /// Creates a new `FileSink` that appends to the file at `path`.
+    ///
+    /// The file is created on the first write if it does not exist.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FileSink {
+            path: path.as_ref().to_path_buf(),
+            fields_separator: ' ',
+        }
+    }
+
+    /// Separates the extra `key=value` fields of CLF entries with
+    /// `separator` instead of a space.
+    pub fn with_fields_separator(mut self, separator: char) -> Self {
+        self.fields_separator = separator;
+        self
+    }
+
+    /// Returns the path of the file this sink appends to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl LogSink for FileSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        let line = log.to_line_with_separator(self.fields_separator)?;
+        append_to_file(&self.path, line.as_bytes()).await
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        // Every write is flushed before the file is closed.
+        Ok(())
+    }
+}
+
+/// A sink that appends log entries to a file as length-prefixed MessagePack
+/// frames.
+///
+/// Each frame is the 4-byte big-endian length of the encoded entry followed
+/// by the bytes produced by `Log::to_bytes`, so the file can be read back
+/// entry by entry with `Log::from_bytes`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BinarySink {
+    path: PathBuf,
+}
+
+impl BinarySink {
+    /// Creates a new `BinarySink` that appends frames to the file at `path`.
+    ///
+    /// The file is created on the first write if it does not exist.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        BinarySink {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the path of the file this sink appends to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl LogSink for BinarySink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        let bytes = log.to_bytes()?;
+        let length = u32::try_from(bytes.len()).map_err(|_| {
+            RlgError::FormattingError(format!(
+                "Log entry of {} bytes is too large for a frame",
+                bytes.len()
+            ))
+        })?;
+
+        // Write the prefix and payload together so frames never interleave.
+        let mut frame = Vec::with_capacity(4 + bytes.len());
+        frame.extend_from_slice(&length.to_be_bytes());
+        frame.extend_from_slice(&bytes);
+        append_to_file(&self.path, &frame).await
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        // Every write is flushed before the file is closed.
+        Ok(())
+    }
+}
+
+/// A sink that POSTs log entries to an HTTP endpoint.
+///
+/// Each entry is sent as the body of its own request, formatted in the
+/// entry's format, together with the configured headers.
+#[cfg(feature = "reqwest")]
+#[derive(Clone, Debug)]
+pub struct HttpSink {
+    url: String,
+    headers: BTreeMap<String, String>,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpSink {
+    /// Creates a new `HttpSink` that posts entries to `url` with `headers`.
+    pub fn new(url: &str, headers: BTreeMap<String, String>) -> Self {
+        HttpSink {
+            url: url.to_string(),
+            headers,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Returns the URL entries are posted to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[async_trait]
+impl LogSink for HttpSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        let mut request =
+            self.client.post(&self.url).body(log.to_string());
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        let response = request.send().await.map_err(|e| {
+            RlgError::NetworkError(format!(
+                "Failed to send log entry to {}: {}",
+                self.url, e
+            ))
+        })?;
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            return Err(RlgError::NetworkError(format!(
+                "{} rejected log entry with status {}",
+                self.url, status
+            )));
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        // Every entry is sent as soon as it is written.
+        Ok(())
+    }
+}
+
+/// A sink that sends log entries to a syslog server over UDP.
+///
+/// Each entry is formatted as RFC 5424, whatever its own format, and sent
+/// as a single datagram. Messages longer than 1024 bytes are truncated,
+/// which shortens the description at the end of the message.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct UdpSyslogSink {
+    addr: SocketAddr,
+}
+
+impl UdpSyslogSink {
+    /// Creates a new `UdpSyslogSink` that sends datagrams to `addr`.
+    pub fn new(addr: SocketAddr) -> Self {
+        UdpSyslogSink { addr }
+    }
+
+    /// Returns the address datagrams are sent to.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+#[async_trait]
+impl LogSink for UdpSyslogSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        let mut message = Log {
+            format: LogFormat::RFC5424,
+            ..log.clone()
+        }
+        .to_string();
+        if message.len() > SYSLOG_UDP_MAX_LEN {
+            let mut end = SYSLOG_UDP_MAX_LEN;
+            while !message.is_char_boundary(end) {
+                end -= 1;
+            }
+            message.truncate(end);
+        }
+
+        send_datagram(self.addr, message.as_bytes()).await
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        // Every entry is sent as soon as it is written.
+        Ok(())
+    }
+}
+
+/// A sink that streams log entries to a TCP server.
+///
+/// Each entry is sent as a newline-terminated line. The connection is
+/// opened on the first write and kept for later ones. If it has been
+/// closed by the server or a write fails, the sink reconnects, retrying up
+/// to three times with an exponential backoff starting at 100 ms before
+/// reporting an `RlgError::NetworkError`.
+#[derive(Debug)]
+pub struct TcpSink {
+    addr: SocketAddr,
+    stream: Mutex<Option<TcpStream>>,
+}
+
+impl TcpSink {
+    /// Creates a new `TcpSink` that connects to `addr`.
+    pub fn new(addr: SocketAddr) -> Self {
+        TcpSink {
+            addr,
+            stream: Mutex::new(None),
+        }
+    }
+
+    /// Returns the address entries are sent to.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the open connection, reconnecting if there is none or the
+    /// server has closed it.
+    async fn connected<'a>(
+        &self,
+        stream: &'a mut Option<TcpStream>,
+    ) -> io::Result<&'a mut TcpStream> {
+        let open = match stream.take() {
+            Some(open) if is_open(&open) => open,
+            _ => TcpStream::connect(self.addr).await?,
+        };
+        Ok(stream.insert(open))
+    }
+}
+
+/// Returns `false` if the server has closed the connection.
+///
+/// A closed connection reads as end of file; anything sent by the server
+/// is discarded.
+fn is_open(stream: &TcpStream) -> bool {
+    let mut buffer = [0; 64];
+    match stream.try_read(&mut buffer) {
+        Ok(n) => n > 0,
+        Err(e) => e.kind() == io::ErrorKind::WouldBlock,
+    }
+}
+
+#[async_trait]
+impl LogSink for TcpSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        let line = log.to_line()?;
+        let mut stream = self.stream.lock().await;
+        let mut delay = TCP_RETRY_BASE_DELAY;
+        let mut attempt = 0;
+        loop {
+            let result = match self.connected(&mut stream).await {
+                Ok(open) => open.write_all(line.as_bytes()).await,
+                Err(e) => Err(e),
+            };
+            let error = match result {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            *stream = None;
+            if attempt == TCP_MAX_RETRIES {
+                return Err(RlgError::NetworkError(format!(
+                    "Failed to send log entry to {} after {} retries: {}",
+                    self.addr, TCP_MAX_RETRIES, error
+                )));
+            }
+            attempt += 1;
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        if let Some(stream) = self.stream.lock().await.as_mut() {
+            stream.flush().await.map_err(|e| {
+                RlgError::NetworkError(format!(
+                    "Failed to flush log entries to {}: {}",
+                    self.addr, e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A sink that sends log entries to a server over UDP.
+///
+/// Each entry is sent as a single datagram holding its formatted line.
+/// Unlike `UdpSyslogSink`, the entry keeps its own format.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct UdpSink {
+    addr: SocketAddr,
+}
+
+impl UdpSink {
+    /// Creates a new `UdpSink` that sends datagrams to `addr`.
+    pub fn new(addr: SocketAddr) -> Self {
+        UdpSink { addr }
+    }
+
+    /// Returns the address datagrams are sent to.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+#[async_trait]
+impl LogSink for UdpSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        send_datagram(self.addr, log.to_line()?.as_bytes()).await
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        // Every entry is sent as soon as it is written.
+        Ok(())
+    }
+}
+
+/// Sends `bytes` to `addr` as a single datagram from an ephemeral port.
+async fn send_datagram(
+    addr: SocketAddr,
+    bytes: &[u8],
+) -> RlgResult<()> {
+    let local: SocketAddr = if addr.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(local).await?;
+    let _ = socket.send_to(bytes, addr).await.map_err(|e| {
+        RlgError::NetworkError(format!(
+            "Failed to send log entry to {}: {}",
+            addr, e
+        ))
+    })?;
+    Ok(())
+}
+
+/// A sink that writes log entries to standard output.
+///
+/// A colorizing sink writes entries as colored multi-line blocks (see
+/// `Log::to_pretty_string_with_colors`) when stdout is a terminal, and in
+/// their format otherwise, so piped output stays machine-readable.
+/// Uncolored entries are written as the same lines as `FileSink` writes,
+/// which for `LogFormat::CLF` entries end with a `Format=CLF` pair that
+/// their `Display` output lacks.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StdoutSink {
+    colorize: bool,
+    colors: HashMap<LogLevel, String>,
+}
+
+impl StdoutSink {
+    /// Creates a sink that writes to standard output, colorizing entries
+    /// if `colorize` is set.
+    pub fn new(colorize: bool) -> Self {
+        StdoutSink {
+            colorize,
+            colors: HashMap::new(),
+        }
+    }
+
+    /// Colors the level of colorized entries with the ANSI escape codes
+    /// in `colors` instead of those of `Config::default_color_map`.
+    pub fn with_colors(
+        mut self,
+        colors: HashMap<LogLevel, String>,
+    ) -> Self {
+        self.colors = colors;
+        self
+    }
+}
+
+#[async_trait]
+impl LogSink for StdoutSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        let line = if self.colorize
+            && colored::control::SHOULD_COLORIZE.should_colorize()
+        {
+            format!(
+                "{}\n",
+                log.to_pretty_string_with_colors(&self.colors)
+            )
+        } else {
+            log.to_line()?
+        };
+        write_line(&mut tokio::io::stdout(), &line).await
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        tokio::io::stdout().flush().await.map_err(RlgError::IoError)
+    }
+}
+
+/// A sink that writes log entries to standard error.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct StderrSink;
+
+#[async_trait]
+impl LogSink for StderrSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        write_line(&mut tokio::io::stderr(), &log.to_line()?).await
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        tokio::io::stderr().flush().await.map_err(RlgError::IoError)
+    }
+}
+
+/// Writes a formatted line to a standard stream and flushes it.
+async fn write_line<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    line: &str,
+) -> RlgResult<()> {
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Appends `bytes` to the file at `path`, creating it if needed, and flushes.
+pub(crate) async fn append_to_file(
+    path: &Path,
+    bytes: &[u8],
+) -> RlgResult<()> {
+    // Open the log file for appending, or create it if it does not exist.
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|e| {
+            RlgError::IoError(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Failed to open log file: {}", e),
+            ))
+        })?;
+
+    file.write_all(bytes).await.map_err(|e| {
+        RlgError::IoError(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Failed to write to log file: {}", e),
+        ))
+    })?;
+
+    file.flush().await.map_err(|e| {
+        RlgError::IoError(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Failed to flush log file: {}", e),
+        ))
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+// rolling.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::LogSink;
+use crate::{
+    config::LogRotation,
+    log::Log,
+    utils::{
+        age_rotation_due, compress_rotated_file, count_lines,
+        force_rotate_log_file,
+    },
+    RlgResult,
+};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::Mutex,
+};
+
+/// A sink that appends log entries to a file and rotates it itself.
+///
+/// Rotation is checked on every write, both before the entry is written,
+/// so that no entry lands in a file that is already due, and after, so
+/// that a file is rotated as soon as it reaches its limit:
+///
+/// - `Size(max)` is due once the file holds `max` bytes.
+/// - `Count(n)` is due once the file holds `n` lines.
+/// - `Time`, `Date` and `Cron` are due as for `utils::rotate_log_file`.
+///
+/// Rotating shifts the rotated files with `utils::force_rotate_log_file`,
+/// keeping at most `max_files` of them, and compresses the newly rotated
+/// file if requested. Writes are serialized, so the sink can be shared
+/// between tasks.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::{config::LogRotation, log::Log, sink::RollingFileSink};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let dir = tempfile::tempdir().unwrap();
+/// let sink = RollingFileSink::new(
+///     dir.path().join("RLG.log"),
+///     LogRotation::Count(2),
+///     3,
+///     false,
+/// );
+/// for _ in 0..2 {
+///     Log::default().log_to(&sink).await.unwrap();
+/// }
+/// assert!(dir.path().join("RLG.log.1").exists());
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct RollingFileSink {
+    base_path: PathBuf,
+    rotation: LogRotation,
+    max_files: u32,
+    compress: bool,
+    file: Mutex<Option<OpenFile>>,
+}
+
+/// The current log file of a `RollingFileSink` and what it holds.
+#[derive(Debug)]
+struct OpenFile {
+    file: File,
+    size: u64,
+    lines: u64,
+}
+
+impl RollingFileSink {
+    /// Creates a new `RollingFileSink` that appends to the file at
+    /// `base_path` and rotates it under `rotation`, keeping at most
+    /// `max_files` rotated files and gzip-compressing them if `compress`
+    /// is set.
+    ///
+    /// The file is opened on the first write, and created if it does not
+    /// exist.
+    pub fn new(
+        base_path: PathBuf,
+        rotation: LogRotation,
+        max_files: u32,
+        compress: bool,
+    ) -> RollingFileSink {
+        RollingFileSink {
+            base_path,
+            rotation,
+            max_files,
+            compress,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Returns the path of the file this sink appends to.
+    pub fn path(&self) -> &Path {
+        &self.base_path
+    }
+
+    /// Opens the log file if it is not open yet and returns it.
+    async fn open<'a>(
+        &self,
+        current: &'a mut Option<OpenFile>,
+    ) -> RlgResult<&'a mut OpenFile> {
+        if current.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.base_path)
+                .await?;
+            let size = file.metadata().await?.len();
+            let lines = match self.rotation {
+                LogRotation::Count(_) if size > 0 => {
+                    count_lines(&self.base_path).await?
+                }
+                _ => 0,
+            };
+            *current = Some(OpenFile { file, size, lines });
+        }
+        Ok(current.as_mut().expect("log file was just opened"))
+    }
+
+    /// Rotates the log file if its rotation policy says it is due.
+    async fn rotate_if_due(
+        &self,
+        current: &mut Option<OpenFile>,
+    ) -> RlgResult<()> {
+        let open = self.open(current).await?;
+        if open.size == 0 {
+            return Ok(());
+        }
+        let due = match &self.rotation {
+            LogRotation::Size(max) => open.size >= max.get(),
+            LogRotation::Count(max) => open.lines >= u64::from(*max),
+            rotation => age_rotation_due(
+                rotation,
+                &open.file.metadata().await?,
+            )?,
+        };
+        if !due {
+            return Ok(());
+        }
+
+        open.file.flush().await?;
+        // Close the file before renaming it, which some platforms require.
+        *current = None;
+        let rotated =
+            force_rotate_log_file(&self.base_path, self.max_files)?;
+        if self.compress {
+            let _ = compress_rotated_file(&rotated).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LogSink for RollingFileSink {
+    async fn write(&self, log: &Log) -> RlgResult<()> {
+        let line = log.to_line()?;
+        let mut current = self.file.lock().await;
+        self.rotate_if_due(&mut current).await?;
+
+        let open = self.open(&mut current).await?;
+        open.file.write_all(line.as_bytes()).await?;
+        open.file.flush().await?;
+        open.size += line.len() as u64;
+        open.lines += line.matches('\n').count() as u64;
+
+        self.rotate_if_due(&mut current).await
+    }
+
+    async fn flush(&self) -> RlgResult<()> {
+        if let Some(open) = self.file.lock().await.as_mut() {
+            open.file.flush().await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+// timer.rs
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    context::session_id_or_random, log::Log, utils::generate_timestamp,
+    LogFormat, LogLevel,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A guard that logs how long an operation took when it is dropped.
+///
+/// Created by `Log::new_timed`. When the timer goes out of scope, an
+/// `INFO` entry describing the operation as `"<description> completed in
+/// <elapsed_ms>ms"` is logged on a spawned Tokio task. Dropping the timer
+/// outside a Tokio runtime, or calling `abort`, logs nothing.
+///
+/// The elapsed time is measured with `tokio::time::Instant`, so it follows
+/// `tokio::time::pause` and `tokio::time::advance` in tests.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::log::Log;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let timer = Log::new_timed("db", "query users");
+/// // ... run the query ...
+/// assert!(timer.entry().description.starts_with("query users completed in "));
+/// timer.abort();
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct LogTimer {
+    session_id: String,
+    component: String,
+    description: String,
+    start: Instant,
+    armed: bool,
+}
+
+impl LogTimer {
+    /// Starts a timer for the operation `description` of `component`.
+    pub(crate) fn new(component: &str, description: &str) -> Self {
+        LogTimer {
+            session_id: session_id_or_random(),
+            component: component.to_string(),
+            description: description.to_string(),
+            start: Instant::now(),
+            armed: true,
+        }
+    }
+
+    /// Returns the time elapsed since the timer was started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the entry that would be logged if the timer were dropped
+    /// now.
+    pub fn entry(&self) -> Log {
+        Log::new(
+            &self.session_id,
+            &generate_timestamp(),
+            &LogLevel::INFO,
+            &self.component,
+            &format!(
+                "{} completed in {}ms",
+                self.description,
+                self.elapsed().as_millis()
+            ),
+            &LogFormat::CLF,
+        )
+    }
+
+    /// Stops the timer without logging anything.
+    pub fn abort(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for LogTimer {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let entry = self.entry();
+            drop(runtime.spawn(async move {
+                // There is no caller left to report a failure to.
+                let _ = entry.log().await;
+            }));
+        }
+    }
+}
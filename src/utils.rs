@@ -3,11 +3,48 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::error::RlgResult;
+use crate::{
+    config::{
+        Config, ConfigError, LogRotation, RotationNamingConvention,
+    },
+    error::{RlgError, RlgResult},
+    log::Log,
+    log_format::LogFormat,
+    log_level::LogLevel,
+};
 use dtt::datetime::DateTime;
-use std::path::Path;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use futures::stream::{self, Stream, StreamExt};
+use notify::{
+    event::ModifyKind, EventKind, RecommendedWatcher, RecursiveMode,
+    Watcher,
+};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    ffi::OsString,
+    io::{BufRead, Read, Seek},
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::Duration,
+};
 use tokio::fs::{self, File, OpenOptions};
-use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::io::{
+    AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt,
+    BufReader, BufWriter,
+};
+use tokio::sync::mpsc;
+use vrd::random::Random;
+
+/// How often `async_tail_follow` checks the file when no change
+/// notification arrives, in case one was missed.
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long `watch_config_file` waits for a configuration file to stop
+/// changing before reloading it.
+const CONFIG_WATCH_SETTLE_TIME: Duration = Duration::from_millis(50);
 
 /// Generates a timestamp string in ISO 8601 format.
 ///
@@ -27,6 +64,91 @@ pub fn generate_timestamp() -> String {
     DateTime::new().to_string()
 }
 
+/// Returns `bytes` random bytes as lowercase hex, never all zeros.
+///
+/// W3C TraceContext treats an all-zero trace or span ID as invalid.
+fn random_hex_id(bytes: usize) -> String {
+    let mut random = Random::default();
+    loop {
+        let id: Vec<u8> = (0..bytes)
+            .map(|_| random.int(0, i32::from(u8::MAX)) as u8)
+            .collect();
+        if id.iter().any(|&byte| byte != 0) {
+            return id
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+        }
+    }
+}
+
+/// Generates a W3C TraceContext trace ID.
+///
+/// # Returns
+///
+/// A `String` of 32 lowercase hex characters (128 random bits).
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::generate_trace_id;
+///
+/// let trace_id = generate_trace_id();
+/// assert_eq!(trace_id.len(), 32);
+/// ```
+pub fn generate_trace_id() -> String {
+    random_hex_id(16)
+}
+
+/// Generates a W3C TraceContext span ID.
+///
+/// # Returns
+///
+/// A `String` of 16 lowercase hex characters (64 random bits).
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::generate_span_id;
+///
+/// let span_id = generate_span_id();
+/// assert_eq!(span_id.len(), 16);
+/// ```
+pub fn generate_span_id() -> String {
+    random_hex_id(8)
+}
+
+/// Generates a W3C `traceparent` header value with a new trace ID and
+/// span ID, for correlating log entries across services.
+///
+/// # Arguments
+///
+/// * `version` - The TraceContext version, `0` for the current one.
+/// * `sampled` - Whether the `sampled` trace flag is set.
+///
+/// # Returns
+///
+/// A `String` of the form `<version>-<trace_id>-<span_id>-<flags>`.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::new_traceparent;
+///
+/// let traceparent = new_traceparent(0, true);
+/// assert!(traceparent.starts_with("00-"));
+/// assert!(traceparent.ends_with("-01"));
+/// ```
+pub fn new_traceparent(version: u8, sampled: bool) -> String {
+    format!(
+        "{:02x}-{}-{}-{:02x}",
+        version,
+        generate_trace_id(),
+        generate_span_id(),
+        u8::from(sampled)
+    )
+}
+
 /// Sanitizes a string for use in log messages.
 ///
 /// This function replaces newlines and control characters with spaces.
@@ -55,6 +177,64 @@ pub fn sanitize_log_message(message: &str) -> String {
         .replace(|c: char| c.is_control(), " ")
 }
 
+/// Substitutes the `%{key}` placeholders of a template with the values
+/// `params` gives for them.
+///
+/// A `%` that does not start a placeholder is kept as it is.
+///
+/// # Arguments
+///
+/// * `template` - A string slice that holds the template.
+/// * `params` - The value of each placeholder, keyed by name.
+///
+/// # Returns
+///
+/// A `RlgResult<String>` containing the substituted template, or a
+/// `RlgError::FormattingError` if a placeholder has no value in `params`
+/// or is not closed.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::apply_template;
+/// use std::collections::HashMap;
+///
+/// let params = HashMap::from([("user", "alice")]);
+/// assert_eq!(
+///     apply_template("User %{user} logged in", &params).unwrap(),
+///     "User alice logged in"
+/// );
+/// assert!(apply_template("From %{ip}", &params).is_err());
+/// ```
+pub fn apply_template(
+    template: &str,
+    params: &HashMap<&str, &str>,
+) -> RlgResult<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("%{") {
+        output.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..];
+        let end = placeholder.find('}').ok_or_else(|| {
+            RlgError::FormattingError(format!(
+                "Unclosed placeholder in template: '{}'",
+                template
+            ))
+        })?;
+        let key = &placeholder[..end];
+        let value = params.get(key).ok_or_else(|| {
+            RlgError::FormattingError(format!(
+                "No value for placeholder '%{{{}}}' in template",
+                key
+            ))
+        })?;
+        output.push_str(value);
+        rest = &placeholder[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
 /// Checks if a file exists and is writable.
 ///
 /// # Arguments
@@ -210,7 +390,7 @@ pub fn format_file_size(size: u64) -> String {
 /// ```
 pub fn parse_datetime(datetime_str: &str) -> RlgResult<DateTime> {
     DateTime::parse(datetime_str)
-        .map_err(|e| crate::error::RlgError::custom(e.to_string()))
+        .map_err(|e| RlgError::custom(e.to_string()))
 }
 
 /// Checks if a directory is writable.
@@ -252,3 +432,1884 @@ pub async fn is_directory_writable(path: &Path) -> RlgResult<bool> {
         Err(_) => Ok(false),
     }
 }
+
+/// Compresses a file with gzip.
+///
+/// The contents of `src` are streamed through a gzip encoder into `dst`,
+/// which is created or truncated. The source file is only removed when
+/// `delete_source` is `true` and compression succeeded.
+///
+/// # Arguments
+///
+/// * `src` - A reference to a `Path` that holds the file to compress.
+/// * `dst` - A reference to a `Path` where the compressed file is written.
+/// * `delete_source` - Whether to remove `src` once `dst` is written.
+///
+/// # Returns
+///
+/// A `RlgResult<()>` which is `Ok(())` if the operation succeeds,
+/// or an error if it fails.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::compress_file;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let src = dir.path().join("example.log");
+///     tokio::fs::write(&src, "Hello, world!").await?;
+///     compress_file(&src, &dir.path().join("example.log.gz"), false).await?;
+///     assert!(src.exists());
+///     Ok(())
+/// }
+/// ```
+pub async fn compress_file(
+    src: &Path,
+    dst: &Path,
+    delete_source: bool,
+) -> RlgResult<()> {
+    let (src, dst) = (src.to_path_buf(), dst.to_path_buf());
+    run_blocking(move || gzip_file(&src, &dst, delete_source)).await
+}
+
+/// Compresses a file with gzip like `compress_file`, blocking the current
+/// thread until done.
+fn gzip_file(
+    src: &Path,
+    dst: &Path,
+    delete_source: bool,
+) -> RlgResult<()> {
+    let mut input = std::fs::File::open(src)?;
+    let output = std::fs::File::create(dst)?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    let _ = std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    if delete_source {
+        std::fs::remove_file(src)?;
+    }
+    Ok(())
+}
+
+/// Decompresses a gzip file.
+///
+/// The contents of `src` are streamed through a gzip decoder into `dst`,
+/// which is created or truncated. The source file is only removed when
+/// `delete_source` is `true` and decompression succeeded.
+///
+/// # Arguments
+///
+/// * `src` - A reference to a `Path` that holds the gzip file to decompress.
+/// * `dst` - A reference to a `Path` where the decompressed file is written.
+/// * `delete_source` - Whether to remove `src` once `dst` is written.
+///
+/// # Returns
+///
+/// A `RlgResult<()>` which is `Ok(())` if the operation succeeds,
+/// or an error if it fails, for example when `src` is not valid gzip data.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::{compress_file, decompress_file};
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let src = dir.path().join("example.log");
+///     let gz = dir.path().join("example.log.gz");
+///     let out = dir.path().join("restored.log");
+///     tokio::fs::write(&src, "Hello, world!").await?;
+///     compress_file(&src, &gz, false).await?;
+///     decompress_file(&gz, &out, true).await?;
+///     assert_eq!(tokio::fs::read_to_string(&out).await?, "Hello, world!");
+///     assert!(!gz.exists());
+///     Ok(())
+/// }
+/// ```
+pub async fn decompress_file(
+    src: &Path,
+    dst: &Path,
+    delete_source: bool,
+) -> RlgResult<()> {
+    let (src, dst) = (src.to_path_buf(), dst.to_path_buf());
+    run_blocking(move || {
+        let mut decoder = GzDecoder::new(std::fs::File::open(&src)?);
+        let mut output = std::fs::File::create(&dst)?;
+        std::io::copy(&mut decoder, &mut output)?;
+        output.sync_all()?;
+        if delete_source {
+            std::fs::remove_file(&src)?;
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Compresses a rotated log file and removes the uncompressed copy.
+///
+/// This is the compression step of log rotation, used when
+/// `Config::compress_rotated_files` is enabled: `RLG.log.1` is replaced by
+/// `RLG.log.1.gz`.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the rotated log file.
+///
+/// # Returns
+///
+/// A `RlgResult<PathBuf>` containing the path of the compressed file,
+/// or an error if the file could not be compressed or removed.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::compress_rotated_file;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let rotated = dir.path().join("RLG.log.1");
+///     tokio::fs::write(&rotated, "old entries").await?;
+///     let compressed = compress_rotated_file(&rotated).await?;
+///     assert_eq!(compressed, dir.path().join("RLG.log.1.gz"));
+///     assert!(!rotated.exists());
+///     Ok(())
+/// }
+/// ```
+pub async fn compress_rotated_file(path: &Path) -> RlgResult<PathBuf> {
+    let compressed = compressed_file_path(path);
+    compress_file(path, &compressed, true).await?;
+    Ok(compressed)
+}
+
+/// Returns the path of the gzip-compressed copy of a file, such as
+/// `RLG.log.1.gz` for `RLG.log.1`.
+fn compressed_file_path(path: &Path) -> PathBuf {
+    let mut compressed = OsString::from(path.as_os_str());
+    compressed.push(".gz");
+    PathBuf::from(compressed)
+}
+
+/// Rotates a log file if its rotation policy says it is due.
+///
+/// The file is checked against `config.log_rotation`:
+///
+/// - `Size(max)` is due once the file is larger than `max` bytes.
+/// - `Time(seconds)` is due once the file is older than `seconds`.
+/// - `Date` is due once the file was last written on a previous day (UTC).
+/// - `Count(n)` is due once the file holds `n` lines; only the lines
+///   appended since the previous check are read.
+/// - `Cron` is due once a scheduled time has passed since the file was
+///   created.
+///
+/// When rotation is due, the file is rotated, keeping
+/// `config.log_rotation_max_files` rotated files, and the newly rotated
+/// file is compressed if `config.compress_rotated_files` is set. Rotated
+/// files are named after `config.rotation_naming_convention`: numbered by
+/// `force_rotate_log_file` by default, or stamped with the rotation date
+/// or time, such as `RLG.2024-01-15.log`.
+/// Rotated files older than `config.max_log_age_days` are then moved to
+/// `config.log_archive_dir` with `archive_old_logs`, if both are set.
+/// Missing and empty files are never rotated. `Log::log()` calls this
+/// before writing each entry.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the log file.
+/// * `config` - The configuration holding the rotation policy.
+///
+/// # Returns
+///
+/// A `RlgResult<bool>` which is `Ok(true)` if the file was rotated and
+/// `Ok(false)` if rotation is not due yet, or an error if the file could
+/// not be inspected, rotated or compressed.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::{config::{Config, LogRotation}, utils::rotate_log_file};
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("RLG.log");
+///     tokio::fs::write(&path, "0123456789").await?;
+///     let config = Config {
+///         log_rotation: Some("size:8".parse::<LogRotation>()?),
+///         ..Default::default()
+///     };
+///
+///     assert!(rotate_log_file(&path, &config).await?);
+///     assert!(dir.path().join("RLG.log.1").exists());
+///     assert!(!rotate_log_file(&path, &config).await?);
+///     Ok(())
+/// }
+/// ```
+pub async fn rotate_log_file(
+    path: &Path,
+    config: &Config,
+) -> RlgResult<bool> {
+    if config.log_rotation.is_none() {
+        return Ok(false);
+    }
+    let (path, config) = (path.to_path_buf(), config.clone());
+    run_blocking(move || rotate_log_file_blocking(&path, &config)).await
+}
+
+/// Rotates a log file if it is due like `rotate_log_file`, blocking the
+/// current thread until done.
+pub(crate) fn rotate_log_file_blocking(
+    path: &Path,
+    config: &Config,
+) -> RlgResult<bool> {
+    let rotation = match &config.log_rotation {
+        Some(rotation) => rotation,
+        None => return Ok(false),
+    };
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) if metadata.len() > 0 => metadata,
+        Ok(_) => return Ok(false),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(false)
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let due = match rotation {
+        LogRotation::Size(max) => metadata.len() > max.get(),
+        LogRotation::Count(max) => {
+            running_line_count(path, metadata.len())? >= u64::from(*max)
+        }
+        _ => age_rotation_due(rotation, &metadata)?,
+    };
+    if !due {
+        return Ok(false);
+    }
+
+    let rotated = match config.rotation_naming_convention {
+        RotationNamingConvention::Numeric => {
+            force_rotate_log_file(path, config.log_rotation_max_files)
+        }
+        convention => stamp_rotate_log_file(
+            path,
+            convention,
+            config.log_rotation_max_files,
+        ),
+    };
+    let _ = LINE_COUNTS.lock().remove(path);
+    let rotated = match rotated {
+        Ok(rotated) => rotated,
+        // Another writer rotated the file first.
+        Err(RlgError::IoError(e))
+            if e.kind() == std::io::ErrorKind::NotFound =>
+        {
+            return Ok(false)
+        }
+        Err(e) => return Err(e),
+    };
+    if config.compress_rotated_files {
+        gzip_file(&rotated, &compressed_file_path(&rotated), true)?;
+    }
+    if let (Some(archive_dir), Some(max_age_days)) =
+        (&config.log_archive_dir, config.max_log_age_days)
+    {
+        let _ =
+            archive_old_logs_blocking(path, max_age_days, archive_dir)?;
+    }
+    Ok(true)
+}
+
+/// Estimates how long it will be before a log file is rotated.
+///
+/// - `Size` and `Count` extrapolate the rate at which bytes or lines were
+///   written since the file was created (or last modified, if earlier or
+///   if creation times are not recorded) to the remaining capacity.
+/// - `Time` returns the rest of the interval since the file was created,
+///   as used by `rotate_log_file`.
+/// - `Cron` returns the time until the next scheduled trigger.
+/// - `Date` rotates on the first write after midnight, which cannot be
+///   predicted, so it is never estimated.
+///
+/// A file that is already due is estimated at `Duration::ZERO`.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the log file.
+/// * `rotation` - The rotation policy of the log file.
+///
+/// # Returns
+///
+/// A `RlgResult<Option<Duration>>` with the estimated time until rotation,
+/// or `None` if it cannot be estimated, such as for missing or empty
+/// files under `Size` and `Count`, or an error if the file could not be
+/// read.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::{config::LogRotation, utils::estimate_rotation_time};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("RLG.log");
+///     tokio::fs::write(&path, "entry\n").await?;
+///
+///     let rotation = "time:3600".parse::<LogRotation>()?;
+///     let estimate = estimate_rotation_time(&path, &rotation).await?;
+///     assert!(estimate.unwrap() <= Duration::from_secs(3600));
+///     assert_eq!(
+///         estimate_rotation_time(&path, &LogRotation::Date).await?,
+///         None
+///     );
+///     Ok(())
+/// }
+/// ```
+pub async fn estimate_rotation_time(
+    path: &Path,
+    rotation: &LogRotation,
+) -> RlgResult<Option<Duration>> {
+    let metadata = match fs::metadata(path).await {
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    // Extrapolates the growth of the file to the remaining capacity.
+    let extrapolate = |current: u64, max: u64| -> Option<Duration> {
+        let metadata = metadata.as_ref()?;
+        let elapsed = [metadata.created(), metadata.modified()]
+            .into_iter()
+            .filter_map(Result::ok)
+            .min()?
+            .elapsed()
+            .ok()?;
+        if current == 0 || elapsed.is_zero() {
+            return None;
+        }
+        let remaining = max.saturating_sub(current);
+        let seconds =
+            elapsed.as_secs_f64() * remaining as f64 / current as f64;
+        // `Duration::from_secs_f64` panics on values it cannot hold.
+        if !seconds.is_finite() || seconds >= u64::MAX as f64 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(seconds))
+    };
+
+    Ok(match rotation {
+        LogRotation::Size(max) => extrapolate(
+            metadata.as_ref().map_or(0, std::fs::Metadata::len),
+            max.get(),
+        ),
+        LogRotation::Count(max) => {
+            let count = match metadata {
+                Some(_) => count_lines(path).await?,
+                None => 0,
+            };
+            extrapolate(count, u64::from(*max))
+        }
+        LogRotation::Time(seconds) => {
+            let age =
+                metadata.as_ref().map(file_age).unwrap_or_default();
+            Some(Duration::from_secs(seconds.get()).saturating_sub(age))
+        }
+        LogRotation::Date => None,
+        LogRotation::Cron(_) => {
+            let now = chrono::Utc::now();
+            rotation
+                .next_trigger(now)
+                .and_then(|next| (next - now).to_std().ok())
+        }
+    })
+}
+
+/// Returns whether a file is due for rotation under a time-based policy
+/// (`Time`, `Date` or `Cron`); always `false` for `Size` and `Count`.
+pub(crate) fn age_rotation_due(
+    rotation: &LogRotation,
+    metadata: &std::fs::Metadata,
+) -> RlgResult<bool> {
+    Ok(match rotation {
+        LogRotation::Time(seconds) => {
+            file_age(metadata) > Duration::from_secs(seconds.get())
+        }
+        LogRotation::Date => {
+            let modified: chrono::DateTime<chrono::Utc> =
+                metadata.modified()?.into();
+            modified.date_naive() < chrono::Utc::now().date_naive()
+        }
+        LogRotation::Cron(_) => {
+            let age = chrono::Duration::from_std(file_age(metadata))
+                .unwrap_or_else(|_| chrono::Duration::zero());
+            let now = chrono::Utc::now();
+            rotation
+                .next_trigger(now - age)
+                .map_or(false, |next| next <= now)
+        }
+        LogRotation::Size(_) | LogRotation::Count(_) => false,
+    })
+}
+
+/// Lines counted by `running_line_count`, keyed by log file: the offset
+/// just past the last newline counted and the number of lines before it.
+static LINE_COUNTS: Lazy<Mutex<HashMap<PathBuf, (u64, u64)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Counts the lines in a log file of `len` bytes like `count_lines`, but
+/// only reads what was appended since the previous count.
+///
+/// The count restarts from the beginning if the file shrank, such as
+/// after being rotated or truncated.
+fn running_line_count(path: &Path, len: u64) -> RlgResult<u64> {
+    let counted = LINE_COUNTS.lock().get(path).copied();
+    let (mut offset, mut lines) = match counted {
+        Some((offset, lines)) if offset <= len => (offset, lines),
+        _ => (0, 0),
+    };
+    if offset < len {
+        let mut file = std::fs::File::open(path)?;
+        let _ = file.seek(std::io::SeekFrom::Start(offset))?;
+        let mut reader =
+            std::io::BufReader::new(file.take(len - offset));
+        let mut line = Vec::new();
+        loop {
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 || line.last() != Some(&b'\n') {
+                break;
+            }
+            offset += read as u64;
+            lines += 1;
+            line.clear();
+        }
+        let _ = LINE_COUNTS
+            .lock()
+            .insert(path.to_path_buf(), (offset, lines));
+    }
+    // A final unterminated line is counted, but read again next time.
+    Ok(lines + u64::from(offset < len))
+}
+
+/// Counts the lines in a file, including a final unterminated line.
+pub(crate) async fn count_lines(path: &Path) -> RlgResult<u64> {
+    let mut reader = BufReader::new(File::open(path).await?);
+    let mut line = Vec::new();
+    let mut count = 0_u64;
+    while reader.read_until(b'\n', &mut line).await? > 0 {
+        count += 1;
+        line.clear();
+    }
+    Ok(count)
+}
+
+/// Returns how long ago a file was created, or last modified on platforms
+/// that do not record creation times.
+pub(crate) fn file_age(metadata: &std::fs::Metadata) -> Duration {
+    metadata
+        .created()
+        .or_else(|_| metadata.modified())
+        .ok()
+        .and_then(|time| time.elapsed().ok())
+        .unwrap_or_default()
+}
+
+/// Rotates a log file now, keeping at most `max_files` rotated files.
+///
+/// Existing rotated files are shifted up by one, `RLG.log.N` becoming
+/// `RLG.log.N+1` (compressed `RLG.log.N.gz` files are shifted alike), the
+/// log file itself is renamed to `RLG.log.1`, and rotated files beyond
+/// `max_files` are then deleted with `enforce_max_rotation_files`.
+///
+/// # Arguments
+///
+/// * `base_path` - A reference to a `Path` that holds the log file.
+/// * `max_files` - The number of rotated files to keep.
+///
+/// # Returns
+///
+/// A `RlgResult<PathBuf>` containing the path of the newly rotated file,
+/// or an error if a file could not be renamed or deleted.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::force_rotate_log_file;
+///
+/// let dir = tempfile::tempdir()?;
+/// let log = dir.path().join("RLG.log");
+/// std::fs::write(&log, "second")?;
+/// std::fs::write(dir.path().join("RLG.log.1"), "first")?;
+///
+/// let rotated = force_rotate_log_file(&log, 1)?;
+/// assert_eq!(rotated, dir.path().join("RLG.log.1"));
+/// assert_eq!(std::fs::read_to_string(&rotated)?, "second");
+/// assert!(!log.exists());
+/// assert!(!dir.path().join("RLG.log.2").exists());
+/// # Ok::<(), rlg::error::RlgError>(())
+/// ```
+pub fn force_rotate_log_file(
+    base_path: &Path,
+    max_files: u32,
+) -> RlgResult<PathBuf> {
+    for n in (1..=max_files).rev() {
+        for suffix in ["", ".gz"] {
+            let from = rotated_file_path(base_path, n, suffix);
+            if from.exists() {
+                std::fs::rename(
+                    &from,
+                    rotated_file_path(base_path, n + 1, suffix),
+                )?;
+            }
+        }
+    }
+    let rotated = rotated_file_path(base_path, 1, "");
+    std::fs::rename(base_path, &rotated)?;
+    enforce_max_rotation_files(base_path, max_files)?;
+    Ok(rotated)
+}
+
+/// Deletes the rotated files of a log file beyond the `max_files` most
+/// recent.
+///
+/// Rotated files are the files named `<log file name>.N` or
+/// `<log file name>.N.gz` in the directory of the log file, where `N` is
+/// a positive number; those with `N` greater than `max_files` are
+/// deleted.
+///
+/// # Arguments
+///
+/// * `base_path` - A reference to a `Path` that holds the log file.
+/// * `max_files` - The number of rotated files to keep.
+///
+/// # Returns
+///
+/// A `RlgResult<()>` which is `Ok(())` if the operation succeeds, or an
+/// error if the directory could not be read or a file could not be
+/// deleted.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::enforce_max_rotation_files;
+///
+/// let dir = tempfile::tempdir()?;
+/// let log = dir.path().join("RLG.log");
+/// for n in 1..=3 {
+///     std::fs::write(dir.path().join(format!("RLG.log.{}", n)), "")?;
+/// }
+///
+/// enforce_max_rotation_files(&log, 2)?;
+/// assert!(dir.path().join("RLG.log.2").exists());
+/// assert!(!dir.path().join("RLG.log.3").exists());
+/// # Ok::<(), rlg::error::RlgError>(())
+/// ```
+pub fn enforce_max_rotation_files(
+    base_path: &Path,
+    max_files: u32,
+) -> RlgResult<()> {
+    let file_name = match base_path.file_name() {
+        Some(file_name) => file_name.to_string_lossy().into_owned(),
+        None => return Ok(()),
+    };
+    let directory = match base_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let number = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| rotated_file_number(name, &file_name));
+        match number {
+            Some(n) if n > u64::from(max_files) => {
+                std::fs::remove_file(entry.path())?
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Rotates a log file now under a date or time-stamped naming convention,
+/// keeping at most `max_files` rotated files.
+///
+/// `RLG.log` is renamed to `RLG.<stamp>.log`, or `RLG.<stamp>.<n>.log` if
+/// a file was already rotated with the same stamp. Older files named with
+/// the same convention beyond `max_files` are then deleted.
+fn stamp_rotate_log_file(
+    base_path: &Path,
+    convention: RotationNamingConvention,
+    max_files: u32,
+) -> RlgResult<PathBuf> {
+    let stamp = match convention {
+        RotationNamingConvention::DateStamped => generate_timestamp()
+            .split('T')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        _ => chrono::Utc::now().timestamp_millis().to_string(),
+    };
+    let (stem, extension) = split_log_file_name(base_path);
+    let directory = match base_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut n = 0;
+    let rotated = loop {
+        let name = match n {
+            0 => format!("{}.{}{}", stem, stamp, extension),
+            _ => format!("{}.{}.{}{}", stem, stamp, n, extension),
+        };
+        let candidate = directory.join(&name);
+        if !candidate.exists() && !directory.join(name + ".gz").exists()
+        {
+            break candidate;
+        }
+        n += 1;
+    };
+    std::fs::rename(base_path, &rotated)?;
+
+    let mut rotated_files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let key = entry.file_name().to_str().and_then(|name| {
+            stamped_file_key(name, &stem, &extension, convention)
+        });
+        if let Some(key) = key {
+            rotated_files.push((key, entry.path()));
+        }
+    }
+    rotated_files.sort_by(|a, b| b.0.cmp(&a.0));
+    for (_, path) in rotated_files.into_iter().skip(max_files as usize)
+    {
+        std::fs::remove_file(path)?;
+    }
+    Ok(rotated)
+}
+
+/// Splits the name of a log file into its stem and extension, such as
+/// `RLG` and `.log`; the extension is empty if there is none.
+fn split_log_file_name(path: &Path) -> (String, String) {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (stem, extension)
+}
+
+/// Returns the order of a file rotated by `stamp_rotate_log_file`, newest
+/// greatest, or `None` if `name` is not such a file.
+fn stamped_file_key(
+    name: &str,
+    stem: &str,
+    extension: &str,
+    convention: RotationNamingConvention,
+) -> Option<(usize, String, u64)> {
+    let rest = name.strip_prefix(stem)?.strip_prefix('.')?;
+    let rest = rest.strip_suffix(".gz").unwrap_or(rest);
+    let rest = rest.strip_suffix(extension)?;
+    let (stamp, n) = match rest.split_once('.') {
+        Some((stamp, n)) if n.bytes().all(|b| b.is_ascii_digit()) => {
+            (stamp, n.parse().ok()?)
+        }
+        Some(_) => return None,
+        None => (rest, 0),
+    };
+    let valid = match convention {
+        RotationNamingConvention::DateStamped => {
+            chrono::NaiveDate::parse_from_str(stamp, "%Y-%m-%d").is_ok()
+        }
+        RotationNamingConvention::TimestampMillis => {
+            !stamp.is_empty()
+                && stamp.bytes().all(|b| b.is_ascii_digit())
+        }
+        RotationNamingConvention::Numeric => false,
+    };
+    // Equal-length stamps compare chronologically as strings.
+    valid.then(|| (stamp.len(), stamp.to_string(), n))
+}
+
+/// Returns `N` if `name` is `<file_name>.N` or `<file_name>.N.gz`, the
+/// name of a rotated file of the log file `file_name`.
+fn rotated_file_number(name: &str, file_name: &str) -> Option<u64> {
+    let number = name.strip_prefix(file_name)?.strip_prefix('.')?;
+    let number = number.strip_suffix(".gz").unwrap_or(number);
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    number.parse().ok()
+}
+
+/// Returns the path of the `n`th rotated file of a log file.
+fn rotated_file_path(
+    base_path: &Path,
+    n: u32,
+    suffix: &str,
+) -> PathBuf {
+    let mut path = OsString::from(base_path.as_os_str());
+    path.push(format!(".{}{}", n, suffix));
+    PathBuf::from(path)
+}
+
+/// Moves the rotated files of a log file that are older than
+/// `max_age_days` days to `archive_dir`.
+///
+/// The rotated files of `RLG.log` are the files of its directory named
+/// `RLG.log.N` or `RLG.log.N.gz`, as kept by
+/// `enforce_max_rotation_files`, or named after a date or time-stamped
+/// `RotationNamingConvention`, such as `RLG.2024-01-15.log`; other files,
+/// including those of other log files, are left alone. Their age is taken from their modification
+/// time. `archive_dir` is created if needed, and files already in it with
+/// the same name are replaced. `Log::log()` calls this after each rotation
+/// when `Config::log_archive_dir` and `Config::max_log_age_days` are set.
+///
+/// # Arguments
+///
+/// * `base_path` - A reference to a `Path` that holds the log file.
+/// * `max_age_days` - The age in days beyond which files are archived.
+/// * `archive_dir` - A reference to a `Path` that holds the directory the
+///   files are moved to.
+///
+/// # Returns
+///
+/// A `RlgResult<u32>` containing the number of files archived, or an
+/// error if the directory of the log file could not be read, the archive
+/// directory could not be created or a file could not be moved.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::archive_old_logs;
+/// use std::time::{Duration, SystemTime};
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let old = std::fs::File::create(dir.path().join("RLG.log.1"))?;
+///     old.set_modified(SystemTime::now() - Duration::from_secs(3 * 24 * 60 * 60))?;
+///     std::fs::write(dir.path().join("RLG.log"), "")?;
+///
+///     let archive = dir.path().join("archive");
+///     let log = dir.path().join("RLG.log");
+///     assert_eq!(archive_old_logs(&log, 2, &archive).await?, 1);
+///     assert!(archive.join("RLG.log.1").exists());
+///     assert!(dir.path().join("RLG.log").exists());
+///     Ok(())
+/// }
+/// ```
+pub async fn archive_old_logs(
+    base_path: &Path,
+    max_age_days: u64,
+    archive_dir: &Path,
+) -> RlgResult<u32> {
+    let (base_path, archive_dir) =
+        (base_path.to_path_buf(), archive_dir.to_path_buf());
+    run_blocking(move || {
+        archive_old_logs_blocking(
+            &base_path,
+            max_age_days,
+            &archive_dir,
+        )
+    })
+    .await
+}
+
+/// Moves old rotated log files to `archive_dir` like `archive_old_logs`,
+/// blocking the current thread until done.
+fn archive_old_logs_blocking(
+    base_path: &Path,
+    max_age_days: u64,
+    archive_dir: &Path,
+) -> RlgResult<u32> {
+    let file_name = match base_path.file_name() {
+        Some(file_name) => file_name.to_string_lossy().into_owned(),
+        None => return Ok(0),
+    };
+    let (stem, extension) = split_log_file_name(base_path);
+    let directory = match base_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let max_age =
+        Duration::from_secs(max_age_days.saturating_mul(24 * 60 * 60));
+    let mut archived = 0;
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let is_rotated = name.to_str().map_or(false, |name| {
+            rotated_file_number(name, &file_name).is_some()
+                || [
+                    RotationNamingConvention::DateStamped,
+                    RotationNamingConvention::TimestampMillis,
+                ]
+                .into_iter()
+                .any(|convention| {
+                    stamped_file_key(
+                        name, &stem, &extension, convention,
+                    )
+                    .is_some()
+                })
+        });
+        if !is_rotated {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        let age = metadata.modified()?.elapsed().unwrap_or_default();
+        if !metadata.is_file() || age <= max_age {
+            continue;
+        }
+
+        if archived == 0 {
+            std::fs::create_dir_all(archive_dir)?;
+        }
+        let from = entry.path();
+        let to = archive_dir.join(&name);
+        // Renaming fails across file systems, where the file is copied.
+        if std::fs::rename(&from, &to).is_err() {
+            let _ = std::fs::copy(&from, &to)?;
+            std::fs::remove_file(&from)?;
+        }
+        archived += 1;
+    }
+    Ok(archived)
+}
+
+/// Statistics about the entries in a log file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LogFileStats {
+    /// Number of entries that could be parsed.
+    pub entry_count: u64,
+    /// Number of entries for each level, keyed by level name.
+    pub level_counts: HashMap<String, u64>,
+    /// Size of the file in bytes.
+    pub file_size_bytes: u64,
+    /// Timestamp of the first entry in the file.
+    pub first_entry_time: Option<String>,
+    /// Timestamp of the last entry in the file.
+    pub last_entry_time: Option<String>,
+}
+
+/// Collects statistics about the CLF entries in a log file.
+///
+/// The file is read line by line and every line is parsed with
+/// `Log::from_clf_str`; lines that are not CLF entries are skipped.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the log file to scan.
+///
+/// # Returns
+///
+/// A `RlgResult<LogFileStats>` with the entry count, the count of entries
+/// per level, the file size and the timestamps of the first and last
+/// entries, or an error if the file could not be read.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::log_file_statistics;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("example.log");
+///     tokio::fs::write(
+///         &path,
+///         "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=started Level=INFO Component=app Format=CLF\n",
+///     )
+///     .await?;
+///     let stats = log_file_statistics(&path).await?;
+///     assert_eq!(stats.entry_count, 1);
+///     assert_eq!(stats.level_counts["INFO"], 1);
+///     Ok(())
+/// }
+/// ```
+pub async fn log_file_statistics(
+    path: &Path,
+) -> RlgResult<LogFileStats> {
+    let file = File::open(path).await?;
+    let mut stats = LogFileStats {
+        file_size_bytes: file.metadata().await?.len(),
+        ..LogFileStats::default()
+    };
+
+    let mut lines = BufReader::new(file).lines();
+    while let Some(line) = lines.next_line().await? {
+        let log = match Log::from_clf_str(&line) {
+            Ok(log) => log,
+            Err(_) => continue,
+        };
+        stats.entry_count += 1;
+        *stats
+            .level_counts
+            .entry(log.level.to_string())
+            .or_insert(0) += 1;
+        if stats.first_entry_time.is_none() {
+            stats.first_entry_time = Some(log.time.clone());
+        }
+        stats.last_entry_time = Some(log.time);
+    }
+    Ok(stats)
+}
+
+/// Counts the `ERROR` and `FATAL` entries in a log file.
+///
+/// This is a shortcut for `count_level_in_file` over both levels, reading
+/// the file once.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the log file to scan.
+///
+/// # Returns
+///
+/// A `RlgResult<u64>` with the number of `ERROR` and `FATAL` entries, or
+/// an error if the file could not be read.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::count_errors_in_file;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("example.log");
+///     tokio::fs::write(
+///         &path,
+///         "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=crashed Level=FATAL Component=app Format=CLF\n\
+///          {\"SessionID\":\"2\",\"Level\":\"ERROR\",\"Description\":\"failed\"}\n",
+///     )
+///     .await?;
+///     assert_eq!(count_errors_in_file(&path).await?, 2);
+///     Ok(())
+/// }
+/// ```
+pub async fn count_errors_in_file(path: &Path) -> RlgResult<u64> {
+    count_lines_with_levels(path, &[LogLevel::ERROR, LogLevel::FATAL])
+        .await
+}
+
+/// Counts the entries of a given level in a log file.
+///
+/// Instead of parsing entries, every line is searched for the
+/// `Level=<LEVEL>` marker of CLF entries or the `"Level":"<LEVEL>"` key
+/// of JSON entries, which makes this fast enough for health checks on
+/// large files. Lines are counted once even if they contain several
+/// markers.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the log file to scan.
+/// * `level` - The level of the entries to count.
+///
+/// # Returns
+///
+/// A `RlgResult<u64>` with the number of entries of `level`, or an error
+/// if the file could not be read.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::{log_level::LogLevel, utils::count_level_in_file};
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("example.log");
+///     tokio::fs::write(
+///         &path,
+///         "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=slow Level=WARN Component=app Format=CLF\n",
+///     )
+///     .await?;
+///     assert_eq!(count_level_in_file(&path, LogLevel::WARN).await?, 1);
+///     assert_eq!(count_level_in_file(&path, LogLevel::INFO).await?, 0);
+///     Ok(())
+/// }
+/// ```
+pub async fn count_level_in_file(
+    path: &Path,
+    level: LogLevel,
+) -> RlgResult<u64> {
+    count_lines_with_levels(path, &[level]).await
+}
+
+/// Counts the lines of `path` containing the CLF or JSON level marker of
+/// any of `levels`.
+async fn count_lines_with_levels(
+    path: &Path,
+    levels: &[LogLevel],
+) -> RlgResult<u64> {
+    let markers: Vec<Vec<u8>> = levels
+        .iter()
+        .flat_map(|level| {
+            vec![
+                format!("Level={}", level).into_bytes(),
+                format!("\"Level\":\"{}\"", level).into_bytes(),
+            ]
+        })
+        .collect();
+
+    let mut reader = BufReader::new(File::open(path).await?);
+    let mut line = Vec::new();
+    let mut count = 0;
+    while reader.read_until(b'\n', &mut line).await? > 0 {
+        if markers.iter().any(|marker| {
+            line.windows(marker.len())
+                .any(|window| window == &marker[..])
+        }) {
+            count += 1;
+        }
+        line.clear();
+    }
+    Ok(count)
+}
+
+/// Splits a CLF log file into one file per log level.
+///
+/// The file is read line by line and every line is parsed with
+/// `Log::from_clf_str`; each entry is copied unchanged to
+/// `dest_dir/<LEVEL>.log`, for example `ERROR.log`. Lines that are not CLF
+/// entries are skipped. `dest_dir` is created if it does not exist, and
+/// existing output files are overwritten.
+///
+/// # Arguments
+///
+/// * `src` - A reference to a `Path` that holds the log file to split.
+/// * `dest_dir` - A reference to a `Path` that holds the directory to
+///   write the per-level files to.
+///
+/// # Returns
+///
+/// A `RlgResult<HashMap<LogLevel, PathBuf>>` mapping each level that had
+/// at least one entry to the file its entries were written to, or an
+/// error if a file could not be read or written.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::{log_level::LogLevel, utils::split_log_file_by_level};
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("mixed.log");
+///     tokio::fs::write(
+///         &path,
+///         "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=started Level=INFO Component=app Format=CLF\n\
+///          SessionID=2 Timestamp=2024-01-01T00:00:01Z Description=crashed Level=ERROR Component=app Format=CLF\n",
+///     )
+///     .await?;
+///     let files = split_log_file_by_level(&path, &dir.path().join("split")).await?;
+///     assert_eq!(files.len(), 2);
+///     assert_eq!(files[&LogLevel::ERROR], dir.path().join("split").join("ERROR.log"));
+///     Ok(())
+/// }
+/// ```
+pub async fn split_log_file_by_level(
+    src: &Path,
+    dest_dir: &Path,
+) -> RlgResult<HashMap<LogLevel, PathBuf>> {
+    let mut lines = BufReader::new(File::open(src).await?).lines();
+    fs::create_dir_all(dest_dir).await?;
+
+    let mut outputs: HashMap<LogLevel, (PathBuf, BufWriter<File>)> =
+        HashMap::new();
+    while let Some(line) = lines.next_line().await? {
+        let level = match Log::from_clf_str(&line) {
+            Ok(log) => log.level,
+            Err(_) => continue,
+        };
+        let (_, writer) = match outputs.entry(level) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let path = dest_dir.join(format!("{}.log", level));
+                let file = File::create(&path).await?;
+                entry.insert((path, BufWriter::new(file)))
+            }
+        };
+        writer.write_all(line.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+    }
+
+    let mut files = HashMap::with_capacity(outputs.len());
+    for (level, (path, mut writer)) in outputs {
+        writer.flush().await?;
+        files.insert(level, path);
+    }
+    Ok(files)
+}
+
+/// Merges several CLF log files into one.
+///
+/// When `sorted` is `false`, the files are concatenated in the order
+/// given. When it is `true`, their lines are merged by the `Timestamp=`
+/// field of each entry, assuming each file is already in timestamp
+/// order, as rotated log files are; entries with the same timestamp keep
+/// the order of `sources`. Lines that are not CLF entries, or whose
+/// timestamp is not RFC 3339, stay after the line that precedes them in
+/// their file.
+///
+/// # Arguments
+///
+/// * `sources` - The log files to merge.
+/// * `dest` - A reference to a `Path` that holds the merged file, which
+///   is created or overwritten.
+/// * `sorted` - Whether to merge the files in timestamp order.
+///
+/// # Returns
+///
+/// A `RlgResult<u64>` containing the number of lines written, or an error
+/// if a file could not be read or written.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::merge_log_files;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let (old, new) = (dir.path().join("app.log.1"), dir.path().join("app.log"));
+///     tokio::fs::write(&old, "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=old Level=INFO Component=app\n").await?;
+///     tokio::fs::write(&new, "SessionID=2 Timestamp=2024-01-02T00:00:00Z Description=new Level=INFO Component=app\n").await?;
+///
+///     let merged = dir.path().join("merged.log");
+///     assert_eq!(merge_log_files(&[&new, &old], &merged, true).await?, 2);
+///     assert!(tokio::fs::read_to_string(&merged).await?.starts_with("SessionID=1 "));
+///     Ok(())
+/// }
+/// ```
+pub async fn merge_log_files(
+    sources: &[&Path],
+    dest: &Path,
+    sorted: bool,
+) -> RlgResult<u64> {
+    let mut readers = Vec::with_capacity(sources.len());
+    for source in sources {
+        readers.push(BufReader::new(File::open(source).await?).lines());
+    }
+    let mut writer = BufWriter::new(File::create(dest).await?);
+    let mut written = 0;
+
+    if sorted {
+        // The next line of each source, smallest timestamp first. Lines
+        // without a timestamp take that of the previous line.
+        let mut last_times = vec![None; readers.len()];
+        let mut heap = BinaryHeap::with_capacity(readers.len());
+        for (index, reader) in readers.iter_mut().enumerate() {
+            if let Some(line) = reader.next_line().await? {
+                let time = merge_time(&line, &mut last_times[index]);
+                heap.push(Reverse((time, index, line)));
+            }
+        }
+        while let Some(Reverse((_, index, line))) = heap.pop() {
+            write_merged_line(&mut writer, &line).await?;
+            written += 1;
+            if let Some(line) = readers[index].next_line().await? {
+                let time = merge_time(&line, &mut last_times[index]);
+                heap.push(Reverse((time, index, line)));
+            }
+        }
+    } else {
+        for reader in &mut readers {
+            while let Some(line) = reader.next_line().await? {
+                write_merged_line(&mut writer, &line).await?;
+                written += 1;
+            }
+        }
+    }
+
+    writer.flush().await?;
+    Ok(written)
+}
+
+/// Returns the timestamp a line is merged by, remembering it in `last`
+/// for the lines that follow.
+fn merge_time(
+    line: &str,
+    last: &mut Option<chrono::DateTime<chrono::FixedOffset>>,
+) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    if let Some(time) = Log::from_clf_str(line).ok().and_then(|log| {
+        chrono::DateTime::parse_from_rfc3339(&log.time).ok()
+    }) {
+        *last = Some(time);
+    }
+    *last
+}
+
+/// Writes a line of a merged log file.
+async fn write_merged_line(
+    writer: &mut BufWriter<File>,
+    line: &str,
+) -> RlgResult<()> {
+    writer.write_all(line.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    Ok(())
+}
+
+/// Removes repeated entries from a CLF log file, such as those logged by
+/// a process caught in a crash-restart loop.
+///
+/// A run of consecutive entries with the same level, component and
+/// description, each logged within `window` of the one before it, is
+/// written as its first entry followed by a `(repeated N times)` line,
+/// where `N` is the number of entries left out. Timestamps are read with
+/// `parse_datetime`; entries whose timestamp cannot be parsed, and lines
+/// that are not CLF entries, are never deduplicated and are copied as
+/// they are.
+///
+/// # Arguments
+///
+/// * `src` - A reference to a `Path` that holds the log file to read.
+/// * `dest` - A reference to a `Path` that holds the deduplicated file,
+///   which is created or overwritten.
+/// * `window` - The longest time between two entries of the same run.
+///
+/// # Returns
+///
+/// A `RlgResult<u64>` containing the number of entries left out, or an
+/// error if a file could not be read or written.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::utils::deduplicate_log_file;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let (src, dest) = (dir.path().join("app.log"), dir.path().join("dedup.log"));
+///     let entry = "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=crashed Level=ERROR Component=app Format=CLF\n";
+///     tokio::fs::write(&src, entry.repeat(3)).await?;
+///
+///     assert_eq!(deduplicate_log_file(&src, &dest, Duration::from_secs(1)).await?, 2);
+///     assert_eq!(
+///         tokio::fs::read_to_string(&dest).await?,
+///         format!("{}(repeated 2 times)\n", entry)
+///     );
+///     Ok(())
+/// }
+/// ```
+pub async fn deduplicate_log_file(
+    src: &Path,
+    dest: &Path,
+    window: Duration,
+) -> RlgResult<u64> {
+    let mut lines = BufReader::new(File::open(src).await?).lines();
+    let mut writer = BufWriter::new(File::create(dest).await?);
+    let mut run: Option<RepeatedRun> = None;
+    let mut suppressed = 0;
+
+    while let Some(line) = lines.next_line().await? {
+        let entry = Log::from_clf_str(&line).ok().and_then(|log| {
+            let time = parse_datetime(&log.time).ok()?.unix_timestamp();
+            Some(((log.level, log.component, log.description), time))
+        });
+        if let (Some((key, time)), Some(current)) = (&entry, &mut run) {
+            let elapsed = Duration::from_secs(
+                (time - current.last_time).unsigned_abs(),
+            );
+            if *key == current.key && elapsed <= window {
+                current.last_time = *time;
+                current.repeats += 1;
+                suppressed += 1;
+                continue;
+            }
+        }
+
+        if let Some(finished) = run.take() {
+            finished.write_summary(&mut writer).await?;
+        }
+        run = entry.map(|(key, last_time)| RepeatedRun {
+            key,
+            last_time,
+            repeats: 0,
+        });
+        writer.write_all(line.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+    }
+    if let Some(finished) = run {
+        finished.write_summary(&mut writer).await?;
+    }
+
+    writer.flush().await?;
+    Ok(suppressed)
+}
+
+/// A run of identical entries being deduplicated by
+/// `deduplicate_log_file`.
+struct RepeatedRun {
+    /// The level, component and description of the entries.
+    key: (LogLevel, String, String),
+    /// The Unix timestamp of the last entry of the run.
+    last_time: i64,
+    /// The number of entries after the first one.
+    repeats: u64,
+}
+
+impl RepeatedRun {
+    /// Writes the summary line of the run, if any entry was left out.
+    async fn write_summary(
+        &self,
+        writer: &mut BufWriter<File>,
+    ) -> RlgResult<()> {
+        if self.repeats > 0 {
+            let summary =
+                format!("(repeated {} times)\n", self.repeats);
+            writer.write_all(summary.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The entries read from a log file by `parse_log_file`.
+#[derive(Debug, Default)]
+pub struct ParsedLogFile {
+    /// Entries parsed from the valid lines, in file order.
+    pub logs: Vec<Log>,
+    /// A `RlgError::FormatParseError` for each line that failed to parse.
+    pub parse_errors: Vec<RlgError>,
+}
+
+/// Reads the entries of a log file written in `format`.
+///
+/// Each non-empty line is parsed with `Log::from_clf_str` for CLF files
+/// or `Log::from_json_str` for JSON files. Lines that fail to parse are
+/// reported in `parse_errors`, so the valid lines are still returned.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the log file to read.
+/// * `format` - The format the log file was written in.
+///
+/// # Returns
+///
+/// A `RlgResult<ParsedLogFile>` with the parsed entries and parse errors,
+/// or an error if the file could not be read or `format` cannot be
+/// parsed.
+///
+/// # Examples
+///
+/// ```
+/// use rlg::log_format::LogFormat;
+/// use rlg::utils::parse_log_file;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("example.log");
+///     tokio::fs::write(
+///         &path,
+///         "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=started Level=INFO Component=app Format=CLF\nnot a log entry\n",
+///     )
+///     .await?;
+///     let parsed = parse_log_file(&path, LogFormat::CLF).await?;
+///     assert_eq!(parsed.logs.len(), 1);
+///     assert_eq!(parsed.parse_errors.len(), 1);
+///     Ok(())
+/// }
+/// ```
+pub async fn parse_log_file(
+    path: &Path,
+    format: LogFormat,
+) -> RlgResult<ParsedLogFile> {
+    read_log_file(path, format, false).await
+}
+
+/// Reads the entries of a log file written in `format`, failing on the
+/// first line that cannot be parsed.
+///
+/// This behaves like `parse_log_file`, except that any invalid line is
+/// returned as an error.
+///
+/// # Arguments
+///
+/// * `path` - A reference to a `Path` that holds the log file to read.
+/// * `format` - The format the log file was written in.
+///
+/// # Returns
+///
+/// A `RlgResult<Vec<Log>>` with the parsed entries, or a
+/// `RlgError::FormatParseError` naming the first invalid line.
+pub async fn parse_log_file_strict(
+    path: &Path,
+    format: LogFormat,
+) -> RlgResult<Vec<Log>> {
+    Ok(read_log_file(path, format, true).await?.logs)
+}
+
+/// Parses a log file line by line, stopping at the first invalid line if
+/// `strict` is set.
+async fn read_log_file(
+    path: &Path,
+    format: LogFormat,
+    strict: bool,
+) -> RlgResult<ParsedLogFile> {
+    let parse = line_parser(format)?;
+    let mut parsed = ParsedLogFile::default();
+    let mut lines = BufReader::new(File::open(path).await?).lines();
+    let mut line_number = 0;
+    while let Some(line) = lines.next_line().await? {
+        line_number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(&line) {
+            Ok(log) => parsed.logs.push(log),
+            Err(e) => {
+                let error = RlgError::FormatParseError(format!(
+                    "Line {}: {}",
+                    line_number, e
+                ));
+                if strict {
+                    return Err(error);
+                }
+                parsed.parse_errors.push(error);
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Streams the entries of a log file written in `format`, one line at a
+/// time.
+///
+/// Unlike `parse_log_file`, the file is never held in memory as a whole,
+/// so arbitrarily large files can be read. Each non-empty line is parsed
+/// with `Log::from_clf_str` for CLF files or `Log::from_json_str` for JSON
+/// files. A line that fails to parse yields a `RlgError::FormatParseError`
+/// naming the line, and reading continues with the next line. The stream
+/// ends at the end of the file.
+///
+/// # Errors
+///
+/// The stream yields a single `RlgError::UnsupportedFormat` if `format`
+/// cannot be parsed, and ends after an `RlgError::IoError` if the file
+/// cannot be opened or read.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use rlg::log_format::LogFormat;
+/// use rlg::utils::async_log_file_reader;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("example.log");
+///     tokio::fs::write(
+///         &path,
+///         "SessionID=1 Timestamp=2024-01-01T00:00:00Z Description=started Level=INFO Component=app Format=CLF\nnot a log entry\n",
+///     )
+///     .await?;
+///     let entries: Vec<_> =
+///         async_log_file_reader(&path, LogFormat::CLF).collect().await;
+///     assert_eq!(entries[0].as_ref().unwrap().description, "started");
+///     assert!(entries[1].is_err());
+///     Ok(())
+/// }
+/// ```
+pub fn async_log_file_reader(
+    path: &Path,
+    format: LogFormat,
+) -> impl Stream<Item = RlgResult<Log>> + Send {
+    let state = ReaderState::Opening(path.to_path_buf(), format);
+    stream::unfold(state, |mut state| async move {
+        loop {
+            state = match state {
+                ReaderState::Opening(path, format) => {
+                    match LogFileReader::open(&path, format).await {
+                        Ok(reader) => ReaderState::Reading(reader),
+                        Err(e) => {
+                            return Some((Err(e), ReaderState::Done))
+                        }
+                    }
+                }
+                ReaderState::Reading(mut reader) => {
+                    match reader.next_entry().await {
+                        Ok(Some(entry)) => {
+                            return Some((
+                                entry,
+                                ReaderState::Reading(reader),
+                            ))
+                        }
+                        Ok(None) => return None,
+                        Err(e) => {
+                            return Some((Err(e), ReaderState::Done))
+                        }
+                    }
+                }
+                ReaderState::Done => return None,
+            };
+        }
+    })
+}
+
+/// The progress of `async_log_file_reader` through the file it reads.
+enum ReaderState {
+    Opening(PathBuf, LogFormat),
+    Reading(LogFileReader),
+    Done,
+}
+
+/// Reads the lines of a log file and parses them as entries.
+struct LogFileReader {
+    lines: tokio::io::Lines<BufReader<File>>,
+    parse: fn(&str) -> RlgResult<Log>,
+    line_number: u64,
+}
+
+impl LogFileReader {
+    /// Opens the log file at `path`, written in `format`.
+    async fn open(path: &Path, format: LogFormat) -> RlgResult<Self> {
+        let parse = line_parser(format)?;
+        let lines = BufReader::new(File::open(path).await?).lines();
+        Ok(LogFileReader {
+            lines,
+            parse,
+            line_number: 0,
+        })
+    }
+
+    /// Parses the next non-empty line, or returns `None` at the end of the
+    /// file. The outer error reports a failed read, the inner one a line
+    /// that could not be parsed.
+    async fn next_entry(
+        &mut self,
+    ) -> RlgResult<Option<RlgResult<Log>>> {
+        while let Some(line) = self.lines.next_line().await? {
+            self.line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Ok(Some((self.parse)(&line).map_err(|e| {
+                RlgError::FormatParseError(format!(
+                    "Line {}: {}",
+                    self.line_number, e
+                ))
+            })));
+        }
+        Ok(None)
+    }
+}
+
+/// Returns the parser for the lines of a log file written in `format`.
+fn line_parser(
+    format: LogFormat,
+) -> RlgResult<fn(&str) -> RlgResult<Log>> {
+    match format {
+        LogFormat::CLF => Ok(Log::from_clf_str),
+        LogFormat::JSON => Ok(Log::from_json_str),
+        other => Err(RlgError::UnsupportedFormat(format!(
+            "Cannot parse {} log files",
+            other
+        ))),
+    }
+}
+
+/// Follows a log file, yielding each line appended to it.
+///
+/// Like `tail -f`, only lines written after the call are yielded: the file
+/// is watched for modifications with `notify`, and new bytes are read from
+/// the last known offset. A line is yielded once its terminating newline
+/// has been written. If the file is truncated, for example by rotation,
+/// reading restarts from its beginning.
+///
+/// The stream never ends on its own; drop it to stop tailing.
+///
+/// # Errors
+///
+/// The stream yields an `RlgError::IoError` if the file cannot be
+/// watched or read. A file that cannot be watched ends the stream.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use rlg::utils::async_tail_follow;
+/// use tokio::io::AsyncWriteExt;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("app.log");
+///     tokio::fs::write(&path, "old entry\n").await?;
+///
+///     let mut lines = async_tail_follow(&path);
+///     let mut file = tokio::fs::OpenOptions::new().append(true).open(&path).await?;
+///     file.write_all(b"new entry\n").await?;
+///
+///     assert_eq!(lines.next().await.unwrap()?, "new entry");
+///     Ok(())
+/// }
+/// ```
+pub fn async_tail_follow(
+    path: &Path,
+) -> Pin<Box<dyn Stream<Item = RlgResult<String>> + Send>> {
+    let (tx, rx) = mpsc::channel(100);
+    let watcher = notify::recommended_watcher(move |res| {
+        // Changes are read up to the end of the file, so an event dropped
+        // while the channel is full is covered by a later one or a poll.
+        let _ = tx.try_send(res);
+    })
+    .and_then(|mut watcher| {
+        watcher.watch(path, RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    });
+    let watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            return Box::pin(stream::once(async move {
+                Err(watch_error(e))
+            }))
+        }
+    };
+
+    let state = TailState {
+        path: path.to_path_buf(),
+        offset: std::fs::metadata(path).map_or(0, |m| m.len()),
+        partial: Vec::new(),
+        lines: VecDeque::new(),
+        events: rx,
+        _watcher: watcher,
+    };
+    Box::pin(stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(line) = state.lines.pop_front() {
+                return Some((Ok(line), state));
+            }
+            match tokio::time::timeout(
+                TAIL_POLL_INTERVAL,
+                state.events.recv(),
+            )
+            .await
+            {
+                Ok(Some(Err(e))) => {
+                    return Some((Err(watch_error(e)), state))
+                }
+                Ok(None) => return None,
+                Ok(Some(Ok(_))) | Err(_) => {}
+            }
+            if let Err(e) = state.read_new_lines().await {
+                return Some((Err(e), state));
+            }
+        }
+    }))
+}
+
+/// The progress of `async_tail_follow` through the followed file.
+struct TailState {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+    lines: VecDeque<String>,
+    events: mpsc::Receiver<notify::Result<notify::Event>>,
+    // Kept alive for as long as the file is followed.
+    _watcher: RecommendedWatcher,
+}
+
+impl TailState {
+    /// Reads the bytes appended since the last read, queueing each
+    /// complete line.
+    async fn read_new_lines(&mut self) -> RlgResult<()> {
+        let mut file = File::open(&self.path).await?;
+        let length = file.metadata().await?.len();
+        if length < self.offset {
+            // The file was truncated or replaced; start over.
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if length == self.offset {
+            return Ok(());
+        }
+
+        let _ =
+            file.seek(std::io::SeekFrom::Start(self.offset)).await?;
+        let read = file.read_to_end(&mut self.partial).await?;
+        self.offset += read as u64;
+
+        while let Some(end) =
+            self.partial.iter().position(|&byte| byte == b'\n')
+        {
+            let mut line: Vec<u8> =
+                self.partial.drain(..=end).collect();
+            let _ = line.pop();
+            if line.last() == Some(&b'\r') {
+                let _ = line.pop();
+            }
+            self.lines
+                .push_back(String::from_utf8_lossy(&line).into_owned());
+        }
+        Ok(())
+    }
+}
+
+/// Watches a configuration file, yielding its configuration each time it
+/// changes.
+///
+/// The current configuration is yielded first. The directory holding the
+/// file is then watched with `notify`, so that files replaced by editors
+/// or deleted and created again keep being followed, and the file is
+/// reloaded with `Config::load_async` whenever it is modified, created or
+/// removed. Changes arriving in quick succession are reloaded once.
+///
+/// Unlike `Config::hot_reload_async`, nothing runs in the background: the
+/// stream never ends on its own, and dropping it stops the watch.
+///
+/// # Errors
+///
+/// Errors are yielded without ending the stream: a
+/// `ConfigError::ConfigParseError` or another `ConfigError` if the file
+/// cannot be parsed or is invalid, and a `ConfigError::FileReadError` if
+/// it was deleted. A directory that cannot be watched yields a
+/// `ConfigError::WatcherError` and ends the stream.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use rlg::utils::watch_config_file;
+///
+/// #[tokio::main]
+/// async fn main() -> rlg::error::RlgResult<()> {
+///     let dir = tempfile::tempdir()?;
+///     let path = dir.path().join("rlg.toml");
+///     tokio::fs::write(&path, "version = \"1.0\"\nlog_level = \"WARN\"").await?;
+///
+///     let mut configs = Box::pin(watch_config_file(&path));
+///     let config = configs.next().await.unwrap()?;
+///     assert_eq!(config.log_level, rlg::LogLevel::WARN);
+///     Ok(())
+/// }
+/// ```
+pub fn watch_config_file(
+    path: &Path,
+) -> impl Stream<Item = RlgResult<Config>> + Send {
+    let path = path.to_path_buf();
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => {
+            parent.to_path_buf()
+        }
+        _ => PathBuf::from("."),
+    };
+    let (tx, rx) = mpsc::channel(100);
+    let watcher = notify::recommended_watcher(move |res| {
+        // The whole file is reloaded, so an event dropped while the
+        // channel is full is covered by the ones queued before it.
+        let _ = tx.try_send(res);
+    })
+    .and_then(|mut watcher| {
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    });
+    let watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            let error = RlgError::ConfigError(ConfigError::from(e));
+            return stream::once(async move { Err(error) })
+                .left_stream();
+        }
+    };
+
+    let state = ConfigWatchState {
+        path,
+        loaded: false,
+        events: rx,
+        _watcher: watcher,
+    };
+    stream::unfold(state, |mut state| async move {
+        if state.loaded {
+            loop {
+                match state.events.recv().await {
+                    Some(Ok(event)) if state.is_change(&event) => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        let error = ConfigError::from(e);
+                        return Some((Err(error.into()), state));
+                    }
+                    None => return None,
+                }
+            }
+            // Files are often written in several steps; wait for the
+            // last one.
+            while let Ok(Some(_)) = tokio::time::timeout(
+                CONFIG_WATCH_SETTLE_TIME,
+                state.events.recv(),
+            )
+            .await
+            {}
+        }
+        state.loaded = true;
+        let config = Config::load_async(Some(&state.path), None)
+            .await
+            .map(|config| config.read().clone())
+            .map_err(RlgError::from);
+        Some((config, state))
+    })
+    .right_stream()
+}
+
+/// The configuration file followed by `watch_config_file`.
+struct ConfigWatchState {
+    path: PathBuf,
+    loaded: bool,
+    events: mpsc::Receiver<notify::Result<notify::Event>>,
+    // Kept alive for as long as the file is watched.
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatchState {
+    /// Returns whether an event of the watched directory changes the
+    /// configuration file.
+    ///
+    /// Metadata changes are ignored, as reading the file may update its
+    /// access time.
+    fn is_change(&self, event: &notify::Event) -> bool {
+        let kind = matches!(
+            event.kind,
+            EventKind::Create(_)
+                | EventKind::Modify(_)
+                | EventKind::Remove(_)
+        ) && !matches!(
+            event.kind,
+            EventKind::Modify(ModifyKind::Metadata(_))
+        );
+        kind && event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == self.path.file_name())
+    }
+}
+
+/// Converts a file watching error into an `RlgError`.
+fn watch_error(error: notify::Error) -> RlgError {
+    RlgError::IoError(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        format!("Failed to watch log file: {}", error),
+    ))
+}
+
+/// Runs blocking file I/O on the blocking thread pool.
+pub(crate) async fn run_blocking<F, T>(task: F) -> RlgResult<T>
+where
+    F: FnOnce() -> RlgResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(task).await.map_err(|e| {
+        RlgError::custom(format!("File task failed: {}", e))
+    })?
+}
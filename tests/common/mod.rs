@@ -0,0 +1,25 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Fixtures shared by the integration tests of RustLogs (RLG).
+
+use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
+
+/// Returns an entry of session `session-1`, logged at
+/// `2024-01-01T00:00:00Z`.
+pub(crate) fn sample_log(
+    level: LogLevel,
+    component: &str,
+    description: &str,
+    format: LogFormat,
+) -> Log {
+    Log::new(
+        "session-1",
+        "2024-01-01T00:00:00Z",
+        &level,
+        component,
+        description,
+        &format,
+    )
+}
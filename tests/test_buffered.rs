@@ -0,0 +1,298 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the buffered logger of RustLogs (RLG).
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use once_cell::sync::Lazy;
+    use parking_lot::Mutex;
+    use rlg::{
+        config::{Config, LogRotation},
+        error::RlgError,
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+    };
+    use std::{fs, path::Path, time::Duration};
+    use tempfile::tempdir;
+
+    /// Flush interval used by the tests, in milliseconds.
+    const INTERVAL_MS: u64 = 50;
+
+    fn config(path: &Path, interval_ms: u64) -> Config {
+        Config {
+            log_file_path: path.to_path_buf(),
+            async_flush_interval_ms: interval_ms,
+            ..Default::default()
+        }
+    }
+
+    fn entry(description: &str) -> Log {
+        common::sample_log(
+            LogLevel::INFO,
+            "buffered",
+            description,
+            LogFormat::CLF,
+        )
+    }
+
+    fn line_count(path: &Path) -> usize {
+        fs::read_to_string(path).unwrap_or_default().lines().count()
+    }
+
+    /// Tests that buffered entries reach the file within two flush
+    /// intervals, but not before the first flush.
+    #[tokio::test(start_paused = true)]
+    async fn test_buffered_logger_flushes_on_interval() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let logger = config(&path, INTERVAL_MS)
+            .create_buffered_logger()
+            .unwrap();
+
+        for i in 0..5 {
+            logger.write(&entry(&format!("entry {}", i))).unwrap();
+        }
+        assert_eq!(line_count(&path), 0);
+
+        tokio::time::sleep(Duration::from_millis(2 * INTERVAL_MS))
+            .await;
+        assert_eq!(line_count(&path), 5);
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.lines().next().unwrap().contains("entry 0"));
+    }
+
+    /// Tests that `flush_now` writes the buffered entries immediately.
+    #[tokio::test]
+    async fn test_buffered_logger_flush_now() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let logger =
+            config(&path, 60_000).create_buffered_logger().unwrap();
+
+        logger.write(&entry("first")).unwrap();
+        logger.write(&entry("second")).unwrap();
+        assert_eq!(line_count(&path), 0);
+        logger.flush_now().unwrap();
+        assert_eq!(line_count(&path), 2);
+        logger.flush_now().unwrap();
+        assert_eq!(line_count(&path), 2);
+    }
+
+    /// Tests that dropping the logger flushes the remaining entries.
+    #[tokio::test]
+    async fn test_buffered_logger_flushes_on_drop() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let logger =
+            config(&path, 60_000).create_buffered_logger().unwrap();
+
+        logger.write(&entry("pending")).unwrap();
+        assert_eq!(line_count(&path), 0);
+        drop(logger);
+        assert_eq!(line_count(&path), 1);
+    }
+
+    /// Tests that a full queue rejects entries without blocking, until a
+    /// flush drains it.
+    #[tokio::test]
+    async fn test_buffered_logger_queue_full() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let config = config(&path, 60_000);
+        assert_eq!(config.max_queue_depth, 10_000);
+        let logger = config.create_buffered_logger().unwrap();
+
+        let log = entry("queued");
+        for _ in 0..10_000 {
+            logger.write(&log).unwrap();
+        }
+        match logger.write(&log) {
+            Err(RlgError::Custom(message)) => {
+                assert_eq!(message, "log queue full")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            logger.try_write(&log),
+            Some(RlgError::Custom(_))
+        ));
+
+        logger.flush_now().unwrap();
+        assert_eq!(line_count(&path), 10_000);
+        assert!(logger.try_write(&log).is_none());
+    }
+
+    /// Tests that entries a flush fails to write still count against the
+    /// queue depth, and are written once, in order, by the next flush.
+    #[tokio::test]
+    async fn test_buffered_logger_queue_full_after_failed_flush() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let logger = Config {
+            max_queue_depth: 2,
+            ..config(&path, 60_000)
+        }
+        .create_buffered_logger()
+        .unwrap();
+        // A directory cannot be opened for appending.
+        fs::remove_file(&path).unwrap();
+        fs::create_dir(&path).unwrap();
+
+        for i in 0..4 {
+            logger.write(&entry(&format!("entry {}", i))).unwrap();
+            if i % 2 == 1 {
+                assert!(logger.flush_now().is_err());
+            }
+        }
+        assert!(matches!(
+            logger.try_write(&entry("rejected")),
+            Some(RlgError::Custom(_))
+        ));
+
+        fs::remove_dir(&path).unwrap();
+        logger.flush_now().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let descriptions: Vec<String> = content
+            .lines()
+            .map(|line| Log::from_clf_str(line).unwrap().description)
+            .collect();
+        assert_eq!(
+            descriptions,
+            ["entry 0", "entry 1", "entry 2", "entry 3"]
+        );
+    }
+
+    /// Tests that a zero interval writes every entry immediately, even
+    /// outside a Tokio runtime.
+    #[test]
+    fn test_buffered_logger_zero_interval() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let logger = config(&path, 0).create_buffered_logger().unwrap();
+
+        logger.write(&entry("immediate")).unwrap();
+        assert_eq!(line_count(&path), 1);
+    }
+
+    /// Tests that entries are filtered, redacted and truncated as by
+    /// `log_with_config`, and that the log file is rotated when due.
+    #[test]
+    fn test_buffered_logger_prepares_entries() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let mut config = Config {
+            log_level: LogLevel::WARN,
+            redact_patterns: vec![r"\d{4}-\d{4}".to_string()],
+            max_message_size: 24,
+            log_rotation: Some(LogRotation::Count(2)),
+            ..config(&path, 0)
+        };
+        let _ = config
+            .component_log_levels
+            .insert("buffered".to_string(), LogLevel::INFO);
+        let logger = config.create_buffered_logger().unwrap();
+
+        logger.write(&entry("card 1234-5678")).unwrap();
+        logger
+            .write(&entry("a description longer than the limit"))
+            .unwrap();
+        logger
+            .write(&Log {
+                component: "other".to_string(),
+                ..entry("filtered")
+            })
+            .unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Description=card [REDACTED] "));
+        assert!(!content.contains("1234-5678"));
+        let truncated =
+            Log::from_clf_str(content.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(truncated.description.len(), 24);
+        assert!(!content.contains("filtered"));
+
+        logger.write(&entry("rotated")).unwrap();
+        assert_eq!(line_count(&path), 1);
+        assert_eq!(line_count(&dir.path().join("buffered.log.1")), 2);
+    }
+
+    /// Messages logged through the `log` crate, such as flush failures.
+    static MESSAGES: Lazy<Mutex<Vec<String>>> =
+        Lazy::new(|| Mutex::new(Vec::new()));
+
+    /// Records the messages logged through the `log` crate.
+    struct CaptureLogger;
+
+    impl ::log::Log for CaptureLogger {
+        fn enabled(&self, _: &::log::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &::log::Record<'_>) {
+            MESSAGES.lock().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Tests that a failed background flush is reported and that its
+    /// entries are written by the next successful flush.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_buffered_logger_reports_flush_errors() {
+        ::log::set_logger(&CaptureLogger).unwrap();
+        ::log::set_max_level(::log::LevelFilter::Warn);
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let logger = config(&path, INTERVAL_MS)
+            .create_buffered_logger()
+            .unwrap();
+        // A directory cannot be opened for appending.
+        fs::remove_file(&path).unwrap();
+        fs::create_dir(&path).unwrap();
+
+        logger.write(&entry("retried")).unwrap();
+        tokio::time::sleep(Duration::from_millis(3 * INTERVAL_MS))
+            .await;
+        assert!(MESSAGES.lock().iter().any(|message| message
+            .starts_with("Failed to flush buffered log entries to")));
+
+        fs::remove_dir(&path).unwrap();
+        logger.flush_now().unwrap();
+        assert_eq!(line_count(&path), 1);
+    }
+
+    /// Tests that a flush interval requires a Tokio runtime.
+    #[test]
+    fn test_buffered_logger_requires_runtime() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        assert!(matches!(
+            config(&path, INTERVAL_MS).create_buffered_logger(),
+            Err(RlgError::Custom(_))
+        ));
+    }
+
+    /// Tests that entries in formats that cannot be written to a file
+    /// are rejected.
+    #[test]
+    fn test_buffered_logger_unsupported_format() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("buffered.log");
+        let logger = config(&path, 0).create_buffered_logger().unwrap();
+
+        let log = Log {
+            format: LogFormat::GELF,
+            ..entry("gelf")
+        };
+        assert!(matches!(
+            logger.write(&log),
+            Err(RlgError::UnsupportedFormat(_))
+        ));
+        assert_eq!(line_count(&path), 0);
+    }
+}
@@ -10,15 +10,24 @@
 
 #[cfg(test)]
 mod tests {
+    use chrono::{TimeZone, Utc};
     use rlg::{
         config::{
-            Config, ConfigError, LogRotation, LoggingDestination,
+            Config, ConfigError, ConfigSource, FieldMergeStrategy,
+            LogRotation, LoggingDestination, MergePriority,
+            RotationNamingConvention,
         },
+        log_format::LogFormat,
         log_level::LogLevel,
+        RlgError,
     };
     use serde::Deserialize;
     use std::{
-        collections::HashMap, env, num::NonZeroU64, path::PathBuf,
+        collections::{BTreeMap, HashMap},
+        env,
+        net::SocketAddr,
+        num::NonZeroU64,
+        path::PathBuf,
         str::FromStr,
     };
     use tempfile::tempdir;
@@ -53,6 +62,27 @@ fn test_config_log_file_path_display() {
             log_format: "%level - %message".to_string(),
             logging_destinations: vec![],
             env_vars: HashMap::new(),
+            component_log_levels: HashMap::new(),
+            compress_rotated_files: false,
+            redact_patterns: Vec::new(),
+            per_destination_format: HashMap::new(),
+            profiles: HashMap::new(),
+            max_message_size: 65536,
+            fallback_destination: None,
+            anonymize_session_ids: false,
+            log_rotation_max_files: 10,
+            include_process_context: false,
+            include_hostname: false,
+            async_flush_interval_ms: 0,
+            colorize_stdout: false,
+            max_queue_depth: 10000,
+            structured_fields_separator: ' ',
+            log_level_color_map: HashMap::new(),
+            log_archive_dir: None,
+            max_log_age_days: None,
+            output_format_aliases: HashMap::new(),
+            rotation_naming_convention:
+                RotationNamingConvention::Numeric,
         };
 
         assert_eq!(
@@ -100,7 +130,7 @@ async fn test_config_load_with_invalid_values() {
         env::set_var("LOG_ROTATION", "INVALID_LOG_ROTATION");
 
         let config_result =
-            Config::load_async(Some(&config_file_path)).await;
+            Config::load_async(Some(&config_file_path), None).await;
         assert!(config_result.is_ok(), "Config load should not fail");
 
         let log_level_env =
@@ -125,16 +155,170 @@ async fn test_config_load_with_invalid_values() {
             .expect("Failed to remove log file");
     }
 
-    /// Tests the cloning and copying capabilities of the LogRotation enum.
+    /// Tests the cloning capabilities of the LogRotation enum.
     #[test]
-    fn test_log_rotation_clone_and_copy() {
+    fn test_log_rotation_clone() {
         let size = NonZeroU64::new(1024 * 1024)
             .expect("Failed to create NonZeroU64 instance");
         let rotation1 = LogRotation::Size(size);
-        let rotation2 = rotation1;
+        let rotation2 = rotation1.clone();
         assert_eq!(rotation1, rotation2);
     }
 
+    /// Tests that every log rotation parses back from its `Display`
+    /// output.
+    #[test]
+    fn test_log_rotation_display_round_trip() {
+        for rotation in [
+            LogRotation::Size(NonZeroU64::new(1024).unwrap()),
+            LogRotation::Time(NonZeroU64::new(3600).unwrap()),
+            LogRotation::Date,
+            LogRotation::Count(10),
+            LogRotation::Cron("0 0 * * *".to_string()),
+        ] {
+            assert_eq!(
+                LogRotation::from_str(&rotation.to_string()).unwrap(),
+                rotation
+            );
+        }
+        assert!(LogRotation::from_str("Size: 0 bytes").is_err());
+    }
+
+    /// Tests parsing and displaying cron-based log rotation.
+    #[test]
+    fn test_log_rotation_cron_from_str_and_display() {
+        let rotation = LogRotation::from_str("cron:0 0 * * *").unwrap();
+        assert_eq!(
+            rotation,
+            LogRotation::Cron("0 0 * * *".to_string())
+        );
+        assert_eq!(rotation.to_string(), "cron:0 0 * * *");
+        assert_eq!(
+            LogRotation::from_str(&rotation.to_string()).unwrap(),
+            rotation
+        );
+
+        assert!(LogRotation::from_str("cron:").is_err());
+        assert!(LogRotation::from_str("cron").is_err());
+        assert!(LogRotation::from_str("cron:not a schedule").is_err());
+    }
+
+    /// Tests that a midnight cron schedule triggers at the next midnight.
+    #[test]
+    fn test_log_rotation_cron_next_trigger_midnight() {
+        let rotation = LogRotation::from_str("cron:0 0 * * *").unwrap();
+
+        let after =
+            Utc.with_ymd_and_hms(2024, 2, 28, 13, 45, 10).unwrap();
+        assert_eq!(
+            rotation.next_trigger(after),
+            Some(Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap())
+        );
+
+        // A trigger time is never returned for the instant itself.
+        let midnight =
+            Utc.with_ymd_and_hms(2024, 12, 31, 0, 0, 0).unwrap();
+        assert_eq!(
+            rotation.next_trigger(midnight),
+            Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    /// Tests that only cron policies have a next trigger.
+    #[test]
+    fn test_log_rotation_next_trigger_without_schedule() {
+        let now = Utc::now();
+        assert_eq!(LogRotation::Date.next_trigger(now), None);
+        assert_eq!(LogRotation::Count(3).next_trigger(now), None);
+        assert_eq!(
+            LogRotation::Cron("61 * * * *".to_string())
+                .next_trigger(now),
+            None
+        );
+    }
+
+    /// Tests the rotation status of size and count-based policies.
+    #[test]
+    fn test_log_rotation_display_next_trigger_fill() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("app.log");
+        std::fs::write(&path, "entry\n".repeat(50)).unwrap();
+
+        let size = LogRotation::from_str("size:1200").unwrap();
+        assert_eq!(
+            size.display_next_trigger(&path).unwrap(),
+            "300 / 1200 bytes (25% full)"
+        );
+        let count = LogRotation::Count(200);
+        assert_eq!(
+            count.display_next_trigger(&path).unwrap(),
+            "50 / 200 entries (25% full)"
+        );
+
+        let missing = temp_dir.path().join("missing.log");
+        assert_eq!(
+            size.display_next_trigger(&missing).unwrap(),
+            "0 / 1200 bytes (0% full)"
+        );
+        assert_eq!(
+            count.display_next_trigger(&missing).unwrap(),
+            "0 / 200 entries (0% full)"
+        );
+    }
+
+    /// Tests the rotation status of time and schedule-based policies.
+    #[test]
+    fn test_log_rotation_display_next_trigger_schedule() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("app.log");
+        std::fs::write(&path, "entry\n").unwrap();
+
+        let status = LogRotation::from_str("time:3600")
+            .unwrap()
+            .display_next_trigger(&path)
+            .unwrap();
+        let remaining: u64 = status
+            .strip_prefix("rotates in ")
+            .and_then(|rest| rest.strip_suffix(" seconds"))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((3590..=3600).contains(&remaining), "{}", status);
+
+        assert_eq!(
+            LogRotation::Date.display_next_trigger(&path).unwrap(),
+            "rotates at next midnight"
+        );
+        let cron = LogRotation::from_str("cron:0 0 * * *")
+            .unwrap()
+            .display_next_trigger(&path)
+            .unwrap();
+        assert!(cron.starts_with("rotates at "), "{}", cron);
+        assert!(cron.contains("T00:00:00"), "{}", cron);
+    }
+
+    /// Tests that validation rejects unparsable cron expressions.
+    #[test]
+    fn test_config_validate_cron_rotation() {
+        let mut config = Config {
+            log_rotation: Some(LogRotation::Cron(
+                "30 6 * * Mon".to_string(),
+            )),
+            logging_destinations: vec![LoggingDestination::Stdout],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.log_rotation =
+            Some(LogRotation::Cron("every midnight".to_string()));
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("Invalid cron expression"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
     /// Tests the ConfigError enum variants.
     #[test]
     fn test_config_error() {
@@ -151,6 +335,7 @@ fn test_config_error() {
 
     /// Tests the LoggingDestination enum variants.
     #[test]
+    #[allow(deprecated)]
     fn test_logging_destination() {
         let file_dest =
             LoggingDestination::File(PathBuf::from("test.log"));
@@ -235,6 +420,25 @@ fn test_config_validate() {
         );
     }
 
+    /// Tests that validating a configuration keeps the entries of its log
+    /// file.
+    #[test]
+    fn test_config_validate_keeps_log_file() {
+        let temp_dir = tempdir().unwrap();
+        let log_file_path = temp_dir.path().join("RLG.log");
+        std::fs::write(&log_file_path, "existing entry\n").unwrap();
+
+        let config = Config {
+            log_file_path: log_file_path.clone(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            std::fs::read_to_string(&log_file_path).unwrap(),
+            "existing entry\n"
+        );
+    }
+
     /// Tests the Config::expand_env_vars method.
     #[test]
     fn test_config_expand_env_vars() {
@@ -319,106 +523,2344 @@ fn test_config_merge() {
         assert_eq!(merged_config.log_format, "%level - %message");
     }
 
-    /// Tests the ConfigError enum variants thoroughly.
+    /// Returns two configurations that differ in every field.
+    fn merge_priority_samples() -> (Config, Config) {
+        let base = Config {
+            version: "1.0".to_string(),
+            profile: "base".to_string(),
+            log_file_path: PathBuf::from("base.log"),
+            log_level: LogLevel::WARN,
+            log_rotation: Some(LogRotation::Date),
+            log_format: "%base".to_string(),
+            logging_destinations: vec![LoggingDestination::Stdout],
+            env_vars: HashMap::from([
+                ("A".to_string(), "base".to_string()),
+                ("B".to_string(), "base".to_string()),
+            ]),
+            component_log_levels: HashMap::from([(
+                "db".to_string(),
+                LogLevel::DEBUG,
+            )]),
+            compress_rotated_files: true,
+            redact_patterns: vec!["base".to_string()],
+            per_destination_format: HashMap::from([(
+                "stdout".to_string(),
+                LogFormat::JSON,
+            )]),
+            max_message_size: 1024,
+            fallback_destination: Some(LoggingDestination::Stderr),
+            anonymize_session_ids: true,
+            log_rotation_max_files: 3,
+            include_process_context: true,
+            include_hostname: true,
+            async_flush_interval_ms: 100,
+            colorize_stdout: true,
+            ..Default::default()
+        };
+        let overlay = Config {
+            version: "1.1".to_string(),
+            profile: "overlay".to_string(),
+            log_file_path: PathBuf::from("overlay.log"),
+            log_level: LogLevel::ERROR,
+            log_rotation: None,
+            log_format: "%overlay".to_string(),
+            logging_destinations: vec![
+                LoggingDestination::Stdout,
+                LoggingDestination::Stderr,
+            ],
+            env_vars: HashMap::from([
+                ("B".to_string(), "overlay".to_string()),
+                ("C".to_string(), "overlay".to_string()),
+            ]),
+            component_log_levels: HashMap::from([(
+                "db".to_string(),
+                LogLevel::ERROR,
+            )]),
+            redact_patterns: vec!["overlay".to_string()],
+            per_destination_format: HashMap::from([(
+                "stdout".to_string(),
+                LogFormat::CLF,
+            )]),
+            max_message_size: 2048,
+            log_rotation_max_files: 5,
+            async_flush_interval_ms: 200,
+            ..Default::default()
+        };
+        (base, overlay)
+    }
+
+    /// Tests that `MergePriority::Overlay` behaves like `merge`.
     #[test]
-    fn test_config_error_enum() {
-        #[allow(dead_code)]
-        #[derive(Deserialize, Debug)]
-        struct EnvConfig {
-            required_field: String,
-        }
+    fn test_config_merge_priority_overlay() {
+        let (base, overlay) = merge_priority_samples();
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Overlay,
+            &HashMap::new(),
+        );
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::to_value(base.merge(&overlay)).unwrap()
+        );
+        assert_eq!(merged.version, "1.1");
+        assert_eq!(merged.profile, "overlay");
+        assert_eq!(merged.log_file_path, PathBuf::from("overlay.log"));
+        assert_eq!(merged.log_level, LogLevel::ERROR);
+        assert_eq!(merged.log_rotation, None);
+        assert_eq!(merged.log_format, "%overlay");
+        assert_eq!(
+            merged.logging_destinations,
+            overlay.logging_destinations
+        );
+        assert_eq!(merged.env_vars["A"], "base");
+        assert_eq!(merged.env_vars["B"], "overlay");
+        assert_eq!(merged.env_vars["C"], "overlay");
+        assert_eq!(merged.component_log_levels["db"], LogLevel::ERROR);
+        assert!(!merged.compress_rotated_files);
+        assert_eq!(merged.redact_patterns, vec!["overlay"]);
+        assert_eq!(
+            merged.per_destination_format["stdout"],
+            LogFormat::CLF
+        );
+        assert_eq!(merged.max_message_size, 2048);
+        assert_eq!(merged.fallback_destination, None);
+        assert!(!merged.anonymize_session_ids);
+        assert_eq!(merged.log_rotation_max_files, 5);
+        assert!(!merged.include_process_context);
+        assert!(!merged.include_hostname);
+        assert_eq!(merged.async_flush_interval_ms, 200);
+        assert!(!merged.colorize_stdout);
+    }
 
-        let env_var_error = ConfigError::EnvVarParseError(
-            envy::from_env::<EnvConfig>().unwrap_err(),
+    /// Tests that `MergePriority::Base` keeps non-default base values and
+    /// takes the others from the overlay.
+    #[test]
+    fn test_config_merge_priority_base() {
+        let (base, overlay) = merge_priority_samples();
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Base,
+            &HashMap::new(),
+        );
+        // The base version is the default, so the overlay's is used.
+        assert_eq!(merged.version, "1.1");
+        assert_eq!(merged.profile, "base");
+        assert_eq!(merged.log_file_path, PathBuf::from("base.log"));
+        assert_eq!(merged.log_level, LogLevel::WARN);
+        assert_eq!(merged.log_rotation, Some(LogRotation::Date));
+        assert_eq!(merged.log_format, "%base");
+        assert_eq!(
+            merged.logging_destinations,
+            vec![LoggingDestination::Stdout]
+        );
+        assert_eq!(merged.env_vars["A"], "base");
+        assert_eq!(merged.env_vars["B"], "base");
+        assert_eq!(merged.env_vars["C"], "overlay");
+        assert_eq!(merged.component_log_levels["db"], LogLevel::DEBUG);
+        assert!(merged.compress_rotated_files);
+        assert_eq!(merged.redact_patterns, vec!["base"]);
+        assert_eq!(
+            merged.per_destination_format["stdout"],
+            LogFormat::JSON
+        );
+        assert_eq!(merged.max_message_size, 1024);
+        assert_eq!(
+            merged.fallback_destination,
+            Some(LoggingDestination::Stderr)
         );
+        assert!(merged.anonymize_session_ids);
+        assert_eq!(merged.log_rotation_max_files, 3);
+        assert!(merged.include_process_context);
+        assert!(merged.include_hostname);
+        assert_eq!(merged.async_flush_interval_ms, 100);
+        assert!(merged.colorize_stdout);
 
-        let config_parse_error = ConfigError::ConfigParseError(
-            config::ConfigError::Message("test error".to_string()),
+        // Default base values never override the overlay.
+        let merged = Config::merge_priority(
+            &Config::default(),
+            &overlay,
+            MergePriority::Base,
+            &HashMap::new(),
+        );
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::to_value(&overlay).unwrap()
         );
+    }
 
-        let invalid_file_path_error =
-            ConfigError::InvalidFilePath("invalid path".to_string());
+    /// Tests that `MergePriority::Newer` lets the higher version win.
+    #[test]
+    fn test_config_merge_priority_newer() {
+        let (mut base, mut overlay) = merge_priority_samples();
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Newer,
+            &HashMap::new(),
+        );
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::to_value(base.merge(&overlay)).unwrap()
+        );
 
-        let env_var_error_message = format!("{}", env_var_error);
-        println!("Env var error message: {}", env_var_error_message);
+        // Versions are compared numerically, so 1.10 is newer than 1.9.
+        base.version = "1.10".to_string();
+        overlay.version = "1.9".to_string();
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Newer,
+            &HashMap::new(),
+        );
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::to_value(overlay.merge(&base)).unwrap()
+        );
+        assert_eq!(merged.version, "1.10");
+        assert_eq!(merged.profile, "base");
+        assert_eq!(merged.log_level, LogLevel::WARN);
+        assert_eq!(merged.env_vars["B"], "base");
+        assert_eq!(merged.env_vars["C"], "overlay");
+        assert_eq!(merged.max_message_size, 1024);
 
-        assert!(
-            env_var_error_message.contains("field") || env_var_error_message.contains("parse"),
-            "Env var error should contain 'field' or 'parse' but was: {}",
-            env_var_error_message
+        // On a tie, the overlay wins.
+        overlay.version = "1.10".to_string();
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Newer,
+            &HashMap::new(),
+        );
+        assert_eq!(merged.profile, "overlay");
+    }
+
+    /// Tests the field merge strategies for rotation, destinations and
+    /// environment variables.
+    #[test]
+    fn test_config_merge_priority_field_strategies() {
+        let (base, overlay) = merge_priority_samples();
+        let append = HashMap::from([
+            ("log_rotation".to_string(), FieldMergeStrategy::Append),
+            (
+                "logging_destinations".to_string(),
+                FieldMergeStrategy::Append,
+            ),
+            ("env_vars".to_string(), FieldMergeStrategy::Append),
+        ]);
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Overlay,
+            &append,
         );
+        assert_eq!(merged.log_rotation, Some(LogRotation::Date));
         assert_eq!(
-            format!("{}", config_parse_error),
-            "Configuration parsing error: test error"
+            merged.logging_destinations,
+            vec![
+                LoggingDestination::Stdout,
+                LoggingDestination::Stderr
+            ]
+        );
+        assert_eq!(merged.env_vars.len(), 3);
+        assert_eq!(merged.env_vars["B"], "overlay");
+
+        let replace = HashMap::from([
+            ("log_rotation".to_string(), FieldMergeStrategy::Replace),
+            (
+                "logging_destinations".to_string(),
+                FieldMergeStrategy::Replace,
+            ),
+            ("env_vars".to_string(), FieldMergeStrategy::Replace),
+        ]);
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Overlay,
+            &replace,
         );
+        assert_eq!(merged.log_rotation, None);
         assert_eq!(
-            format!("{}", invalid_file_path_error),
-            "Invalid file path: invalid path"
+            merged.logging_destinations,
+            overlay.logging_destinations
+        );
+        assert_eq!(merged.env_vars, overlay.env_vars);
+
+        let merged = Config::merge_priority(
+            &base,
+            &overlay,
+            MergePriority::Base,
+            &replace,
         );
+        assert_eq!(merged.log_rotation, Some(LogRotation::Date));
+        assert_eq!(merged.env_vars, base.env_vars);
     }
 
-    // Additional tests for Config methods
+    /// Tests that a transaction with an invalid change fails to commit
+    /// and rolls back to the original configuration.
+    #[test]
+    fn test_config_transaction_rollback() {
+        let config = Config::default();
+        let mut transaction = config.begin_transaction();
+        transaction
+            .set("log_level", "DEBUG")
+            .unwrap()
+            .set("max_message_size", 1024)
+            .unwrap()
+            .set("profile", "")
+            .unwrap();
 
-    /// Tests the Config::get method.
+        match transaction.commit() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("Profile"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let original = transaction.rollback();
+        assert!(Config::diff(&config, &original).is_empty());
+        assert_eq!(original.profile, "default");
+        assert_eq!(original.log_level, LogLevel::INFO);
+    }
+
+    /// Tests that a committed transaction returns every change, and that
+    /// a failed `set` leaves the draft unchanged.
     #[test]
-    fn test_config_get() {
-        let config = Config {
-            version: "1.0".to_string(),
-            profile: "test".to_string(),
-            log_file_path: PathBuf::from("test.log"),
-            log_level: LogLevel::INFO,
-            log_rotation: Some(LogRotation::Size(
-                NonZeroU64::new(1024).unwrap(),
-            )),
-            log_format: "%level - %message".to_string(),
-            logging_destinations: vec![LoggingDestination::File(
-                PathBuf::from("test.log"),
-            )],
-            env_vars: HashMap::new(),
-        };
+    fn test_config_transaction_commit() {
+        let config = Config::default();
+        let mut transaction = config.begin_transaction();
+        transaction
+            .set("log_level", "DEBUG")
+            .unwrap()
+            .set("profile", "batch")
+            .unwrap();
+        assert!(transaction.set("unknown_key", 1).is_err());
+        assert!(transaction.set("max_message_size", "big").is_err());
+
+        let committed = transaction.commit().unwrap();
+        let differences = Config::diff(&config, &committed);
+        assert_eq!(differences.len(), 2);
+        assert_eq!(committed.log_level, LogLevel::DEBUG);
+        assert_eq!(committed.profile, "batch");
+        assert_eq!(committed.max_message_size, config.max_message_size);
+        assert_eq!(config.log_level, LogLevel::INFO);
+    }
+
+    /// Tests the default, validation and manipulation of
+    /// `log_rotation_max_files`.
+    #[test]
+    fn test_config_log_rotation_max_files() {
+        let mut config = Config::default();
+        assert_eq!(config.log_rotation_max_files, 10);
+        let parsed: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.log_rotation_max_files, 10);
 
+        assert!(config.set("log_rotation_max_files", 3).is_ok());
         assert_eq!(
-            config.get::<String>("version"),
-            Some("1.0".to_string())
+            config.get::<u32>("log_rotation_max_files"),
+            Some(3)
         );
+        assert!(config.set("log_rotation_max_files", -1).is_err());
         assert_eq!(
-            config.get::<String>("profile"),
-            Some("test".to_string())
+            Config::diff(&Config::default(), &config)
+                .get("log_rotation_max_files")
+                .unwrap(),
+            "10 -> 3"
         );
         assert_eq!(
-            config.get::<LogLevel>("log_level"),
-            Some(LogLevel::INFO)
+            Config::default().merge(&config).log_rotation_max_files,
+            3
         );
-        assert_eq!(config.get::<String>("non_existent"), None);
+
+        config.log_rotation_max_files = 0;
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("Log rotation max files"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
     }
 
-    /// Tests the Config::set method.
+    /// Tests the default and manipulation of `include_process_context`.
     #[test]
-    fn test_config_set() {
+    fn test_config_include_process_context() {
         let mut config = Config::default();
+        assert!(!config.include_process_context);
 
-        assert!(config.set("version", "2.0").is_ok());
-        assert_eq!(config.version, "2.0");
+        assert!(config.set("include_process_context", true).is_ok());
+        assert_eq!(
+            config.get::<bool>("include_process_context"),
+            Some(true)
+        );
+        assert!(config.set("include_process_context", 1).is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("include_process_context")
+                .unwrap(),
+            "false -> true"
+        );
+        assert!(
+            Config::default().merge(&config).include_process_context
+        );
+    }
 
-        assert!(config.set("log_level", LogLevel::DEBUG).is_ok());
-        assert_eq!(config.log_level, LogLevel::DEBUG);
+    /// Tests the default and manipulation of `include_hostname`.
+    #[test]
+    fn test_config_include_hostname() {
+        let mut config = Config::default();
+        assert!(!config.include_hostname);
 
-        assert!(config.set("non_existent", "value").is_err());
+        assert!(config.set("include_hostname", true).is_ok());
+        assert_eq!(config.get::<bool>("include_hostname"), Some(true));
+        assert!(config.set("include_hostname", "yes").is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("include_hostname")
+                .unwrap(),
+            "false -> true"
+        );
+        assert!(Config::default().merge(&config).include_hostname);
     }
 
-    /// Tests the Config::save_to_file method.
+    /// Tests the default and manipulation of `colorize_stdout`.
     #[test]
-    fn test_config_save_to_file() {
-        let temp_dir =
-            tempdir().expect("Failed to create temp directory");
-        let config_path = temp_dir.path().join("test_config.json");
+    fn test_config_colorize_stdout() {
+        let mut config = Config::default();
+        assert!(!config.colorize_stdout);
 
-        let config = Config::default();
-        assert!(config.save_to_file(&config_path).is_ok());
+        assert!(config.set("colorize_stdout", true).is_ok());
+        assert_eq!(config.get::<bool>("colorize_stdout"), Some(true));
+        assert!(config.set("colorize_stdout", "yes").is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("colorize_stdout")
+                .unwrap(),
+            "false -> true"
+        );
+        assert!(Config::default().merge(&config).colorize_stdout);
+    }
 
-        assert!(
-            config_path.exists(),
-            "Config file should have been created"
+    /// Tests the default and manipulation of `async_flush_interval_ms`.
+    #[test]
+    fn test_config_async_flush_interval_ms() {
+        let mut config = Config::default();
+        assert_eq!(config.async_flush_interval_ms, 0);
+
+        assert!(config.set("async_flush_interval_ms", 250).is_ok());
+        assert_eq!(
+            config.get::<u64>("async_flush_interval_ms"),
+            Some(250)
+        );
+        assert!(config.set("async_flush_interval_ms", -1).is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("async_flush_interval_ms")
+                .unwrap(),
+            "0 -> 250"
+        );
+        assert_eq!(
+            Config::default().merge(&config).async_flush_interval_ms,
+            250
+        );
+    }
+
+    /// Tests the default and manipulation of `anonymize_session_ids`.
+    #[test]
+    fn test_config_anonymize_session_ids() {
+        let mut config = Config::default();
+        assert!(!config.anonymize_session_ids);
+
+        assert!(config.set("anonymize_session_ids", true).is_ok());
+        assert_eq!(
+            config.get::<bool>("anonymize_session_ids"),
+            Some(true)
+        );
+        assert!(config.set("anonymize_session_ids", "yes").is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("anonymize_session_ids")
+                .unwrap(),
+            "false -> true"
+        );
+        assert!(Config::default().merge(&config).anonymize_session_ids);
+    }
+
+    /// Tests the default, validation and manipulation of
+    /// `max_message_size`.
+    #[test]
+    fn test_config_max_message_size() {
+        let mut config = Config::default();
+        assert_eq!(config.max_message_size, 65536);
+        assert_eq!(
+            config.get::<usize>("max_message_size"),
+            Some(65536)
+        );
+
+        assert!(config.set("max_message_size", 1024).is_ok());
+        assert_eq!(config.max_message_size, 1024);
+        assert!(config.set("max_message_size", "big").is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("max_message_size")
+                .unwrap(),
+            "65536 -> 1024"
+        );
+        assert_eq!(
+            Config::default().merge(&config).max_message_size,
+            1024
+        );
+
+        config.max_message_size = 0;
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("Max message size"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let parsed: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.max_message_size, 65536);
+    }
+
+    /// Tests the default, validation and manipulation of
+    /// `max_queue_depth`.
+    #[test]
+    fn test_config_max_queue_depth() {
+        let mut config = Config::default();
+        assert_eq!(config.max_queue_depth, 10000);
+        assert_eq!(config.get::<usize>("max_queue_depth"), Some(10000));
+
+        assert!(config.set("max_queue_depth", 16).is_ok());
+        assert_eq!(config.max_queue_depth, 16);
+        assert!(config.set("max_queue_depth", "deep").is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("max_queue_depth")
+                .unwrap(),
+            "10000 -> 16"
+        );
+        assert_eq!(
+            Config::default().merge(&config).max_queue_depth,
+            16
         );
+
+        config.max_queue_depth = 0;
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("Max queue depth"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let parsed: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.max_queue_depth, 10000);
+    }
+
+    /// Tests the default, validation and manipulation of
+    /// `structured_fields_separator`.
+    #[test]
+    fn test_config_structured_fields_separator() {
+        let mut config = Config::default();
+        assert_eq!(config.structured_fields_separator, ' ');
+
+        assert!(config
+            .set("structured_fields_separator", '\t')
+            .is_ok());
+        assert_eq!(
+            config.get::<char>("structured_fields_separator"),
+            Some('\t')
+        );
+        assert!(config
+            .set("structured_fields_separator", "ab")
+            .is_err());
+        assert_eq!(
+            Config::diff(&Config::default(), &config)
+                .get("structured_fields_separator")
+                .unwrap(),
+            "' ' -> '\\t'"
+        );
+        assert!(config.validate().is_ok());
+
+        for separator in ['=', '\n'] {
+            config.structured_fields_separator = separator;
+            match config.validate() {
+                Err(ConfigError::ValidationError(message)) => {
+                    assert!(
+                        message.contains("separator"),
+                        "{}",
+                        message
+                    )
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+
+        let parsed: Config = serde_json::from_str(
+            r#"{"structured_fields_separator": ";"}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.structured_fields_separator, ';');
+    }
+
+    /// Tests the defaults, validation and merging of
+    /// `log_level_color_map`.
+    #[test]
+    fn test_config_log_level_color_map() {
+        let mut config = Config::default();
+        assert!(config.log_level_color_map.is_empty());
+        let defaults = Config::default_color_map();
+        assert_eq!(defaults[&LogLevel::ERROR], "\x1b[31m");
+        assert_eq!(defaults[&LogLevel::WARN], "\x1b[33m");
+
+        let colors =
+            HashMap::from([(LogLevel::INFO, "\x1b[32m".to_string())]);
+        assert!(config.set("log_level_color_map", &colors).is_ok());
+        assert_eq!(
+            config.get::<HashMap<LogLevel, String>>(
+                "log_level_color_map"
+            ),
+            Some(colors.clone())
+        );
+        assert!(config.set("log_level_color_map", "green").is_err());
+        assert!(Config::diff(&Config::default(), &config)
+            .contains_key("log_level_color_map"));
+        assert!(config.validate().is_ok());
+
+        let merged = Config::default().merge(&config);
+        assert_eq!(merged.log_level_color_map, colors);
+
+        config
+            .log_level_color_map
+            .insert(LogLevel::ERROR, "red".to_string());
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("ERROR"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let parsed: Config = serde_json::from_str(
+            r#"{"log_level_color_map": {"INFO": "\u001b[32m"}}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.log_level_color_map, colors);
+    }
+
+    /// Tests validating and manipulating `log_archive_dir` and
+    /// `max_log_age_days`.
+    #[test]
+    fn test_config_log_archive() {
+        let mut config = Config::default();
+        assert_eq!(config.log_archive_dir, None);
+        assert_eq!(config.max_log_age_days, None);
+
+        let archive = PathBuf::from("archive");
+        assert!(config.set("log_archive_dir", &archive).is_ok());
+        assert_eq!(
+            config.get::<Option<PathBuf>>("log_archive_dir"),
+            Some(Some(archive.clone()))
+        );
+        assert!(config.set("max_log_age_days", "soon").is_err());
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("together"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        assert!(config.set("max_log_age_days", 30).is_ok());
+        assert_eq!(config.get::<u64>("max_log_age_days"), Some(30));
+        assert!(config.validate().is_ok());
+        let diff = Config::diff(&Config::default(), &config);
+        assert_eq!(diff["max_log_age_days"], "None -> Some(30)");
+        assert!(diff.contains_key("log_archive_dir"));
+        assert_eq!(
+            Config::default().merge(&config).max_log_age_days,
+            Some(30)
+        );
+
+        config.max_log_age_days = Some(0);
+        assert!(config.validate().is_err());
+    }
+
+    /// Tests resolving and validating `output_format_aliases`.
+    #[test]
+    fn test_config_output_format_aliases() {
+        let mut config = Config {
+            log_format: "j".to_string(),
+            ..Default::default()
+        };
+        assert!(config.output_format_aliases.is_empty());
+        assert_eq!(config.resolve_format().unwrap(), LogFormat::JSON);
+        config.log_format = "syslog".to_string();
+        assert_eq!(
+            config.resolve_format().unwrap(),
+            LogFormat::RFC5424
+        );
+
+        // Aliases of formats that cannot be written are rejected, even
+        // when unused.
+        for alias in Config::default_format_aliases().keys() {
+            let default = Config {
+                log_format: alias.clone(),
+                ..Default::default()
+            };
+            assert!(default.resolve_format().is_ok(), "{}", alias);
+        }
+        let _ = config
+            .output_format_aliases
+            .insert("Apache".to_string(), LogFormat::ApacheAccessLog);
+        config.log_format = "apache".to_string();
+        assert!(matches!(
+            config.resolve_format(),
+            Err(RlgError::UnsupportedFormat(_))
+        ));
+        assert!(config.validate().is_err());
+        config.log_format = "syslog".to_string();
+        let _ = config.output_format_aliases.remove("Apache");
+        let _ = config
+            .output_format_aliases
+            .insert("x".to_string(), LogFormat::GELF);
+        assert!(config.validate().is_err());
+        let _ = config.output_format_aliases.remove("x");
+        assert!(config.validate().is_ok());
+
+        // Configured aliases take precedence over the defaults.
+        assert!(config
+            .set(
+                "output_format_aliases",
+                HashMap::from([("j", LogFormat::CEF)])
+            )
+            .is_ok());
+        config.log_format = "j".to_string();
+        assert_eq!(config.resolve_format().unwrap(), LogFormat::CEF);
+        assert!(config.validate().is_ok());
+        let diff = Config::diff(&Config::default(), &config);
+        assert!(diff.contains_key("output_format_aliases"));
+        assert_eq!(
+            Config::default().merge(&config).output_format_aliases["j"],
+            LogFormat::CEF
+        );
+
+        config.log_format = "unknown".to_string();
+        assert!(config.resolve_format().is_err());
+        assert!(config.validate().is_err());
+
+        config.log_format = "json".to_string();
+        let _ = config
+            .output_format_aliases
+            .insert("clf".to_string(), LogFormat::JSON);
+        assert!(config.validate().is_err());
+        let _ = config.output_format_aliases.remove("clf");
+        let _ = config
+            .output_format_aliases
+            .insert(" ".to_string(), LogFormat::JSON);
+        assert!(config.validate().is_err());
+    }
+
+    /// Tests round-tripping a configuration through environment
+    /// variables.
+    #[test]
+    fn test_config_env_vars_round_trip() {
+        let mut config = Config {
+            profile: "prod".to_string(),
+            log_file_path: PathBuf::from("/var/log/app.log"),
+            log_level: LogLevel::WARN,
+            log_rotation: Some(LogRotation::Count(500)),
+            log_format: "%level - %message".to_string(),
+            logging_destinations: vec![
+                LoggingDestination::Stdout,
+                LoggingDestination::Http {
+                    url: "https://example.com/logs".to_string(),
+                    headers: BTreeMap::from([(
+                        "Authorization".to_string(),
+                        "Bearer token".to_string(),
+                    )]),
+                },
+                LoggingDestination::Tcp(
+                    "127.0.0.1:5000".parse().unwrap(),
+                ),
+            ],
+            env_vars: HashMap::from([(
+                "REGION".to_string(),
+                "eu-west-1".to_string(),
+            )]),
+            component_log_levels: HashMap::from([(
+                "db".to_string(),
+                LogLevel::DEBUG,
+            )]),
+            compress_rotated_files: true,
+            redact_patterns: vec![r"\d{16}".to_string()],
+            per_destination_format: HashMap::from([(
+                "stdout".to_string(),
+                LogFormat::JSON,
+            )]),
+            max_message_size: 1024,
+            fallback_destination: Some(LoggingDestination::Stderr),
+            include_hostname: true,
+            async_flush_interval_ms: 250,
+            structured_fields_separator: ',',
+            log_level_color_map: HashMap::from([(
+                LogLevel::WARN,
+                "\x1b[35m".to_string(),
+            )]),
+            log_archive_dir: Some(PathBuf::from("archive")),
+            max_log_age_days: Some(30),
+            ..Default::default()
+        };
+        let _ = config
+            .profiles
+            .insert("debug".to_string(), Config::default());
+
+        let vars = config.to_env_vars();
+        assert!(vars.contains(&(
+            "RLG_LOG_FILE_PATH".to_string(),
+            "/var/log/app.log".to_string()
+        )));
+        assert!(vars.contains(&(
+            "RLG_LOG_ROTATION".to_string(),
+            "Count: 500 logs".to_string()
+        )));
+        assert!(vars.iter().all(|(name, _)| name.starts_with("RLG_")));
+
+        let mut pairs: Vec<(&str, &str)> = vars
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        pairs.push(("PATH", "/usr/bin"));
+        let parsed = Config::from_env_pairs(&pairs).unwrap();
+        assert_eq!(parsed.log_rotation, config.log_rotation);
+        assert_eq!(
+            parsed.logging_destinations,
+            config.logging_destinations
+        );
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+
+        // Unset optional fields round-trip as well.
+        config.log_rotation = None;
+        config.log_archive_dir = None;
+        config.max_log_age_days = None;
+        let vars = config.to_env_vars();
+        let pairs: Vec<(&str, &str)> = vars
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let parsed = Config::from_env_pairs(&pairs).unwrap();
+        assert_eq!(parsed.log_rotation, None);
+        assert_eq!(parsed.log_archive_dir, None);
+        assert_eq!(parsed.max_log_age_days, None);
+
+        assert!(Config::from_env_pairs(&[("RLG_LOG_LEVEL", "LOUD")])
+            .is_err());
+        assert!(
+            Config::from_env_pairs(&[("RLG_UNKNOWN", "1")]).is_err()
+        );
+    }
+
+    /// Tests validating and manipulating `fallback_destination`.
+    #[test]
+    fn test_config_fallback_destination() {
+        let mut config = Config::default();
+        assert_eq!(config.fallback_destination, None);
+
+        let fallback =
+            LoggingDestination::File(PathBuf::from("backup.log"));
+        assert!(config.set("fallback_destination", &fallback).is_ok());
+        assert_eq!(
+            config.get::<LoggingDestination>("fallback_destination"),
+            Some(fallback.clone())
+        );
+        assert!(config.validate().is_ok());
+        assert!(Config::diff(&Config::default(), &config)
+            .contains_key("fallback_destination"));
+        assert_eq!(
+            Config::default().merge(&config).fallback_destination,
+            Some(fallback)
+        );
+
+        config.fallback_destination = Some(LoggingDestination::File(
+            config.log_file_path.clone(),
+        ));
+        assert!(config.validate().is_err());
+
+        config.fallback_destination = Some(LoggingDestination::Http {
+            url: "not a url".to_string(),
+            headers: BTreeMap::new(),
+        });
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.starts_with("Fallback destination: "))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Tests building a configuration from `RLG_*` environment variables.
+    #[test]
+    fn test_config_load_from_env() {
+        let vars = [
+            ("RLG_VERSION", "1.0"),
+            ("RLG_PROFILE", "container"),
+            ("RLG_LOG_FILE_PATH", "/var/log/app.log"),
+            ("RLG_LOG_LEVEL", "debug"),
+            ("RLG_LOG_FORMAT", "%time %level %message"),
+            ("RLG_LOG_ROTATION", "count:5"),
+        ];
+        for (name, value) in vars {
+            env::set_var(name, value);
+        }
+
+        let config = Config::load_from_env().unwrap();
+        assert_eq!(config.version, "1.0");
+        assert_eq!(config.profile, "container");
+        assert_eq!(
+            config.log_file_path,
+            PathBuf::from("/var/log/app.log")
+        );
+        assert_eq!(config.log_level, LogLevel::DEBUG);
+        assert_eq!(config.log_format, "%time %level %message");
+        assert_eq!(config.log_rotation, Some(LogRotation::Count(5)));
+
+        env::set_var("RLG_LOG_LEVEL", "BOGUS");
+        match Config::load_from_env() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("BOGUS"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        env::set_var("RLG_LOG_LEVEL", "INFO");
+        env::set_var("RLG_LOG_ROTATION", "weekly");
+        assert!(matches!(
+            Config::load_from_env(),
+            Err(ConfigError::ValidationError(_))
+        ));
+        env::set_var("RLG_LOG_ROTATION", "count:5");
+        env::set_var("RLG_LOG_FORMAT", " ");
+        assert!(matches!(
+            Config::load_from_env(),
+            Err(ConfigError::ValidationError(_))
+        ));
+
+        for (name, _) in vars {
+            env::remove_var(name);
+        }
+        let defaults = Config::default();
+        let config = Config::load_from_env().unwrap();
+        assert_eq!(config.profile, defaults.profile);
+        assert_eq!(config.log_file_path, defaults.log_file_path);
+        assert_eq!(config.log_level, defaults.log_level);
+        assert_eq!(config.log_rotation, defaults.log_rotation);
+    }
+
+    /// Tests building a configuration from command-line arguments.
+    #[test]
+    fn test_config_from_args() {
+        let config = Config::from_args(&[
+            "--log-level",
+            "debug",
+            "--log-file",
+            "/tmp/test.log",
+        ])
+        .unwrap();
+        assert_eq!(config.log_level, LogLevel::DEBUG);
+        assert_eq!(
+            config.log_file_path,
+            PathBuf::from("/tmp/test.log")
+        );
+        assert_eq!(config.log_rotation, Config::default().log_rotation);
+
+        let config = Config::from_args(&[
+            "app",
+            "--verbose",
+            "--log-format=json",
+            "--log-rotation",
+            "count:5",
+            "--profile",
+            "cli",
+            "--log-level",
+            "warn",
+            "--log-level",
+            "error",
+        ])
+        .unwrap();
+        assert_eq!(config.log_format, "json");
+        assert_eq!(config.log_rotation, Some(LogRotation::Count(5)));
+        assert_eq!(config.profile, "cli");
+        assert_eq!(config.log_level, LogLevel::ERROR);
+
+        match Config::from_args(&["--log-level", "invalid"]) {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("invalid"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            Config::from_args(&["--log-file"]),
+            Err(ConfigError::ValidationError(_))
+        ));
+        assert!(matches!(
+            Config::from_args(&["--log-format", "yaml"]),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    /// Tests the ConfigError enum variants thoroughly.
+    #[test]
+    fn test_config_error_enum() {
+        #[allow(dead_code)]
+        #[derive(Deserialize, Debug)]
+        struct EnvConfig {
+            required_field: String,
+        }
+
+        let env_var_error = ConfigError::EnvVarParseError(
+            envy::from_env::<EnvConfig>().unwrap_err(),
+        );
+
+        let config_parse_error = ConfigError::ConfigParseError(
+            config::ConfigError::Message("test error".to_string()),
+        );
+
+        let invalid_file_path_error =
+            ConfigError::InvalidFilePath("invalid path".to_string());
+
+        let env_var_error_message = format!("{}", env_var_error);
+        println!("Env var error message: {}", env_var_error_message);
+
+        assert!(
+            env_var_error_message.contains("field") || env_var_error_message.contains("parse"),
+            "Env var error should contain 'field' or 'parse' but was: {}",
+            env_var_error_message
+        );
+        assert_eq!(
+            format!("{}", config_parse_error),
+            "Configuration parsing error: test error"
+        );
+        assert_eq!(
+            format!("{}", invalid_file_path_error),
+            "Invalid file path: invalid path"
+        );
+    }
+
+    // Additional tests for Config methods
+
+    /// Tests the Config::get method.
+    #[test]
+    fn test_config_get() {
+        let config = Config {
+            version: "1.0".to_string(),
+            profile: "test".to_string(),
+            log_file_path: PathBuf::from("test.log"),
+            log_level: LogLevel::INFO,
+            log_rotation: Some(LogRotation::Size(
+                NonZeroU64::new(1024).unwrap(),
+            )),
+            log_format: "%level - %message".to_string(),
+            logging_destinations: vec![LoggingDestination::File(
+                PathBuf::from("test.log"),
+            )],
+            env_vars: HashMap::new(),
+            component_log_levels: HashMap::new(),
+            compress_rotated_files: false,
+            redact_patterns: Vec::new(),
+            per_destination_format: HashMap::new(),
+            profiles: HashMap::new(),
+            max_message_size: 65536,
+            fallback_destination: None,
+            anonymize_session_ids: false,
+            log_rotation_max_files: 10,
+            include_process_context: false,
+            include_hostname: false,
+            async_flush_interval_ms: 0,
+            colorize_stdout: false,
+            max_queue_depth: 10000,
+            structured_fields_separator: ' ',
+            log_level_color_map: HashMap::new(),
+            log_archive_dir: None,
+            max_log_age_days: None,
+            output_format_aliases: HashMap::new(),
+            rotation_naming_convention:
+                RotationNamingConvention::Numeric,
+        };
+
+        assert_eq!(
+            config.get::<String>("version"),
+            Some("1.0".to_string())
+        );
+        assert_eq!(
+            config.get::<String>("profile"),
+            Some("test".to_string())
+        );
+        assert_eq!(
+            config.get::<LogLevel>("log_level"),
+            Some(LogLevel::INFO)
+        );
+        assert_eq!(config.get::<String>("non_existent"), None);
+    }
+
+    /// Tests the Config::set method.
+    #[test]
+    fn test_config_set() {
+        let mut config = Config::default();
+
+        assert!(config.set("version", "2.0").is_ok());
+        assert_eq!(config.version, "2.0");
+
+        assert!(config.set("log_level", LogLevel::DEBUG).is_ok());
+        assert_eq!(config.log_level, LogLevel::DEBUG);
+
+        assert!(config.set("non_existent", "value").is_err());
+    }
+
+    /// Tests the Config::save_to_file method.
+    #[test]
+    fn test_config_save_to_file() {
+        let temp_dir =
+            tempdir().expect("Failed to create temp directory");
+        let config_path = temp_dir.path().join("test_config.json");
+
+        let config = Config::default();
+        assert!(config.save_to_file(&config_path).is_ok());
+
+        assert!(
+            config_path.exists(),
+            "Config file should have been created"
+        );
+    }
+
+    /// Tests that every field survives a round trip through YAML.
+    #[tokio::test]
+    async fn test_config_yaml_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rlg.yaml");
+
+        let mut headers = BTreeMap::new();
+        headers.insert(
+            "Authorization".to_string(),
+            "Bearer x".to_string(),
+        );
+        let mut config = Config {
+            profile: "prod".to_string(),
+            log_file_path: temp_dir.path().join("app.log"),
+            log_level: LogLevel::WARN,
+            log_rotation: Some(LogRotation::Cron(
+                "0 0 * * *".to_string(),
+            )),
+            log_format: "%time %level %message".to_string(),
+            logging_destinations: vec![
+                LoggingDestination::File(
+                    temp_dir.path().join("app.log"),
+                ),
+                LoggingDestination::Stdout,
+                LoggingDestination::Http {
+                    url: "https://logs.example.com/ingest".to_string(),
+                    headers,
+                },
+                LoggingDestination::UdpSyslog {
+                    addr: "127.0.0.1:514".parse().unwrap(),
+                },
+            ],
+            compress_rotated_files: true,
+            redact_patterns: vec![r"password=\S+".to_string()],
+            max_message_size: 4096,
+            fallback_destination: Some(LoggingDestination::Stderr),
+            ..Default::default()
+        };
+        config
+            .env_vars
+            .insert("RLG_MODE".to_string(), "container".to_string());
+        config
+            .component_log_levels
+            .insert("database".to_string(), LogLevel::DEBUG);
+        config
+            .per_destination_format
+            .insert("stdout".to_string(), LogFormat::JSON);
+        config.profiles.insert(
+            "debug".to_string(),
+            Config {
+                profile: "debug".to_string(),
+                log_level: LogLevel::DEBUG,
+                ..config.clone()
+            },
+        );
+
+        config.save_to_yaml(&config_path).unwrap();
+        let yaml = fs::read_to_string(&config_path).await.unwrap();
+        assert!(yaml.contains("logging_destinations:\n- type: File\n"));
+
+        let loaded = Config::load_from_yaml_async(&config_path)
+            .await
+            .unwrap()
+            .read()
+            .clone();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+        assert_eq!(loaded.profiles["debug"].log_level, LogLevel::DEBUG);
+    }
+
+    /// Tests loading hand-written YAML files, including invalid ones.
+    #[tokio::test]
+    async fn test_config_load_from_yaml_errors() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rlg.yaml");
+
+        fs::write(
+            &config_path,
+            "version: \"1.0\"\nlog_level: ERROR\nprofiles:\n  dev:\n    log_level: DEBUG\n",
+        )
+        .await
+        .unwrap();
+        let config = Config::load_from_yaml_async(&config_path)
+            .await
+            .unwrap()
+            .read()
+            .clone();
+        assert_eq!(config.log_level, LogLevel::ERROR);
+        assert_eq!(config.profiles["dev"].profile, "dev");
+        assert_eq!(config.profiles["dev"].log_level, LogLevel::DEBUG);
+
+        fs::write(&config_path, "log_level: DEBUG\n").await.unwrap();
+        assert!(matches!(
+            Config::load_from_yaml_async(&config_path).await,
+            Err(ConfigError::MissingFieldError(field)) if field == "version"
+        ));
+
+        fs::write(&config_path, "version: \"0.1\"\n").await.unwrap();
+        assert!(matches!(
+            Config::load_from_yaml_async(&config_path).await,
+            Err(ConfigError::VersionError(_))
+        ));
+
+        fs::write(&config_path, "- not\n- a mapping\n")
+            .await
+            .unwrap();
+        assert!(matches!(
+            Config::load_from_yaml_async(&config_path).await,
+            Err(ConfigError::ConfigParseError(_))
+        ));
+
+        fs::write(
+            &config_path,
+            "version: \"1.0\"\nlog_format: \" \"\n",
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            Config::load_from_yaml_async(&config_path).await,
+            Err(ConfigError::ValidationError(_))
+        ));
+
+        let missing = temp_dir.path().join("missing.yaml");
+        assert!(matches!(
+            Config::load_from_yaml_async(&missing).await,
+            Err(ConfigError::FileReadError(_))
+        ));
+        let invalid = Config {
+            log_format: String::new(),
+            ..Default::default()
+        };
+        assert!(invalid.save_to_yaml(&config_path).is_err());
+    }
+
+    /// Tests that `log_format` accepts format names and templates only.
+    #[test]
+    fn test_config_resolve_format() {
+        let mut config = Config::default();
+        assert_eq!(config.resolve_format().unwrap(), LogFormat::CLF);
+        assert!(config.validate().is_ok());
+
+        for (name, format) in [
+            ("json", LogFormat::JSON),
+            (" CSV ", LogFormat::CSV),
+            ("otel", LogFormat::OpenTelemetry),
+            ("%time %level %message", LogFormat::CLF),
+        ] {
+            config.log_format = name.to_string();
+            assert_eq!(config.resolve_format().unwrap(), format);
+            assert!(config.validate().is_ok(), "{}", name);
+        }
+
+        for name in ["gelf", "log4jxml", "ndjson"] {
+            config.log_format = name.to_string();
+            assert!(matches!(
+                config.resolve_format(),
+                Err(RlgError::UnsupportedFormat(_))
+            ));
+            assert!(config.validate().is_err(), "{}", name);
+        }
+
+        config.log_format = "yaml".to_string();
+        assert!(matches!(
+            config.resolve_format(),
+            Err(RlgError::FormatParseError(_))
+        ));
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("yaml"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Tests that component overrides take precedence over the global level.
+    #[test]
+    fn test_config_effective_level_for() {
+        let mut config = Config {
+            log_level: LogLevel::WARN,
+            ..Default::default()
+        };
+        config
+            .component_log_levels
+            .insert("database".to_string(), LogLevel::DEBUG);
+
+        assert_eq!(
+            config.effective_level_for("database"),
+            LogLevel::DEBUG
+        );
+        assert_eq!(config.effective_level_for("http"), LogLevel::WARN);
+    }
+
+    /// Tests that an empty component name in the overrides is rejected.
+    #[test]
+    fn test_config_validate_component_log_levels() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config {
+            log_file_path: temp_dir.path().join("RLG.log"),
+            ..Default::default()
+        };
+        config
+            .component_log_levels
+            .insert("database".to_string(), LogLevel::DEBUG);
+        assert!(config.validate().is_ok());
+
+        config
+            .component_log_levels
+            .insert(" ".to_string(), LogLevel::DEBUG);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    /// Tests getting, setting, diffing and merging component log levels.
+    #[test]
+    fn test_config_component_log_levels_operations() {
+        let mut levels = HashMap::new();
+        levels.insert("http".to_string(), LogLevel::WARN);
+
+        let mut config = Config::default();
+        assert!(config.set("component_log_levels", &levels).is_ok());
+        assert_eq!(
+            config.get::<HashMap<String, LogLevel>>(
+                "component_log_levels"
+            ),
+            Some(levels.clone())
+        );
+
+        let differences = Config::diff(&Config::default(), &config);
+        assert!(differences.contains_key("component_log_levels"));
+
+        let mut other = Config::default();
+        other
+            .component_log_levels
+            .insert("database".to_string(), LogLevel::DEBUG);
+        let merged = config.merge(&other);
+        assert_eq!(merged.component_log_levels.len(), 2);
+        assert_eq!(
+            merged.component_log_levels["database"],
+            LogLevel::DEBUG
+        );
+    }
+
+    /// Tests that component log levels survive `save_to_file`.
+    #[test]
+    fn test_config_save_to_file_component_log_levels() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("test_config.json");
+
+        let mut config = Config::default();
+        config
+            .component_log_levels
+            .insert("database".to_string(), LogLevel::DEBUG);
+        config.save_to_file(&config_path).unwrap();
+
+        let contents = std::fs::read_to_string(&config_path).unwrap();
+        let loaded: Config = serde_json::from_str(&contents).unwrap();
+        assert_eq!(
+            loaded.component_log_levels,
+            config.component_log_levels
+        );
+    }
+
+    /// Tests reading and changing the rotation naming convention.
+    #[test]
+    fn test_config_rotation_naming_convention() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.rotation_naming_convention,
+            RotationNamingConvention::Numeric
+        );
+        assert!(config
+            .set("rotation_naming_convention", "DateStamped")
+            .is_ok());
+        assert_eq!(
+            config.get::<RotationNamingConvention>(
+                "rotation_naming_convention"
+            ),
+            Some(RotationNamingConvention::DateStamped)
+        );
+        assert!(config
+            .set("rotation_naming_convention", "Weekly")
+            .is_err());
+        assert!(Config::diff(&Config::default(), &config)
+            .contains_key("rotation_naming_convention"));
+        assert_eq!(
+            Config::default().merge(&config).rotation_naming_convention,
+            RotationNamingConvention::DateStamped
+        );
+
+        let config = Config::from_toml_str(
+            "version = \"1.0\"\nrotation_naming_convention = \"TimestampMillis\"",
+        )
+        .unwrap();
+        assert_eq!(
+            config.rotation_naming_convention,
+            RotationNamingConvention::TimestampMillis
+        );
+    }
+
+    /// Tests layering configuration sources, with later sources winning.
+    #[tokio::test]
+    async fn test_config_load_layered_async() {
+        let temp_dir = tempdir().unwrap();
+        let warn_path = temp_dir.path().join("warn.toml");
+        let debug_path = temp_dir.path().join("debug.json");
+        fs::write(
+            &warn_path,
+            "version = \"1.0\"\nlog_level = \"WARN\"",
+        )
+        .await
+        .unwrap();
+        fs::write(
+            &debug_path,
+            r#"{"version":"1.0","log_level":"DEBUG"}"#,
+        )
+        .await
+        .unwrap();
+        let error = ConfigSource::Str(
+            "version = \"1.0\"\nlog_level = \"ERROR\"",
+        );
+
+        let config = Config::load_layered_async(&[
+            ConfigSource::File(warn_path.clone()),
+            error.clone(),
+            ConfigSource::File(debug_path.clone()),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(config.read().log_level, LogLevel::DEBUG);
+
+        let config = Config::load_layered_async(&[
+            ConfigSource::Defaults,
+            ConfigSource::File(debug_path.clone()),
+            error.clone(),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(config.read().log_level, LogLevel::ERROR);
+
+        // An invalid source fails even when a later source would hide it.
+        let result = Config::load_layered_async(&[
+            ConfigSource::Str("version = \"1.0\"\nprofile = \"\""),
+            ConfigSource::File(warn_path),
+        ])
+        .await;
+        assert!(matches!(result, Err(ConfigError::ValidationError(_))));
+
+        let result = Config::load_layered_async(&[
+            error,
+            ConfigSource::File(temp_dir.path().join("missing.toml")),
+        ])
+        .await;
+        assert!(matches!(result, Err(ConfigError::FileReadError(_))));
+    }
+
+    /// Tests that a layer setting one field keeps the fields set by the
+    /// layers before it.
+    #[tokio::test]
+    async fn test_config_load_layered_async_partial_layers() {
+        let temp_dir = tempdir().unwrap();
+        let base_path = temp_dir.path().join("base.yaml");
+        let log_file_path = temp_dir.path().join("app.log");
+        fs::write(
+            &base_path,
+            format!(
+                "version: \"1.0\"\nlog_level: WARN\nlog_file_path: {:?}\nmax_message_size: 512\n",
+                log_file_path
+            ),
+        )
+        .await
+        .unwrap();
+
+        let config = Config::load_layered_async(&[
+            ConfigSource::File(base_path.clone()),
+            ConfigSource::Str(
+                "version = \"1.0\"\nlog_format = \"json\"",
+            ),
+        ])
+        .await
+        .unwrap()
+        .read()
+        .clone();
+        assert_eq!(config.log_format, "json");
+        assert_eq!(config.log_level, LogLevel::WARN);
+        assert_eq!(config.log_file_path, log_file_path);
+        assert_eq!(config.max_message_size, 512);
+
+        // Defaults reset every field set before them.
+        let config = Config::load_layered_async(&[
+            ConfigSource::File(base_path),
+            ConfigSource::Defaults,
+        ])
+        .await
+        .unwrap();
+        assert_eq!(
+            config.read().log_level,
+            Config::default().log_level
+        );
+    }
+
+    /// Tests that component log levels are read by `load_async`.
+    #[tokio::test]
+    async fn test_config_load_async_component_log_levels() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let log_file_path = temp_dir.path().join("RLG.log");
+        let config_content = format!(
+            r#"
+version = "1.0"
+profile = "default"
+log_file_path = "{path}"
+log_level = "WARN"
+logging_destinations = [{{ type = "File", value = "{path}" }}]
+
+[component_log_levels]
+database = "DEBUG"
+http = "ERROR"
+"#,
+            path = log_file_path.display()
+        );
+        fs::write(&config_path, config_content).await.unwrap();
+
+        let config =
+            Config::load_async(Some(&config_path), None).await.unwrap();
+        let config = config.read();
+        assert_eq!(
+            config.effective_level_for("database"),
+            LogLevel::DEBUG
+        );
+        assert_eq!(config.effective_level_for("http"), LogLevel::ERROR);
+        assert_eq!(config.effective_level_for("other"), LogLevel::WARN);
+    }
+
+    /// Returns a configuration with non-default values for the format
+    /// detection tests.
+    fn detect_format_sample(temp_dir: &tempfile::TempDir) -> Config {
+        let mut config = Config {
+            log_file_path: temp_dir.path().join("app.log"),
+            log_level: LogLevel::WARN,
+            log_format: "%time %level %message".to_string(),
+            logging_destinations: vec![LoggingDestination::Stdout],
+            max_message_size: 4096,
+            ..Default::default()
+        };
+        config
+            .component_log_levels
+            .insert("database".to_string(), LogLevel::DEBUG);
+        config
+    }
+
+    /// Tests that `load_async` reads `.json` files as JSON.
+    #[tokio::test]
+    async fn test_config_load_async_json() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rlg.json");
+        let config = detect_format_sample(&temp_dir);
+        fs::write(
+            &config_path,
+            serde_json::to_string(&config).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let loaded = Config::load_async(Some(&config_path), None)
+            .await
+            .unwrap()
+            .read()
+            .clone();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+    }
+
+    /// Tests that `load_async` reads `.yaml` and `.yml` files as YAML.
+    #[tokio::test]
+    async fn test_config_load_async_yaml() {
+        let temp_dir = tempdir().unwrap();
+        let config = detect_format_sample(&temp_dir);
+        for name in ["rlg.yaml", "rlg.yml"] {
+            let config_path = temp_dir.path().join(name);
+            config.save_to_yaml(&config_path).unwrap();
+
+            let loaded = Config::load_async(Some(&config_path), None)
+                .await
+                .unwrap()
+                .read()
+                .clone();
+            assert_eq!(
+                serde_json::to_value(&loaded).unwrap(),
+                serde_json::to_value(&config).unwrap(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    /// Tests that unrecognized extensions are parsed as TOML.
+    #[tokio::test]
+    async fn test_config_load_async_unknown_extension() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rlg.conf");
+        fs::write(
+            &config_path,
+            "version = \"1.0\"\nlog_level = \"ERROR\"\n",
+        )
+        .await
+        .unwrap();
+        let loaded =
+            Config::load_async(Some(&config_path), None).await.unwrap();
+        assert_eq!(loaded.read().log_level, LogLevel::ERROR);
+
+        // JSON content is not accepted under an unrecognized extension.
+        fs::write(&config_path, "{\"version\": \"1.0\"}")
+            .await
+            .unwrap();
+        assert!(matches!(
+            Config::load_async(Some(&config_path), None).await,
+            Err(ConfigError::ConfigParseError(_))
+        ));
+
+        assert_eq!(
+            Config::detect_format(&temp_dir.path().join("RLG.JSON")),
+            config::FileFormat::Json
+        );
+        assert_eq!(
+            Config::detect_format(&temp_dir.path().join("rlg")),
+            config::FileFormat::Toml
+        );
+    }
+
+    /// Tests getting, setting, diffing and merging `compress_rotated_files`.
+    #[test]
+    fn test_config_compress_rotated_files_operations() {
+        let mut config = Config::default();
+        assert!(!config.compress_rotated_files);
+        assert!(config.set("compress_rotated_files", true).is_ok());
+        assert_eq!(
+            config.get::<bool>("compress_rotated_files"),
+            Some(true)
+        );
+        assert!(config.set("compress_rotated_files", "yes").is_err());
+
+        let differences = Config::diff(&Config::default(), &config);
+        assert_eq!(
+            differences.get("compress_rotated_files"),
+            Some(&"false -> true".to_string())
+        );
+
+        let merged = Config::default().merge(&config);
+        assert!(merged.compress_rotated_files);
+
+        let loaded: Config = serde_json::from_str(
+            &serde_json::to_string(&config).unwrap(),
+        )
+        .unwrap();
+        assert!(loaded.compress_rotated_files);
+    }
+
+    /// Tests that the `Stderr` destination validates and round-trips.
+    #[test]
+    fn test_config_stderr_destination() {
+        let config = Config {
+            logging_destinations: vec![LoggingDestination::Stderr],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let json =
+            serde_json::to_string(&LoggingDestination::Stderr).unwrap();
+        assert_eq!(json, r#"{"type":"Stderr"}"#);
+        let destination: LoggingDestination =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(destination, LoggingDestination::Stderr);
+    }
+
+    /// Tests validating and manipulating `redact_patterns`.
+    #[test]
+    fn test_config_redact_patterns() {
+        let mut config = Config::default();
+        let patterns = vec![r"\d{16}".to_string()];
+        assert!(config.set("redact_patterns", &patterns).is_ok());
+        assert_eq!(
+            config.get::<Vec<String>>("redact_patterns"),
+            Some(patterns.clone())
+        );
+        assert!(config.validate().is_ok());
+
+        let differences = Config::diff(&Config::default(), &config);
+        assert!(differences.contains_key("redact_patterns"));
+        assert_eq!(
+            Config::default().merge(&config).redact_patterns,
+            patterns
+        );
+
+        config.redact_patterns.push("(unclosed".to_string());
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(message))
+                if message.contains("Invalid redact pattern '(unclosed'")
+        ));
+    }
+
+    /// Writes a configuration with `dev` and `prod` profiles to a file.
+    async fn write_profiles_config(dir: &std::path::Path) -> PathBuf {
+        let log_file_path = dir.join("RLG.log");
+        let config_path = dir.join("config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+version = "1.0"
+log_file_path = "{path}"
+log_level = "INFO"
+logging_destinations = [{{ type = "Stdout" }}]
+redact_patterns = ["secret"]
+
+[profiles.dev]
+log_level = "DEBUG"
+
+[profiles.prod]
+log_level = "ERROR"
+log_format = "%time %level %message"
+"#,
+                path = log_file_path.display()
+            ),
+        )
+        .await
+        .unwrap();
+        config_path
+    }
+
+    /// Tests that profiles are parsed and activated when loading.
+    #[tokio::test]
+    async fn test_config_profiles_load_async() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = write_profiles_config(temp_dir.path()).await;
+
+        let base =
+            Config::load_async(Some(&config_path), None).await.unwrap();
+        let base = base.read().clone();
+        assert_eq!(base.log_level, LogLevel::INFO);
+        assert_eq!(base.profiles.len(), 2);
+
+        let prod = Config::load_async(Some(&config_path), Some("prod"))
+            .await
+            .unwrap();
+        let prod = prod.read().clone();
+        assert_eq!(prod.profile, "prod");
+        assert_eq!(prod.log_level, LogLevel::ERROR);
+        assert_eq!(prod.log_format, "%time %level %message");
+        assert_eq!(prod.log_file_path, base.log_file_path);
+        assert_eq!(prod.redact_patterns, base.redact_patterns);
+        assert_eq!(
+            prod.logging_destinations,
+            vec![LoggingDestination::Stdout]
+        );
+
+        let dev = Config::load_async(Some(&config_path), Some("dev"))
+            .await
+            .unwrap();
+        let dev = dev.read().clone();
+        assert_eq!(dev.profile, "dev");
+        assert_eq!(dev.log_level, LogLevel::DEBUG);
+        assert_eq!(dev.log_format, base.log_format);
+
+        assert!(matches!(
+            Config::load_async(Some(&config_path), Some("staging"))
+                .await,
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    /// Tests `activate_profile` on a configuration built in code.
+    #[test]
+    fn test_config_activate_profile() {
+        let mut config = Config::default();
+        let staging = Config {
+            log_level: LogLevel::WARN,
+            ..config.clone()
+        };
+        config.profiles.insert("staging".to_string(), staging);
+
+        let active = config.activate_profile("staging").unwrap();
+        assert_eq!(active.log_level, LogLevel::WARN);
+        assert_eq!(active.profile, "staging");
+        assert!(active.profiles.contains_key("staging"));
+        assert_eq!(
+            active.activate_profile("staging").unwrap().log_level,
+            LogLevel::WARN
+        );
+
+        assert!(matches!(
+            config.activate_profile("prod"),
+            Err(RlgError::ConfigError(ConfigError::ValidationError(_)))
+        ));
+        assert_eq!(
+            Config::diff(&Config::default(), &config)["profiles"],
+            r#"[] -> ["staging"]"#
+        );
+    }
+
+    /// Tests that invalid profiles fail validation.
+    #[test]
+    fn test_config_validate_profiles() {
+        let mut config = Config {
+            logging_destinations: vec![LoggingDestination::Stdout],
+            ..Default::default()
+        };
+        let broken = Config {
+            redact_patterns: vec!["(unclosed".to_string()],
+            ..config.clone()
+        };
+        config.profiles.insert("broken".to_string(), broken);
+
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.starts_with("Profile 'broken': "));
+                assert!(message.contains("Invalid redact pattern"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Tests validating and serializing `Http` destinations.
+    #[test]
+    fn test_http_logging_destination() {
+        let mut headers = BTreeMap::new();
+        headers.insert("X-Api-Key".to_string(), "secret".to_string());
+        let destination = LoggingDestination::Http {
+            url: "https://logs.example.com/ingest".to_string(),
+            headers,
+        };
+        assert_eq!(
+            destination.to_string(),
+            "http:https://logs.example.com/ingest"
+        );
+
+        let json = serde_json::to_string(&destination).unwrap();
+        assert_eq!(
+            serde_json::from_str::<LoggingDestination>(&json).unwrap(),
+            destination
+        );
+        let without_headers: LoggingDestination = serde_json::from_str(
+            r#"{"type":"Http","value":{"url":"http://localhost/logs"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            without_headers,
+            LoggingDestination::Http {
+                url: "http://localhost/logs".to_string(),
+                headers: BTreeMap::new(),
+            }
+        );
+
+        let mut config = Config {
+            logging_destinations: vec![destination.clone()],
+            ..Default::default()
+        };
+        config
+            .per_destination_format
+            .insert(destination.to_string(), LogFormat::JSON);
+        assert!(config.validate().is_ok());
+
+        for url in ["not a url", "ftp://example.com/logs", ""] {
+            config.logging_destinations =
+                vec![LoggingDestination::Http {
+                    url: url.to_string(),
+                    headers: BTreeMap::new(),
+                }];
+            assert!(
+                matches!(
+                    config.validate(),
+                    Err(ConfigError::InvalidUrl(_))
+                ),
+                "url {:?} should be rejected",
+                url
+            );
+        }
+    }
+
+    /// Tests validating and serializing `UdpSyslog` destinations.
+    #[test]
+    fn test_udp_syslog_logging_destination() {
+        let destination = LoggingDestination::UdpSyslog {
+            addr: "127.0.0.1:514".parse().unwrap(),
+        };
+        assert_eq!(destination.to_string(), "udp-syslog:127.0.0.1:514");
+
+        let json = serde_json::to_string(&destination).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"UdpSyslog","value":{"addr":"127.0.0.1:514"}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<LoggingDestination>(&json).unwrap(),
+            destination
+        );
+
+        let mut config = Config {
+            logging_destinations: vec![destination.clone()],
+            ..Default::default()
+        };
+        config
+            .per_destination_format
+            .insert(destination.to_string(), LogFormat::RFC5424);
+        assert!(config.validate().is_ok());
+
+        config.logging_destinations =
+            vec![LoggingDestination::UdpSyslog {
+                addr: "[::1]:0".parse().unwrap(),
+            }];
+        config.per_destination_format.clear();
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert!(message.contains("port cannot be 0"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Tests the report of a migration from a v1.0 configuration to a
+    /// v2.0 one with deprecated and incompatible settings.
+    #[test]
+    #[allow(deprecated)]
+    fn test_config_diff_and_migrate() {
+        let old = Config {
+            log_rotation: Some(LogRotation::Size(
+                NonZeroU64::new(1024).unwrap(),
+            )),
+            ..Default::default()
+        };
+        let new = Config {
+            version: "2.0".to_string(),
+            profile: "prod".to_string(),
+            log_rotation: Some(LogRotation::Cron(
+                "at midnight".to_string(),
+            )),
+            logging_destinations: vec![LoggingDestination::Network(
+                "127.0.0.1:514".to_string(),
+            )],
+            ..old.clone()
+        };
+
+        let report = Config::diff_and_migrate(&old, &new);
+        assert_eq!(report.changes.len(), 4);
+        assert_eq!(report.changes[0], "log_rotation: Some(Size(1024)) -> Some(Cron(\"at midnight\"))");
+        assert!(report
+            .changes
+            .contains(&"profile: default -> prod".to_string()));
+        assert!(report
+            .changes
+            .contains(&"version: 1.0 -> 2.0".to_string()));
+        assert!(
+            report
+                .changes
+                .iter()
+                .any(|change| change
+                    .starts_with("logging_destinations: "))
+        );
+
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[0]
+            .starts_with("version: 2.0 is not supported"));
+        assert!(report.warnings[1].contains(
+            "network:127.0.0.1:514 uses the deprecated Network destination"
+        ));
+
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with(
+            "log_rotation: cannot switch from Size: 1024 bytes to cron: "
+        ));
+        assert!(report.errors[0].contains("'at midnight'"));
+        assert!(!report.is_ok());
+    }
+
+    /// Tests that a valid migration has no errors, and that other
+    /// validation failures of the new configuration are reported.
+    #[test]
+    fn test_config_diff_and_migrate_valid() {
+        let old = Config::default();
+        let new = Config {
+            log_rotation: Some(LogRotation::Cron(
+                "0 0 * * *".to_string(),
+            )),
+            ..Default::default()
+        };
+        let report = Config::diff_and_migrate(&old, &new);
+        assert_eq!(report.changes.len(), 1);
+        assert!(report.warnings.is_empty());
+        assert!(report.is_ok());
+
+        let unchanged = Config::diff_and_migrate(&old, &old);
+        assert_eq!(unchanged, Default::default());
+
+        let new = Config {
+            log_rotation: None,
+            max_message_size: 0,
+            ..Default::default()
+        };
+        let report = Config::diff_and_migrate(&old, &new);
+        assert_eq!(
+            report.warnings,
+            ["log_rotation: log rotation is disabled"]
+        );
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("Max message size"));
+    }
+
+    /// Tests the display, serialization and validation of `Tcp` and `Udp`
+    /// destinations.
+    #[test]
+    fn test_tcp_and_udp_logging_destinations() {
+        let addr: SocketAddr = "127.0.0.1:5170".parse().unwrap();
+        let tcp = LoggingDestination::Tcp(addr);
+        let udp = LoggingDestination::Udp(addr);
+        assert_eq!(tcp.to_string(), "tcp:127.0.0.1:5170");
+        assert_eq!(udp.to_string(), "udp:127.0.0.1:5170");
+        assert_eq!(
+            serde_json::to_string(&tcp).unwrap(),
+            r#"{"type":"Tcp","value":"127.0.0.1:5170"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<LoggingDestination>(
+                r#"{"type":"Udp","value":"127.0.0.1:5170"}"#
+            )
+            .unwrap(),
+            udp
+        );
+
+        let mut config = Config {
+            logging_destinations: vec![tcp.clone(), udp.clone()],
+            ..Default::default()
+        };
+        config
+            .per_destination_format
+            .insert(tcp.to_string(), LogFormat::JSON);
+        config
+            .per_destination_format
+            .insert(udp.to_string(), LogFormat::CLF);
+        assert!(config.validate().is_ok());
+
+        config.per_destination_format.clear();
+        for destination in [
+            LoggingDestination::Tcp("127.0.0.1:0".parse().unwrap()),
+            LoggingDestination::Udp("[::1]:0".parse().unwrap()),
+        ] {
+            config.logging_destinations = vec![destination];
+            match config.validate() {
+                Err(ConfigError::ValidationError(message)) => {
+                    assert!(message.contains("port cannot be 0"))
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+
+    /// Tests validating and manipulating `per_destination_format`.
+    #[test]
+    #[allow(deprecated)]
+    fn test_config_per_destination_format() {
+        let mut config = Config::default();
+        let mut formats = HashMap::new();
+        formats.insert("stdout".to_string(), LogFormat::CLF);
+        formats.insert(
+            "file:/var/log/app.log".to_string(),
+            LogFormat::JSON,
+        );
+        assert!(config.set("per_destination_format", &formats).is_ok());
+        assert_eq!(
+            config.get::<HashMap<String, LogFormat>>(
+                "per_destination_format"
+            ),
+            Some(formats.clone())
+        );
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.format_for(&LoggingDestination::File(
+                PathBuf::from("/var/log/app.log")
+            )),
+            Some(LogFormat::JSON)
+        );
+        assert_eq!(
+            config.format_for(&LoggingDestination::Network(
+                "127.0.0.1:514".to_string()
+            )),
+            None
+        );
+
+        let differences = Config::diff(&Config::default(), &config);
+        assert!(differences.contains_key("per_destination_format"));
+        assert_eq!(
+            Config::default().merge(&config).per_destination_format,
+            formats
+        );
+
+        for key in ["console", "file:", "network: ", "STDOUT"] {
+            let mut invalid = config.clone();
+            invalid
+                .per_destination_format
+                .insert(key.to_string(), LogFormat::CLF);
+            assert!(
+                matches!(
+                    invalid.validate(),
+                    Err(ConfigError::ValidationError(ref message))
+                        if message.contains("per_destination_format")
+                ),
+                "key {:?} should be rejected",
+                key
+            );
+        }
+    }
+
+    /// Tests that `per_destination_format` survives saving and loading.
+    #[tokio::test]
+    async fn test_config_per_destination_format_save_and_load() {
+        let temp_dir = tempdir().unwrap();
+        let log_file_path = temp_dir.path().join("RLG.log");
+        let mut config = Config {
+            log_file_path: log_file_path.clone(),
+            logging_destinations: vec![LoggingDestination::Stdout],
+            ..Default::default()
+        };
+        config.per_destination_format.insert(
+            "network:127.0.0.1:514".to_string(),
+            LogFormat::GELF,
+        );
+        config.per_destination_format.insert(
+            format!("file:{}", log_file_path.display()),
+            LogFormat::JSON,
+        );
+
+        let json_path = temp_dir.path().join("config.json");
+        config.save_to_file(&json_path).unwrap();
+        let saved = fs::read_to_string(&json_path).await.unwrap();
+        assert_eq!(
+            Config::from_json_str(&saved)
+                .unwrap()
+                .per_destination_format,
+            config.per_destination_format
+        );
+
+        let toml_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &toml_path,
+            format!(
+                r#"
+version = "1.0"
+log_file_path = "{path}"
+logging_destinations = [{{ type = "Stdout" }}]
+
+[per_destination_format]
+"network:127.0.0.1:514" = "GELF"
+"file:{path}" = "JSON"
+"#,
+                path = log_file_path.display()
+            ),
+        )
+        .await
+        .unwrap();
+        let loaded =
+            Config::load_async(Some(&toml_path), None).await.unwrap();
+        assert_eq!(
+            loaded.read().per_destination_format,
+            config.per_destination_format
+        );
+    }
+
+    /// Tests that `from_toml_str` fills in defaults for omitted fields.
+    #[test]
+    fn test_config_from_toml_str_defaults() {
+        let temp_dir = tempdir().unwrap();
+        let log_file_path = temp_dir.path().join("RLG.log");
+        let toml = format!(
+            r#"
+version = "1.0"
+log_file_path = "{path}"
+logging_destinations = [{{ type = "File", value = "{path}" }}]
+"#,
+            path = log_file_path.display()
+        );
+
+        let config = Config::from_toml_str(&toml).unwrap();
+        let defaults = Config::default();
+        assert_eq!(config.version, "1.0");
+        assert_eq!(config.log_file_path, log_file_path);
+        assert_eq!(config.profile, defaults.profile);
+        assert_eq!(config.log_level, defaults.log_level);
+        assert_eq!(config.log_format, defaults.log_format);
+        assert!(config.env_vars.is_empty());
+        assert!(config.redact_patterns.is_empty());
+        assert!(!config.compress_rotated_files);
+    }
+
+    /// Tests that `from_json_str` parses a JSON configuration.
+    #[test]
+    fn test_config_from_json_str() {
+        let temp_dir = tempdir().unwrap();
+        let log_file_path = temp_dir.path().join("RLG.log");
+        let json = serde_json::json!({
+            "version": "1.0",
+            "profile": "production",
+            "log_file_path": log_file_path,
+            "log_level": "ERROR",
+            "logging_destinations": [{ "type": "Stderr" }],
+        })
+        .to_string();
+
+        let config = Config::from_json_str(&json).unwrap();
+        assert_eq!(config.profile, "production");
+        assert_eq!(config.log_level, LogLevel::ERROR);
+        assert_eq!(
+            config.logging_destinations,
+            vec![LoggingDestination::Stderr]
+        );
+    }
+
+    /// Tests that a missing `version` is reported as a missing field.
+    #[test]
+    fn test_config_from_str_missing_version() {
+        let result = Config::from_toml_str(r#"profile = "default""#);
+        assert!(matches!(
+            result,
+            Err(ConfigError::MissingFieldError(field)) if field == "version"
+        ));
+
+        let result = Config::from_json_str(r#"{"profile": "default"}"#);
+        assert!(matches!(
+            result,
+            Err(ConfigError::MissingFieldError(_))
+        ));
+    }
+
+    /// Tests that an unsupported `version` is rejected.
+    #[test]
+    fn test_config_from_str_unsupported_version() {
+        let result = Config::from_toml_str(r#"version = "2.0""#);
+        assert!(matches!(result, Err(ConfigError::VersionError(_))));
+
+        let result = Config::from_json_str(r#"{"version": "0.9"}"#);
+        assert!(matches!(result, Err(ConfigError::VersionError(_))));
+    }
+
+    /// Tests that malformed or invalid configuration strings are rejected.
+    #[test]
+    fn test_config_from_str_invalid() {
+        let result = Config::from_toml_str("version = ");
+        assert!(matches!(
+            result,
+            Err(ConfigError::ConfigParseError(_))
+        ));
+
+        let result = Config::from_json_str("{not json");
+        assert!(matches!(
+            result,
+            Err(ConfigError::ConfigParseError(_))
+        ));
+
+        let result = Config::from_toml_str(
+            r#"
+version = "1.0"
+log_format = "  "
+logging_destinations = [{ type = "Stdout" }]
+"#,
+        );
+        assert!(matches!(result, Err(ConfigError::ValidationError(_))));
+    }
+
+    /// Tests that `validate_all` reports every validation failure.
+    #[test]
+    fn test_config_validate_all_collects_errors() {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("RLG_MODE".to_string(), " ".to_string());
+        let config = Config {
+            version: String::new(),
+            profile: " ".to_string(),
+            log_rotation: Some(LogRotation::Count(0)),
+            log_format: String::new(),
+            logging_destinations: vec![LoggingDestination::Stdout],
+            env_vars,
+            ..Default::default()
+        };
+
+        let errors = config.validate_all();
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        let messages: Vec<String> =
+            errors.iter().map(ToString::to_string).collect();
+        for expected in [
+            "Version cannot be empty",
+            "Profile cannot be empty",
+            "Log rotation count must be greater than 0",
+            "Log format cannot be empty",
+            "Value for environment variable 'RLG_MODE' cannot be empty",
+        ] {
+            assert!(
+                messages
+                    .iter()
+                    .any(|message| message.contains(expected)),
+                "missing {:?} in {:?}",
+                expected,
+                messages
+            );
+        }
+
+        match config.validate() {
+            Err(ConfigError::ValidationError(message)) => {
+                assert_eq!(message, "Version cannot be empty")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Tests that `validate_all` is empty for a valid configuration.
+    #[test]
+    fn test_config_validate_all_valid() {
+        let temp_dir = tempdir().unwrap();
+        let log_file_path =
+            temp_dir.path().join("logs").join("RLG.log");
+        let config = Config {
+            log_file_path: log_file_path.clone(),
+            logging_destinations: vec![LoggingDestination::File(
+                log_file_path,
+            )],
+            ..Default::default()
+        };
+        assert!(config.validate_all().is_empty());
+        assert!(config.validate().is_ok());
     }
 }
@@ -0,0 +1,163 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the task-local log context.
+
+#[cfg(test)]
+mod tests {
+    use rlg::{
+        context::{
+            clear_log_context, get_log_context, set_log_context,
+            with_log_context, LogContext,
+        },
+        log_level::LogLevel,
+        macro_error_log, macro_info_log,
+    };
+    use std::time::Duration;
+
+    /// Tests that each concurrent task sees only its own context.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_tasks_do_not_leak_context() {
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let session_id = format!("session-{}", i);
+                let component = format!("component-{}", i);
+                tokio::spawn(with_log_context(
+                    LogContext::new(&session_id, &component),
+                    async move {
+                        for _ in 0..5 {
+                            tokio::time::sleep(Duration::from_millis(
+                                1,
+                            ))
+                            .await;
+                            let log = macro_info_log!(
+                                "2024-01-01T00:00:00Z",
+                                "message"
+                            );
+                            assert_eq!(log.session_id, session_id);
+                            assert_eq!(log.component, component);
+                        }
+                    },
+                ))
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    }
+
+    /// Tests that changing the context inside a task does not affect another.
+    #[tokio::test]
+    async fn test_set_and_clear_within_task_scope() {
+        let first =
+            with_log_context(LogContext::new("first", "api"), async {
+                set_log_context(LogContext::new("changed", "db"));
+                tokio::task::yield_now().await;
+                let changed = get_log_context();
+                clear_log_context();
+                (changed, get_log_context())
+            });
+        let second =
+            with_log_context(LogContext::new("second", "api"), async {
+                tokio::task::yield_now().await;
+                get_log_context()
+            });
+
+        let ((changed, cleared), second) = tokio::join!(first, second);
+        assert_eq!(changed, Some(LogContext::new("changed", "db")));
+        assert_eq!(cleared, None);
+        assert_eq!(second, Some(LogContext::new("second", "api")));
+        assert_eq!(get_log_context(), None);
+    }
+
+    /// Tests that tasks outside `with_log_context` cannot set a context
+    /// that other tasks on the same thread would see.
+    #[tokio::test]
+    async fn test_unscoped_tasks_do_not_share_context() {
+        let result = tokio::spawn(async {
+            set_log_context(LogContext::new("leaked", "task"));
+            get_log_context()
+        })
+        .await;
+        // Debug builds report the misuse, release builds ignore it.
+        if cfg!(debug_assertions) {
+            assert!(result.unwrap_err().is_panic());
+        } else {
+            assert_eq!(result.unwrap(), None);
+        }
+
+        let other = tokio::spawn(async { get_log_context() });
+        assert_eq!(other.await.unwrap(), None);
+        assert_eq!(get_log_context(), None);
+    }
+
+    /// Tests that a thread context set before entering a runtime is not
+    /// seen by the tasks of the runtime.
+    #[test]
+    fn test_thread_context_hidden_inside_runtime() {
+        set_log_context(LogContext::new("thread-session", "main"));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (blocked_on, spawned) = runtime.block_on(async {
+            let spawned = tokio::spawn(async { get_log_context() });
+            (get_log_context(), spawned.await.unwrap())
+        });
+        assert_eq!(blocked_on, None);
+        assert_eq!(spawned, None);
+
+        assert_eq!(
+            get_log_context(),
+            Some(LogContext::new("thread-session", "main"))
+        );
+        clear_log_context();
+    }
+
+    /// Tests the thread fallback used outside `with_log_context`.
+    #[test]
+    fn test_thread_context_fallback() {
+        assert_eq!(get_log_context(), None);
+
+        set_log_context(LogContext::new("thread-session", "worker"));
+        let log = macro_error_log!("2024-01-01T00:00:00Z", "failed");
+        assert_eq!(log.session_id, "thread-session");
+        assert_eq!(log.component, "worker");
+        assert_eq!(log.level, LogLevel::ERROR);
+
+        let other_thread =
+            std::thread::spawn(get_log_context).join().unwrap();
+        assert_eq!(other_thread, None);
+
+        clear_log_context();
+        assert_eq!(get_log_context(), None);
+    }
+
+    /// Tests that macros fall back to random session IDs without a context.
+    #[test]
+    fn test_macros_without_context() {
+        let log =
+            macro_info_log!("2024-01-01T00:00:00Z", "app", "message");
+        #[cfg(not(feature = "uuid_session_ids"))]
+        assert!(log.session_id.parse::<u32>().is_ok());
+        #[cfg(feature = "uuid_session_ids")]
+        assert!(uuid::Uuid::parse_str(&log.session_id).is_ok());
+        assert_eq!(log.component, "app");
+
+        let log = macro_info_log!("2024-01-01T00:00:00Z", "message");
+        assert_eq!(log.component, "");
+    }
+
+    /// Tests that an explicit component overrides the context component.
+    #[tokio::test]
+    async fn test_explicit_component_overrides_context() {
+        with_log_context(LogContext::new("request-1", "http"), async {
+            let log =
+                macro_info_log!("2024-01-01T00:00:00Z", "db", "query");
+            assert_eq!(log.session_id, "request-1");
+            assert_eq!(log.component, "db");
+        })
+        .await;
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the `LogFilter` type.
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use rlg::{
+        filter::LogFilter, log::Log, log_format::LogFormat,
+        log_level::LogLevel,
+    };
+
+    /// Creates a log entry with the given level, component and description.
+    fn entry(
+        level: LogLevel,
+        component: &str,
+        description: &str,
+    ) -> Log {
+        common::sample_log(
+            level,
+            component,
+            description,
+            LogFormat::CLF,
+        )
+    }
+
+    /// A set of sample entries used by the tests.
+    fn samples() -> Vec<Log> {
+        vec![
+            entry(LogLevel::DEBUG, "db", "query planned"),
+            entry(LogLevel::INFO, "db-pool", "connection opened"),
+            entry(LogLevel::WARN, "http", "slow request"),
+            entry(LogLevel::ERROR, "db-pool", "connection timeout"),
+            entry(LogLevel::CRITICAL, "auth", "key store unavailable"),
+        ]
+    }
+
+    /// Returns the descriptions of the sample entries accepted by `filter`.
+    fn accepted(filter: &LogFilter) -> Vec<String> {
+        samples()
+            .into_iter()
+            .filter(|log| log.matches(filter))
+            .map(|log| log.description)
+            .collect()
+    }
+
+    /// Tests that the level filter is inclusive at both ends.
+    #[test]
+    fn test_level_filter() {
+        let filter = LogFilter::level(LogLevel::INFO, LogLevel::ERROR);
+        assert_eq!(
+            accepted(&filter),
+            vec![
+                "connection opened",
+                "slow request",
+                "connection timeout"
+            ]
+        );
+        assert!(accepted(&LogFilter::level(
+            LogLevel::ERROR,
+            LogLevel::INFO
+        ))
+        .is_empty());
+    }
+
+    /// Tests glob matching of components.
+    #[test]
+    fn test_component_filter() {
+        assert_eq!(
+            accepted(&LogFilter::component("db*")).len(),
+            3,
+            "db* should match db and db-pool"
+        );
+        assert_eq!(
+            accepted(&LogFilter::component("db")),
+            vec!["query planned"]
+        );
+        assert_eq!(
+            accepted(&LogFilter::component("h?tp")),
+            vec!["slow request"]
+        );
+        assert_eq!(accepted(&LogFilter::component("*")).len(), 5);
+        assert!(accepted(&LogFilter::component("db.pool")).is_empty());
+    }
+
+    /// Tests matching on description substrings.
+    #[test]
+    fn test_description_contains_filter() {
+        assert_eq!(
+            accepted(&LogFilter::description_contains("connection")),
+            vec!["connection opened", "connection timeout"]
+        );
+        assert_eq!(
+            accepted(&LogFilter::description_contains("")).len(),
+            5
+        );
+    }
+
+    /// Tests composing filters with `and`, `or` and `not`.
+    #[test]
+    fn test_composed_filters() {
+        let db_problems = LogFilter::component("db*")
+            .and(LogFilter::level(LogLevel::WARN, LogLevel::AUDIT));
+        assert_eq!(accepted(&db_problems), vec!["connection timeout"]);
+
+        let urgent_or_slow =
+            LogFilter::level(LogLevel::CRITICAL, LogLevel::CRITICAL)
+                .or(LogFilter::description_contains("slow"));
+        assert_eq!(
+            accepted(&urgent_or_slow),
+            vec!["slow request", "key store unavailable"]
+        );
+
+        let not_db = LogFilter::component("db*").not();
+        assert_eq!(
+            accepted(&not_db),
+            vec!["slow request", "key store unavailable"]
+        );
+
+        let nested = db_problems.clone().or(not_db.clone()).not();
+        assert_eq!(
+            accepted(&nested),
+            vec!["query planned", "connection opened"]
+        );
+        assert_eq!(accepted(&db_problems), vec!["connection timeout"]);
+    }
+
+    /// Tests that `Log::matches` agrees with `LogFilter::matches`.
+    #[test]
+    fn test_log_matches_wrapper() {
+        let filter = LogFilter::description_contains("timeout");
+        for log in samples() {
+            assert_eq!(log.matches(&filter), filter.matches(&log));
+        }
+    }
+
+    /// Tests that the debug output describes the composed filter.
+    #[test]
+    fn test_filter_debug() {
+        let filter = LogFilter::component("db*")
+            .and(LogFilter::description_contains("timeout").not());
+        assert_eq!(
+            format!("{:?}", filter),
+            r#"LogFilter("(component(\"db*\") and not description_contains(\"timeout\"))")"#
+        );
+    }
+}
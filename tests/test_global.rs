@@ -0,0 +1,92 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the global configuration of RustLogs (RLG).
+
+#[cfg(test)]
+mod tests {
+    use rlg::{
+        config::{Config, LoggingDestination},
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+    };
+    use std::{fs, panic, time::Duration};
+    use tempfile::tempdir;
+    use tokio::{io::AsyncReadExt, net::TcpListener, time::timeout};
+
+    /// Tests setting, updating and logging with the global configuration.
+    ///
+    /// Everything runs in one test, as the global configuration can only
+    /// be set once per process.
+    #[tokio::test]
+    async fn test_config_global() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("global.log");
+        Config::init_global(Config {
+            log_file_path: path.clone(),
+            ..Default::default()
+        });
+        assert_eq!(Config::global().read().log_file_path, path);
+
+        Config::global().write().log_level = LogLevel::ERROR;
+        assert_eq!(Config::global().read().log_level, LogLevel::ERROR);
+
+        // `log()` writes with the global configuration.
+        for (level, description) in
+            [(LogLevel::INFO, "filtered"), (LogLevel::ERROR, "written")]
+        {
+            Log::new(
+                "global",
+                "2024-01-01T00:00:00Z",
+                &level,
+                "app",
+                description,
+                &LogFormat::CLF,
+            )
+            .log()
+            .await
+            .unwrap();
+        }
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Description=written"));
+        assert!(!content.contains("filtered"));
+
+        let result = panic::catch_unwind(|| {
+            Config::init_global(Config::default());
+        });
+        assert!(result.is_err(), "a second init_global should panic");
+        assert_eq!(Config::global().read().log_level, LogLevel::ERROR);
+
+        // Removing a TCP destination closes its connection.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        Config::global().write().logging_destinations =
+            vec![LoggingDestination::Tcp(
+                listener.local_addr().unwrap(),
+            )];
+        let entry = |description: &str| {
+            Log::new(
+                "global",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::ERROR,
+                "app",
+                description,
+                &LogFormat::CLF,
+            )
+        };
+        entry("forwarded").log().await.unwrap();
+        let (mut stream, _) = listener.accept().await.unwrap();
+        Config::global().write().logging_destinations = Vec::new();
+        entry("local").log().await.unwrap();
+        let mut received = String::new();
+        let closed = timeout(
+            Duration::from_secs(5),
+            stream.read_to_string(&mut received),
+        )
+        .await;
+        assert!(closed.is_ok(), "the connection was not closed");
+        assert!(received.contains("Description=forwarded"));
+        assert!(!received.contains("local"));
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for `HttpSink` and the `Http` logging destination.
+
+#[cfg(feature = "reqwest")]
+mod common;
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use crate::common;
+    use rlg::{
+        config::{Config, LoggingDestination},
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+        sink::{HttpSink, LogSink},
+        RlgError,
+    };
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+    use wiremock::{
+        matchers::{body_string, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    /// Creates a log entry with fixed values.
+    fn sample_log(format: LogFormat) -> Log {
+        common::sample_log(
+            LogLevel::ERROR,
+            "api",
+            "upstream failed",
+            format,
+        )
+    }
+
+    /// Returns the headers used by the tests.
+    fn headers() -> BTreeMap<String, String> {
+        let mut headers = BTreeMap::new();
+        headers.insert(
+            "Authorization".to_string(),
+            "Bearer t0k3n".to_string(),
+        );
+        headers.insert("X-Source".to_string(), "rlg".to_string());
+        headers
+    }
+
+    /// Tests that the entry and headers are posted to the endpoint.
+    #[tokio::test]
+    async fn test_http_sink_posts_entry() {
+        let server = MockServer::start().await;
+        let log = sample_log(LogFormat::JSON);
+        Mock::given(method("POST"))
+            .and(path("/logs"))
+            .and(header("Authorization", "Bearer t0k3n"))
+            .and(header("X-Source", "rlg"))
+            .and(body_string(log.to_string()))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let sink =
+            HttpSink::new(&format!("{}/logs", server.uri()), headers());
+        assert_eq!(sink.url(), format!("{}/logs", server.uri()));
+        log.log_to(&sink).await.unwrap();
+        sink.flush().await.unwrap();
+    }
+
+    /// Tests that a 4xx response is reported as a network error.
+    #[tokio::test]
+    async fn test_http_sink_client_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let sink = HttpSink::new(&server.uri(), BTreeMap::new());
+        match sample_log(LogFormat::CLF).log_to(&sink).await {
+            Err(RlgError::NetworkError(message)) => {
+                assert!(message.contains("401"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Tests that an unreachable endpoint is reported as a network error.
+    #[tokio::test]
+    async fn test_http_sink_unreachable() {
+        let server = MockServer::start().await;
+        let url = server.uri();
+        drop(server);
+
+        let sink = HttpSink::new(&url, BTreeMap::new());
+        assert!(matches!(
+            sample_log(LogFormat::CLF).log_to(&sink).await,
+            Err(RlgError::NetworkError(_))
+        ));
+    }
+
+    /// Tests that `log_with_config` posts to `Http` destinations using the
+    /// per-destination format.
+    #[tokio::test]
+    async fn test_log_with_config_http_destination() {
+        let server = MockServer::start().await;
+        // A fixed sequence number keeps the posted body predictable.
+        let mut log = sample_log(LogFormat::CLF);
+        log.sequence = Some(1);
+        Mock::given(method("POST"))
+            .and(header("X-Source", "rlg"))
+            .and(body_string(log.render(LogFormat::JSON).unwrap()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let destination = LoggingDestination::Http {
+            url: server.uri(),
+            headers: headers(),
+        };
+        let mut config = Config {
+            log_file_path: temp_dir.path().join("http.log"),
+            logging_destinations: vec![destination.clone()],
+            ..Default::default()
+        };
+        config
+            .per_destination_format
+            .insert(destination.to_string(), LogFormat::JSON);
+
+        log.log_with_config(&config).await.unwrap();
+    }
+}
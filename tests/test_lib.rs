@@ -11,11 +11,11 @@
 mod tests {
     use rlg::{
         log::Log, log_format::LogFormat, log_level::LogLevel,
-        macro_debug_log, macro_error_log, macro_fatal_log,
-        macro_info_log, macro_log, macro_log_if,
+        macro_critical_log, macro_debug_log, macro_error_log,
+        macro_fatal_log, macro_info_log, macro_log, macro_log_if,
         macro_log_with_metadata, macro_print_log,
-        macro_set_log_format_clf, macro_trace_log, macro_warn_log,
-        VERSION,
+        macro_print_log_pretty, macro_set_log_format_clf,
+        macro_trace_log, macro_verbose_log, macro_warn_log, VERSION,
     };
 
     /// Tests the common log format (CLF) for a log entry.
@@ -515,6 +515,18 @@ fn test_macro_fatal_log() {
         assert_eq!(log.level, LogLevel::FATAL);
     }
 
+    #[test]
+    fn test_macro_critical_log() {
+        let log = macro_critical_log!("2022-01-01", "app", "message");
+        assert_eq!(log.level, LogLevel::CRITICAL);
+    }
+
+    #[test]
+    fn test_macro_verbose_log() {
+        let log = macro_verbose_log!("2022-01-01", "app", "message");
+        assert_eq!(log.level, LogLevel::VERBOSE);
+    }
+
     #[test]
     fn test_macro_print_log() {
         let log = macro_info_log!("2022-01-01", "app", "message");
@@ -522,6 +534,13 @@ fn test_macro_print_log() {
         // Asserting that the macro doesn't panic
     }
 
+    #[test]
+    fn test_macro_print_log_pretty() {
+        let log = macro_info_log!("2022-01-01", "app", "message");
+        macro_print_log_pretty!(log);
+        // Asserting that the macro doesn't panic
+    }
+
     #[test]
     fn test_macro_set_log_format_clf() {
         let mut log = macro_info_log!("2022-01-01", "app", "message");
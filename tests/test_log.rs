@@ -0,0 +1,1843 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the `Log` struct and its construction APIs.
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use rlg::{
+        config::{Config, LoggingDestination},
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+        RlgError,
+    };
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    };
+    use tempfile::tempdir;
+    use tokio::fs;
+
+    /// Tests that the builder fills in defaults for unset fields.
+    #[test]
+    fn test_log_builder_defaults() {
+        let log = Log::builder()
+            .component("auth")
+            .description("login attempt")
+            .build()
+            .unwrap();
+
+        assert_eq!(log.component, "auth");
+        assert_eq!(log.description, "login attempt");
+        assert_eq!(log.level, LogLevel::INFO);
+        assert_eq!(log.format, LogFormat::CLF);
+        assert!(!log.session_id.is_empty());
+        assert!(!log.time.is_empty());
+    }
+
+    /// Tests that explicitly set fields take precedence over defaults.
+    #[test]
+    fn test_log_builder_all_fields() {
+        let log = Log::builder()
+            .session_id("session-1")
+            .time("2024-01-01T00:00:00Z")
+            .level(LogLevel::ERROR)
+            .component("db")
+            .description("connection lost")
+            .format(LogFormat::JSON)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            log,
+            Log::new(
+                "session-1",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::ERROR,
+                "db",
+                "connection lost",
+                &LogFormat::JSON,
+            )
+        );
+    }
+
+    /// Tests that the builder can be reused to build several entries.
+    #[test]
+    fn test_log_builder_reuse() {
+        let mut builder = Log::builder();
+        builder.component("worker").level(LogLevel::DEBUG);
+
+        let first = builder.description("first").build().unwrap();
+        let second = builder.description("second").build().unwrap();
+
+        assert_eq!(first.component, "worker");
+        assert_eq!(second.component, "worker");
+        assert_eq!(first.description, "first");
+        assert_eq!(second.description, "second");
+        assert_eq!(second.level, LogLevel::DEBUG);
+    }
+
+    /// Tests that missing or empty mandatory fields are rejected.
+    #[test]
+    fn test_log_builder_missing_fields() {
+        assert!(Log::builder().description("message").build().is_err());
+        assert!(Log::builder().component("app").build().is_err());
+        assert!(Log::builder()
+            .component("")
+            .description("message")
+            .build()
+            .is_err());
+        assert!(Log::builder()
+            .component("app")
+            .description("  ")
+            .build()
+            .is_err());
+    }
+
+    /// Tests that explicitly empty session IDs and timestamps are rejected.
+    #[test]
+    fn test_log_builder_empty_generated_fields() {
+        let result = Log::builder()
+            .session_id("")
+            .component("app")
+            .description("message")
+            .build();
+        assert!(result.is_err());
+
+        let result = Log::builder()
+            .time("")
+            .component("app")
+            .description("message")
+            .build();
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("time"));
+    }
+
+    /// Builds a sample log entry in the given format.
+    fn sample_log(format: LogFormat) -> Log {
+        common::sample_log(
+            LogLevel::INFO,
+            "api",
+            "request handled",
+            format,
+        )
+    }
+
+    /// Tests that extra fields are attached by the chainable constructors.
+    #[test]
+    fn test_log_with_fields() {
+        let log = sample_log(LogFormat::CLF)
+            .with_field("user_id", "42")
+            .with_fields(&[
+                ("request_id", "abc"),
+                ("latency_ms", "13"),
+            ]);
+
+        assert_eq!(log.fields.len(), 3);
+        assert_eq!(log.fields["user_id"], "42");
+        assert_eq!(log.fields["request_id"], "abc");
+        assert_eq!(log.fields["latency_ms"], "13");
+    }
+
+    /// Tests that a log without extra fields renders exactly as before.
+    #[test]
+    fn test_log_without_fields() {
+        let log = sample_log(LogFormat::CLF);
+        assert!(log.fields.is_empty());
+        assert_eq!(
+            log.to_string(),
+            "SessionID=session-1 Timestamp=2024-01-01T00:00:00Z Description=request handled Level=INFO Component=api"
+        );
+
+        let json = serde_json::to_string(&log).unwrap();
+        assert!(!json.contains("fields"));
+    }
+
+    /// Tests that text formats append extra fields as key=value pairs.
+    #[test]
+    fn test_log_fields_text_formats() {
+        let clf = sample_log(LogFormat::CLF)
+            .with_fields(&[("request_id", "abc"), ("user_id", "42")]);
+        assert!(clf
+            .to_string()
+            .ends_with("Component=api request_id=abc user_id=42"));
+
+        let cef =
+            sample_log(LogFormat::CEF).with_field("user_id", "42");
+        assert!(cef.to_string().ends_with("|CEF user_id=42"));
+    }
+
+    /// Tests that JSON-family formats serialize extra fields inline.
+    #[test]
+    fn test_log_fields_json_formats() {
+        for format in [
+            LogFormat::JSON,
+            LogFormat::GELF,
+            LogFormat::Logstash,
+            LogFormat::NDJSON,
+        ] {
+            let log = sample_log(format.clone())
+                .with_field("user_id", "42")
+                .with_field("quote", "say \"hi\"");
+            let value: serde_json::Value =
+                serde_json::from_str(&log.to_string()).unwrap();
+            assert_eq!(value["user_id"], "42", "format {}", format);
+            assert_eq!(
+                value["quote"], "say \"hi\"",
+                "format {}",
+                format
+            );
+        }
+    }
+
+    /// Tests that extra fields survive a serde JSON round trip.
+    #[test]
+    fn test_log_fields_serde_round_trip() {
+        let log = sample_log(LogFormat::JSON)
+            .with_fields(&[("user_id", "42"), ("request_id", "abc")]);
+        let json = serde_json::to_string(&log).unwrap();
+        let decoded: Log = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, log);
+
+        let legacy = r#"{"session_id":"1","time":"t","level":"INFO","component":"c","description":"d","format":"CLF"}"#;
+        let decoded: Log = serde_json::from_str(legacy).unwrap();
+        assert!(decoded.fields.is_empty());
+    }
+
+    /// Tests that a batch is written to the file in order.
+    #[tokio::test]
+    async fn test_log_batch_to_path() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("batch.log");
+
+        let entries: Vec<Log> = (0..10)
+            .map(|i| {
+                Log::new(
+                    &format!("session_{}", i),
+                    "2024-01-01T00:00:00Z",
+                    &LogLevel::INFO,
+                    "batch",
+                    &format!("message {}", i),
+                    &LogFormat::CLF,
+                )
+            })
+            .collect();
+
+        Log::log_batch_to_path(&path, &entries).await.unwrap();
+        Log::log_batch_to_path(&path, &entries[..2]).await.unwrap();
+
+        let content = fs::read_to_string(&path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 12);
+        for (i, line) in lines.iter().take(10).enumerate() {
+            assert!(
+                line.starts_with(&format!("SessionID=session_{} ", i))
+            );
+            assert!(
+                line.contains(&format!("Description=message {} ", i))
+            );
+        }
+        assert!(lines[10].starts_with("SessionID=session_0 "));
+    }
+
+    /// Tests that mixed supported formats are each written on their own line.
+    #[tokio::test]
+    async fn test_log_batch_mixed_formats() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("mixed.log");
+
+        let entries = vec![
+            sample_log(LogFormat::CLF),
+            sample_log(LogFormat::JSON),
+            sample_log(LogFormat::CEF),
+        ];
+        Log::log_batch_to_path(&path, &entries).await.unwrap();
+
+        let content = fs::read_to_string(&path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("Format=CLF"));
+        assert!(
+            serde_json::from_str::<serde_json::Value>(lines[1]).is_ok()
+        );
+        assert!(lines[2].starts_with("CEF:0|"));
+    }
+
+    /// Tests that an unsupported format is rejected before the file is opened.
+    #[tokio::test]
+    async fn test_log_batch_unsupported_format() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("unsupported.log");
+
+        let entries = vec![
+            sample_log(LogFormat::CLF),
+            sample_log(LogFormat::GELF),
+        ];
+        let result = Log::log_batch_to_path(&path, &entries).await;
+        assert!(matches!(result, Err(RlgError::UnsupportedFormat(_))));
+        assert!(!path.exists());
+
+        let result = Log::log_batch(&entries).await;
+        assert!(matches!(result, Err(RlgError::UnsupportedFormat(_))));
+    }
+
+    /// Tests that entries below their component's level are skipped.
+    #[tokio::test]
+    async fn test_log_with_config_component_levels() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config {
+            log_file_path: temp_dir.path().join("levels.log"),
+            log_level: LogLevel::WARN,
+            ..Default::default()
+        };
+        config
+            .component_log_levels
+            .insert("database".to_string(), LogLevel::DEBUG);
+
+        let entry = |level: LogLevel, component: &str| {
+            Log::new(
+                "1",
+                "2024-01-01T00:00:00Z",
+                &level,
+                component,
+                &format!("{} {}", component, level),
+                &LogFormat::CLF,
+            )
+        };
+        for log in [
+            entry(LogLevel::DEBUG, "database"),
+            entry(LogLevel::INFO, "http"),
+            entry(LogLevel::ERROR, "http"),
+        ] {
+            log.log_with_config(&config).await.unwrap();
+        }
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        assert!(content.contains("Description=database DEBUG"));
+        assert!(!content.contains("Description=http INFO"));
+        assert!(content.contains("Description=http ERROR"));
+        assert_eq!(content.lines().count(), 2);
+    }
+
+    /// Representative log entries used by the parser round-trip tests.
+    fn parser_samples(format: LogFormat) -> Vec<Log> {
+        vec![
+            Log::new(
+                "123",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::INFO,
+                "api",
+                "request handled",
+                &format,
+            ),
+            Log::new(
+                "12345678-1234-1234-1234-1234567890ab",
+                "2023-01-23 14:03:00.000+0000",
+                &LogLevel::ERROR,
+                "Test",
+                "This is a test log message",
+                &format,
+            ),
+            Log::new(
+                "456",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::WARN,
+                "",
+                "empty component",
+                &format,
+            ),
+            Log::new(
+                "789",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::DEBUG,
+                "parser",
+                "key=value a=b == c",
+                &format,
+            ),
+            Log::new(
+                "ünïcødé",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::CRITICAL,
+                "日本語",
+                "Grüße 🦀 — naïve café",
+                &format,
+            ),
+            Log::new(
+                "321",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::TRACE,
+                "auth",
+                "user logged in",
+                &format,
+            )
+            .with_fields(&[("user_id", "42"), ("request_id", "abc")]),
+        ]
+    }
+
+    /// Tests that CLF output parses back into an equal `Log`.
+    #[test]
+    fn test_log_from_clf_str_round_trip() {
+        for log in parser_samples(LogFormat::CLF) {
+            let parsed = Log::from_clf_str(&log.to_string()).unwrap();
+            assert_eq!(parsed, log);
+        }
+    }
+
+    /// Tests that lines written to a log file by `log()` can be parsed.
+    #[test]
+    fn test_log_from_clf_str_file_line() {
+        let line = "SessionID=1 Timestamp=2024-01-01 Description=started Level=INFO Component=app Format=CLF user_id=42\n";
+        let log = Log::from_clf_str(line).unwrap();
+        assert_eq!(log.description, "started");
+        assert_eq!(log.component, "app");
+        assert_eq!(log.fields.len(), 1);
+        assert_eq!(log.fields["user_id"], "42");
+    }
+
+    /// Tests that malformed CLF input is rejected.
+    #[test]
+    fn test_log_from_clf_str_invalid() {
+        for input in [
+            "",
+            "not a log entry",
+            "SessionID=1 Timestamp=t Description=d Component=c",
+            "SessionID=1 Timestamp=t Description=d Level=BOGUS Component=c",
+        ] {
+            let result = Log::from_clf_str(input);
+            assert!(
+                matches!(result, Err(RlgError::FormatParseError(_))),
+                "input {:?} should be rejected",
+                input
+            );
+        }
+    }
+
+    /// Tests that changing one CLF part changes only that field.
+    #[test]
+    fn test_log_clf_parts_round_trip() {
+        let log = Log::new(
+            "7",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::WARN,
+            "auth",
+            "user logged in",
+            &LogFormat::CLF,
+        );
+        let mut parts = log.into_clf_parts();
+        assert_eq!(parts.session_id, "7");
+        assert_eq!(parts.timestamp, "2024-01-01T00:00:00Z");
+        assert_eq!(parts.level, "WARN");
+        assert_eq!(parts.component, "auth");
+
+        parts.description = "user logged out".to_string();
+        let changed = parts.to_log(LogFormat::CLF).unwrap();
+        assert_eq!(changed.description, "user logged out");
+        assert_eq!(
+            Log {
+                description: log.description.clone(),
+                ..changed
+            },
+            log
+        );
+
+        parts.level = "BOGUS".to_string();
+        assert!(matches!(
+            parts.to_log(LogFormat::CLF),
+            Err(RlgError::FormatParseError(_))
+        ));
+    }
+
+    /// Tests that JSON output parses back into an equal `Log`.
+    #[test]
+    fn test_log_from_json_str_round_trip() {
+        for log in parser_samples(LogFormat::JSON) {
+            let parsed = Log::from_json_str(&log.to_string()).unwrap();
+            assert_eq!(parsed, log);
+        }
+    }
+
+    /// Tests that `to_json_value` equals the parsed JSON output, with
+    /// extra fields and sequence numbers included.
+    #[test]
+    fn test_log_to_json_value() {
+        let mut samples = parser_samples(LogFormat::JSON);
+        samples.push(
+            sample_log(LogFormat::JSON)
+                .with_fields(&[("user_id", "42"), ("region", "eu")]),
+        );
+        samples.push(Log {
+            sequence: Some(7),
+            ..sample_log(LogFormat::JSON)
+        });
+        for log in samples {
+            let expected: serde_json::Value =
+                serde_json::from_str(&log.to_string()).unwrap();
+            assert_eq!(log.to_json_value(), expected);
+        }
+    }
+
+    /// Tests that `merge_json` overlays string members onto the fields and
+    /// rejects values that are not objects.
+    #[test]
+    fn test_log_merge_json() {
+        let mut log =
+            sample_log(LogFormat::JSON).with_field("region", "us");
+        log.merge_json(serde_json::json!({
+            "region": "eu",
+            "user_id": "42",
+            "retries": 3,
+            "nested": { "a": "b" },
+        }))
+        .unwrap();
+        assert_eq!(log.fields.len(), 2);
+        assert_eq!(log.fields["region"], "eu");
+        assert_eq!(log.fields["user_id"], "42");
+        assert_eq!(log.to_json_value()["user_id"], "42");
+
+        let before = log.clone();
+        for value in [
+            serde_json::json!(null),
+            serde_json::json!("text"),
+            serde_json::json!([{ "a": "b" }]),
+        ] {
+            assert!(matches!(
+                log.merge_json(value),
+                Err(RlgError::FormatParseError(_))
+            ));
+        }
+        assert_eq!(log, before);
+    }
+
+    /// Tests that malformed JSON input is rejected with a descriptive error.
+    #[test]
+    fn test_log_from_json_str_invalid() {
+        let result = Log::from_json_str("{not json");
+        assert!(matches!(result, Err(RlgError::FormatParseError(_))));
+
+        let result = Log::from_json_str("[1, 2, 3]");
+        assert!(matches!(result, Err(RlgError::FormatParseError(_))));
+
+        let result = Log::from_json_str(
+            r#"{"SessionID":"1","Timestamp":"t","Level":"INFO","Component":"c"}"#,
+        );
+        match result {
+            Err(RlgError::FormatParseError(message)) => {
+                assert!(message.contains("Description"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let result = Log::from_json_str(
+            r#"{"SessionID":"1","Timestamp":"t","Level":"LOUD","Component":"c","Description":"d"}"#,
+        );
+        assert!(matches!(result, Err(RlgError::FormatParseError(_))));
+    }
+
+    /// Tests that GELF output parses back into an equal `Log`, with extra
+    /// fields and sequence numbers included.
+    #[test]
+    fn test_log_from_gelf_str_round_trip() {
+        let mut samples = parser_samples(LogFormat::GELF);
+        samples.push(
+            sample_log(LogFormat::GELF)
+                .with_fields(&[("user_id", "42"), ("pid", "7")]),
+        );
+        samples.push(Log {
+            sequence: Some(7),
+            ..sample_log(LogFormat::GELF)
+        });
+        for log in samples {
+            let parsed = Log::from_gelf_str(&log.to_string()).unwrap();
+            assert_eq!(parsed, log);
+        }
+    }
+
+    /// Tests that the component falls back to `host`, and that malformed
+    /// GELF input is rejected.
+    #[test]
+    fn test_log_from_gelf_str_invalid() {
+        let parsed = Log::from_gelf_str(
+            r#"{"version":"1.1","host":"web","short_message":"m","level":"WARN","timestamp":"t","session_id":"1"}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.component, "web");
+        assert_eq!(parsed.level, LogLevel::WARN);
+        assert_eq!(parsed.description, "m");
+        assert!(parsed.fields.is_empty());
+
+        let result = Log::from_gelf_str("{not json");
+        assert!(matches!(result, Err(RlgError::FormatParseError(_))));
+
+        let result = Log::from_gelf_str(r#""text""#);
+        assert!(matches!(result, Err(RlgError::FormatParseError(_))));
+
+        let result = Log::from_gelf_str(
+            r#"{"version":"1.1","host":"web","level":"INFO","timestamp":"t","session_id":"1"}"#,
+        );
+        match result {
+            Err(RlgError::FormatParseError(message)) => {
+                assert!(message.contains("short_message"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let result = Log::from_gelf_str(
+            r#"{"version":"1.1","host":"web","short_message":"m","level":"LOUD","timestamp":"t","session_id":"1"}"#,
+        );
+        assert!(matches!(result, Err(RlgError::FormatParseError(_))));
+    }
+
+    /// An error with an optional underlying cause.
+    #[derive(Debug)]
+    struct LayeredError {
+        message: String,
+        source: Option<Box<LayeredError>>,
+    }
+
+    impl std::fmt::Display for LayeredError {
+        fn fmt(
+            &self,
+            f: &mut std::fmt::Formatter<'_>,
+        ) -> std::fmt::Result {
+            f.write_str(&self.message)
+        }
+    }
+
+    impl std::error::Error for LayeredError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            let source: &(dyn std::error::Error + 'static) =
+                self.source.as_deref()?;
+            Some(source)
+        }
+    }
+
+    /// Builds an error chain with one layer per message, outermost first.
+    fn layered(messages: &[&str]) -> LayeredError {
+        messages
+            .iter()
+            .rev()
+            .fold(None, |source, message| {
+                Some(LayeredError {
+                    message: message.to_string(),
+                    source: source.map(Box::new),
+                })
+            })
+            .unwrap()
+    }
+
+    /// Tests that every level of an error chain is in the description.
+    #[test]
+    fn test_log_new_from_error() {
+        let err = layered(&[
+            "request failed",
+            "query timed out",
+            "connection reset",
+        ]);
+        let log = Log::new_from_error(&err, LogLevel::ERROR, "api");
+        assert_eq!(
+            log.description,
+            "request failed caused by: query timed out caused by: connection reset"
+        );
+        assert_eq!(log.level, LogLevel::ERROR);
+        assert_eq!(log.component, "api");
+        assert_eq!(log.format, LogFormat::CLF);
+        assert!(log.session_id.parse::<u32>().is_ok());
+        assert!(!log.time.is_empty());
+
+        let single = layered(&["disk full"]);
+        let log =
+            Log::new_from_error(&single, LogLevel::WARN, "storage");
+        assert_eq!(log.description, "disk full");
+    }
+
+    /// Tests that at most five sources are included.
+    #[test]
+    fn test_log_new_from_error_depth_limit() {
+        let err = layered(&["e0", "e1", "e2", "e3", "e4", "e5", "e6"]);
+        let log = Log::new_from_error(&err, LogLevel::ERROR, "api");
+        assert_eq!(
+            log.description,
+            "e0 caused by: e1 caused by: e2 caused by: e3 caused by: e4 caused by: e5"
+        );
+    }
+
+    /// Tests that template placeholders are substituted into the
+    /// description.
+    #[test]
+    fn test_log_new_with_format_template() {
+        let params = HashMap::from([
+            ("user", "alice"),
+            ("ip", "10.0.0.1"),
+            ("method", "password"),
+        ]);
+        let log = Log::new_with_format_template(
+            "User %{user} logged in from %{ip} with %{method}",
+            &params,
+            &LogLevel::INFO,
+            "auth",
+        )
+        .unwrap();
+        assert_eq!(
+            log.description,
+            "User alice logged in from 10.0.0.1 with password"
+        );
+        assert_eq!(log.level, LogLevel::INFO);
+        assert_eq!(log.component, "auth");
+        assert_eq!(log.format, LogFormat::CLF);
+
+        let result = Log::new_with_format_template(
+            "User %{user} logged in from %{host}",
+            &params,
+            &LogLevel::INFO,
+            "auth",
+        );
+        assert!(matches!(result, Err(RlgError::FormattingError(_))));
+    }
+
+    /// Tests that `new_child` inherits the parent's identity.
+    #[test]
+    fn test_log_new_child() {
+        let parent = Log::new(
+            "session-1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::WARN,
+            "pipeline",
+            "ingest",
+            &LogFormat::JSON,
+        );
+        let child = Log::new_child(&parent, "parse batch");
+        assert_eq!(parent.session_id, child.session_id);
+        assert_eq!(parent.component, child.component);
+        assert_eq!(child.level, LogLevel::WARN);
+        assert_eq!(child.format, LogFormat::JSON);
+        assert_eq!(child.description, "parse batch");
+        assert!(!child.time.is_empty());
+
+        let child = Log::new_child_with_level(
+            &parent,
+            LogLevel::DEBUG,
+            "batch details",
+        );
+        assert_eq!(parent.session_id, child.session_id);
+        assert_eq!(parent.component, child.component);
+        assert_eq!(child.level, LogLevel::DEBUG);
+        assert_eq!(child.description, "batch details");
+    }
+
+    /// Tests that `render` produces valid JSON from any original format.
+    #[test]
+    fn test_log_render_json_from_any_format() {
+        for format in [
+            LogFormat::CLF,
+            LogFormat::JSON,
+            LogFormat::CEF,
+            LogFormat::GELF,
+            LogFormat::Log4jXML,
+            LogFormat::CSV,
+        ] {
+            for log in parser_samples(format.clone()) {
+                let json = log.render(LogFormat::JSON).unwrap();
+                assert!(
+                    serde_json::from_str::<serde_json::Value>(&json)
+                        .is_ok(),
+                    "invalid JSON from {}: {}",
+                    format,
+                    json
+                );
+                assert_eq!(log.format, format);
+
+                let parsed = Log::from_json_str(&json).unwrap();
+                assert_eq!(
+                    parsed,
+                    log.convert_format(LogFormat::JSON).unwrap()
+                );
+            }
+        }
+    }
+
+    /// Tests that `convert_format` only changes the format.
+    #[test]
+    fn test_log_convert_format() {
+        let log = Log::new(
+            "1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::ERROR,
+            "db",
+            "connection lost",
+            &LogFormat::CLF,
+        )
+        .with_field("retries", "3");
+        let converted = log.convert_format(LogFormat::NDJSON).unwrap();
+        assert_eq!(converted.format, LogFormat::NDJSON);
+        assert_eq!(
+            Log {
+                format: LogFormat::CLF,
+                ..converted.clone()
+            },
+            log
+        );
+        assert_eq!(
+            converted.to_string(),
+            log.render(LogFormat::NDJSON).unwrap()
+        );
+    }
+
+    /// Tests that converting GELF to CLF flattens the JSON fields.
+    #[test]
+    fn test_log_convert_gelf_to_clf_flattens_fields() {
+        let gelf = Log::new(
+            "1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::WARN,
+            "api",
+            "slow request",
+            &LogFormat::GELF,
+        )
+        .with_fields(&[("duration_ms", "1200"), ("user_id", "42")]);
+        assert!(gelf.to_string().contains("\"duration_ms\": \"1200\""));
+
+        let clf = gelf.render(LogFormat::CLF).unwrap();
+        assert!(
+            clf.ends_with(" duration_ms=1200 user_id=42"),
+            "{}",
+            clf
+        );
+        let parsed = Log::from_clf_str(&clf).unwrap();
+        assert_eq!(
+            parsed,
+            gelf.convert_format(LogFormat::CLF).unwrap()
+        );
+        assert_eq!(parsed.fields["user_id"], "42");
+    }
+
+    /// Tests that UUID session IDs are valid UUID v4 strings and unique.
+    #[test]
+    fn test_log_new_with_uuid_session() {
+        let new = || {
+            Log::new_with_uuid_session(
+                "2024-01-01T00:00:00Z",
+                &LogLevel::INFO,
+                "api",
+                "request",
+                &LogFormat::JSON,
+            )
+        };
+        let (first, second) = (new(), new());
+        for log in [&first, &second] {
+            let uuid = uuid::Uuid::parse_str(&log.session_id).unwrap();
+            assert_eq!(uuid.get_version_num(), 4);
+            assert_eq!(log.time, "2024-01-01T00:00:00Z");
+            assert_eq!(log.level, LogLevel::INFO);
+            assert_eq!(log.component, "api");
+            assert_eq!(log.description, "request");
+            assert_eq!(log.format, LogFormat::JSON);
+        }
+        assert_ne!(first.session_id, second.session_id);
+    }
+
+    /// Tests that the pretty output lists every field as plain text when
+    /// color is disabled.
+    #[test]
+    fn test_log_to_pretty_string() {
+        colored::control::set_override(false);
+        let mut log = Log::new(
+            "session-1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::ERROR,
+            "db",
+            "connection lost",
+            &LogFormat::JSON,
+        )
+        .with_field("retries", "3");
+        log.sequence = Some(7);
+
+        let pretty = log.to_pretty_string();
+        assert!(!pretty.contains('\u{1b}'), "{:?}", pretty);
+        assert_eq!(
+            pretty,
+            "ERROR 2024-01-01T00:00:00Z db\n  connection lost\n  SessionID=session-1\n  retries=3\n  Sequence=7"
+        );
+    }
+
+    /// Tests that MessagePack encoding round-trips every entry.
+    #[test]
+    fn test_log_bytes_round_trip() {
+        for format in
+            [LogFormat::CLF, LogFormat::JSON, LogFormat::Log4jXML]
+        {
+            for log in parser_samples(format) {
+                let bytes = log.to_bytes().unwrap();
+                assert_eq!(Log::from_bytes(&bytes).unwrap(), log);
+            }
+        }
+        let log = Log::default();
+        assert_eq!(
+            Log::from_bytes(&log.to_bytes().unwrap()).unwrap(),
+            log
+        );
+    }
+
+    /// Tests rendering and reading back the tags set by `with_tags`.
+    #[test]
+    fn test_log_with_tags() {
+        let log = Log::new(
+            "session-1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::INFO,
+            "billing",
+            "invoice sent",
+            &LogFormat::JSON,
+        )
+        .with_tags(&["billing", "eu-west-1", "v2"]);
+        assert_eq!(log.tags(), ["billing", "eu-west-1", "v2"]);
+        assert_eq!(log.fields["_tags"], "billing,eu-west-1,v2");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&log.to_string()).unwrap();
+        assert_eq!(
+            json["_tags"],
+            serde_json::json!(["billing", "eu-west-1", "v2"])
+        );
+        assert_eq!(Log::from_json_str(&log.to_string()).unwrap(), log);
+        for format in
+            [LogFormat::GELF, LogFormat::Logstash, LogFormat::NDJSON]
+        {
+            let rendered: serde_json::Value =
+                serde_json::from_str(&log.render(format).unwrap())
+                    .unwrap();
+            assert!(rendered["_tags"].is_array(), "{}", rendered);
+        }
+
+        let clf = log.render(LogFormat::CLF).unwrap();
+        assert!(clf.ends_with(" tags=billing,eu-west-1,v2"), "{}", clf);
+        assert_eq!(Log::from_clf_str(&clf).unwrap().tags(), log.tags());
+
+        let untagged = log.clone().with_tags(&[]);
+        assert!(untagged.tags().is_empty());
+        let replaced = log.with_tags(&["us"]);
+        assert_eq!(replaced.tags(), ["us"]);
+    }
+
+    /// Tests that context map entries become `ctx.`-prefixed fields.
+    #[test]
+    fn test_log_new_with_context_map() {
+        let context: HashMap<String, String> = [
+            ("request_id", "req-42"),
+            ("user_id", "7"),
+            ("tenant", "acme"),
+            ("region", "eu-west-1"),
+            ("_tags", "not-a-tag"),
+        ]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        let log = Log::new_with_context_map(
+            "session-1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::INFO,
+            "api",
+            "request handled",
+            &LogFormat::JSON,
+            &context,
+        );
+        assert_eq!(log.fields.len(), 5);
+        assert!(log.tags().is_empty());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&log.to_string()).unwrap();
+        for (key, value) in &context {
+            assert_eq!(
+                json[format!("ctx.{}", key)],
+                value.as_str(),
+                "{}",
+                json
+            );
+        }
+    }
+
+    /// Tests that `severity_score` follows the level and grows mildly
+    /// with the description length.
+    #[test]
+    fn test_log_severity_score() {
+        let log = |level: &LogLevel, description: &str| {
+            Log::new(
+                "session-1",
+                "2024-01-01T00:00:00Z",
+                level,
+                "db",
+                description,
+                &LogFormat::CLF,
+            )
+        };
+        let short = log(&LogLevel::WARN, "slow");
+        let long = log(&LogLevel::WARN, &"slow ".repeat(500));
+        assert!(
+            short.severity_score() >= LogLevel::WARN.base_severity()
+        );
+        assert!(long.severity_score() > short.severity_score());
+        assert!(
+            long.severity_score()
+                < log(&LogLevel::ERROR, "").severity_score()
+        );
+        assert_eq!(log(&LogLevel::ALL, "").severity_score(), 0.0);
+        assert_eq!(
+            log(&LogLevel::CRITICAL, &"x".repeat(4096))
+                .severity_score(),
+            1.0
+        );
+    }
+
+    /// Tests that `hash_id` depends only on level, component and
+    /// description.
+    #[test]
+    fn test_log_hash_id() {
+        let log = Log::new(
+            "session-1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::ERROR,
+            "db",
+            "connection lost",
+            &LogFormat::CLF,
+        );
+        let hash = log.hash_id();
+        assert_eq!(hash.len(), 64);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+
+        let same = Log {
+            session_id: "session-2".to_string(),
+            time: "2024-06-01T12:00:00Z".to_string(),
+            format: LogFormat::JSON,
+            ..log.clone()
+        }
+        .with_field("retry", "1");
+        assert_eq!(same.hash_id(), hash);
+
+        let different = [
+            Log {
+                description: "connection restored".to_string(),
+                ..log.clone()
+            },
+            Log {
+                component: "cache".to_string(),
+                ..log.clone()
+            },
+            Log {
+                level: LogLevel::WARN,
+                ..log.clone()
+            },
+            Log {
+                component: "dbconnection".to_string(),
+                description: " lost".to_string(),
+                ..log.clone()
+            },
+        ];
+        for other in &different {
+            assert_ne!(other.hash_id(), hash, "{:?}", other);
+        }
+    }
+
+    /// Tests that invalid MessagePack input is rejected.
+    #[test]
+    fn test_log_from_bytes_invalid() {
+        for input in [&[][..], &[0xc1][..], b"not msgpack"] {
+            assert!(matches!(
+                Log::from_bytes(input),
+                Err(RlgError::FormatParseError(_))
+            ));
+        }
+    }
+
+    /// A pattern matching credit-card-like numbers.
+    const CARD_PATTERN: &str = r"\b(?:\d{4}[ -]?){3}\d{4}\b";
+
+    /// A pattern matching email addresses.
+    const EMAIL_PATTERN: &str = r"[\w.+-]+@[\w-]+\.[\w.]+";
+
+    /// Tests that credit-card-like numbers are masked.
+    #[test]
+    fn test_log_redact_credit_card() {
+        let log = Log {
+            description: "charged card 4111 1111 1111 1111 ok"
+                .to_string(),
+            ..sample_log(LogFormat::CLF)
+        }
+        .with_field("card", "4111-1111-1111-1111");
+
+        let redacted = log.redact(&[CARD_PATTERN]);
+        assert_eq!(redacted.description, "charged card [REDACTED] ok");
+        assert_eq!(redacted.fields["card"], "[REDACTED]");
+        assert_eq!(log.fields["card"], "4111-1111-1111-1111");
+    }
+
+    /// Tests that email addresses are masked in the description and fields.
+    #[test]
+    fn test_log_redact_email() {
+        let log = Log {
+            description:
+                "welcome mail sent to jane.doe+test@example.co.uk"
+                    .to_string(),
+            ..sample_log(LogFormat::JSON)
+        }
+        .with_fields(&[("to", "bob@example.com"), ("user_id", "42")]);
+
+        let redacted = log.redact(&[CARD_PATTERN, EMAIL_PATTERN]);
+        assert_eq!(
+            redacted.description,
+            "welcome mail sent to [REDACTED]"
+        );
+        assert_eq!(redacted.fields["to"], "[REDACTED]");
+        assert_eq!(redacted.fields["user_id"], "42");
+    }
+
+    /// Tests that text not matching any pattern is left untouched.
+    #[test]
+    fn test_log_redact_untouched() {
+        let log =
+            sample_log(LogFormat::CLF).with_field("user_id", "42");
+
+        assert_eq!(log.redact(&[CARD_PATTERN, EMAIL_PATTERN]), log);
+        assert_eq!(log.redact(&[]), log);
+        // Invalid patterns are ignored rather than masking everything.
+        assert_eq!(log.redact(&["(unclosed"]), log);
+    }
+
+    /// Tests that `log_with_config` applies the configured redact patterns.
+    #[tokio::test]
+    async fn test_log_with_config_redact_patterns() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("redact.log"),
+            redact_patterns: vec![EMAIL_PATTERN.to_string()],
+            ..Default::default()
+        };
+
+        Log {
+            description: "login by jane@example.com".to_string(),
+            ..sample_log(LogFormat::CLF)
+        }
+        .log_with_config(&config)
+        .await
+        .unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        assert!(content.contains("Description=login by [REDACTED]"));
+        assert!(!content.contains("jane@example.com"));
+    }
+
+    /// Tests that card numbers in the description and fields never reach
+    /// the log file.
+    #[tokio::test]
+    async fn test_log_with_config_redacts_credit_card() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("redact.log"),
+            redact_patterns: vec![CARD_PATTERN.to_string()],
+            ..Default::default()
+        };
+
+        Log {
+            description: "payload {\"card\":\"4111 1111 1111 1111\"}"
+                .to_string(),
+            ..sample_log(LogFormat::CLF)
+        }
+        .with_field("card", "4111-1111-1111-1111")
+        .log_with_config(&config)
+        .await
+        .unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        assert!(
+            content.contains("{\"card\":\"[REDACTED]\"}"),
+            "{}",
+            content
+        );
+        assert!(content.contains(" card=[REDACTED]"), "{}", content);
+        assert!(!content.contains("4111"), "{}", content);
+    }
+
+    /// Tests that entries in the default format are written in the format
+    /// named by `Config::log_format`, and others keep their own.
+    #[tokio::test]
+    async fn test_log_with_config_log_format_name() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("format.log"),
+            log_format: "json".to_string(),
+            ..Default::default()
+        };
+
+        sample_log(LogFormat::CLF)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+        sample_log(LogFormat::CEF)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", content);
+        let parsed = Log::from_json_str(lines[0]).unwrap();
+        assert_eq!(parsed.format, LogFormat::JSON);
+        assert_eq!(parsed.description, "request handled");
+        assert!(lines[1].starts_with("CEF:0|"), "{}", lines[1]);
+    }
+
+    /// Tests that extra CLF fields in the log file are separated by
+    /// `Config::structured_fields_separator`.
+    #[tokio::test]
+    async fn test_log_with_config_structured_fields_separator() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("separator.log"),
+            structured_fields_separator: '\t',
+            ..Default::default()
+        };
+
+        Log {
+            sequence: Some(3),
+            ..sample_log(LogFormat::CLF)
+        }
+        .with_fields(&[("region", "eu"), ("user_id", "42")])
+        .log_with_config(&config)
+        .await
+        .unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        assert!(
+            content.ends_with(
+                "Format=CLF\tregion=eu\tuser_id=42\tseq=3\n"
+            ),
+            "{:?}",
+            content
+        );
+    }
+
+    /// Tests that session IDs are anonymized consistently, that distinct
+    /// session IDs stay distinct, and that the original is not exposed.
+    #[test]
+    fn test_log_anonymize_session_id() {
+        let log = sample_log(LogFormat::CLF);
+        let anonymized = log.anonymize_session_id();
+        assert_eq!(anonymized.session_id, "84097828fc31a8c8");
+        assert!(anonymized
+            .session_id
+            .chars()
+            .all(|c| c.is_ascii_hexdigit()));
+        assert!(!anonymized.to_string().contains("session-1"));
+        assert_eq!(
+            Log {
+                session_id: log.session_id.clone(),
+                ..anonymized.clone()
+            },
+            log
+        );
+
+        let same = Log {
+            time: "2024-06-01T12:00:00Z".to_string(),
+            ..log.clone()
+        };
+        assert_eq!(
+            same.anonymize_session_id().session_id,
+            anonymized.session_id
+        );
+        let other = Log {
+            session_id: "session-2".to_string(),
+            ..log.clone()
+        };
+        assert_ne!(
+            other.anonymize_session_id().session_id,
+            anonymized.session_id
+        );
+    }
+
+    /// Tests that the process ID and thread name are attached, with the
+    /// process ID rendered as a JSON number.
+    #[test]
+    fn test_log_with_process_context() {
+        let log = sample_log(LogFormat::JSON).with_process_context();
+        assert_eq!(log.fields["pid"], std::process::id().to_string());
+        assert!(!log.fields["thread"].is_empty());
+
+        let value: serde_json::Value =
+            serde_json::from_str(&log.to_string()).unwrap();
+        assert_eq!(value["pid"], std::process::id());
+        assert!(value["thread"].is_string());
+        assert_eq!(log.to_json_value(), value);
+
+        let named = std::thread::Builder::new()
+            .name("worker-1".to_string())
+            .spawn(|| sample_log(LogFormat::CLF).with_process_context())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(named.fields["thread"], "worker-1");
+        let unnamed = std::thread::spawn(|| {
+            sample_log(LogFormat::CLF).with_process_context()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(unnamed.fields["thread"], "unnamed");
+    }
+
+    /// Tests that `log_with_config` attaches the process context when
+    /// `include_process_context` is set.
+    #[tokio::test]
+    async fn test_log_with_config_include_process_context() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("context.log"),
+            include_process_context: true,
+            ..Default::default()
+        };
+        sample_log(LogFormat::CLF)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        let logged = Log::from_clf_str(content.trim_end()).unwrap();
+        assert_eq!(
+            logged.fields["pid"],
+            std::process::id().to_string()
+        );
+        assert!(!logged.fields["thread"].is_empty());
+    }
+
+    /// Tests that `log_with_config` attaches the hostname when
+    /// `include_hostname` is set, in CLF and JSON entries.
+    #[tokio::test]
+    async fn test_log_with_config_include_hostname() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("hostname.log"),
+            include_hostname: true,
+            ..Default::default()
+        };
+        let hostname =
+            hostname::get().unwrap().to_string_lossy().into_owned();
+        sample_log(LogFormat::CLF)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+        sample_log(LogFormat::JSON)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let logged = Log::from_clf_str(lines[0]).unwrap();
+        assert_eq!(logged.fields["hostname"], hostname);
+        let logged: serde_json::Value =
+            serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(logged["hostname"], hostname.as_str());
+    }
+
+    /// Tests that `log_with_config` anonymizes session IDs when
+    /// `anonymize_session_ids` is set.
+    #[tokio::test]
+    async fn test_log_with_config_anonymize_session_ids() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config {
+            log_file_path: temp_dir.path().join("anonymized.log"),
+            anonymize_session_ids: true,
+            ..Default::default()
+        };
+        let log = sample_log(LogFormat::CLF);
+        log.log_with_config(&config).await.unwrap();
+        config.anonymize_session_ids = false;
+        log.log_with_config(&config).await.unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("SessionID=84097828fc31a8c8 "));
+        assert!(lines[1].starts_with("SessionID=session-1 "));
+    }
+
+    /// Tests truncating descriptions at character boundaries.
+    #[test]
+    fn test_log_ensure_max_description() {
+        let log = sample_log(LogFormat::CLF);
+        assert_eq!(log.ensure_max_description(1024), log);
+
+        let long = Log {
+            description: "é".repeat(20),
+            ..log.clone()
+        };
+        let truncated = long.ensure_max_description(25);
+        assert_eq!(
+            truncated.description,
+            format!("{}…[truncated]", "é".repeat(5))
+        );
+        let truncated = long.ensure_max_description(26);
+        assert_eq!(truncated.description.len(), 26);
+        assert!(truncated.description.ends_with("…[truncated]"));
+
+        // Limits too small for the marker cut the description alone.
+        assert_eq!(long.ensure_max_description(5).description, "éé");
+        assert_eq!(long.ensure_max_description(0).description, "");
+    }
+
+    /// Tests that `log_with_config` truncates descriptions longer than
+    /// `max_message_size`.
+    #[tokio::test]
+    async fn test_log_with_config_max_message_size() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("truncate.log"),
+            max_message_size: 1000,
+            ..Default::default()
+        };
+
+        Log {
+            description: "a".repeat(100 * 1024),
+            ..sample_log(LogFormat::CLF)
+        }
+        .log_with_config(&config)
+        .await
+        .unwrap();
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        let parsed = Log::from_clf_str(content.trim_end()).unwrap();
+        assert_eq!(parsed.description.len(), 1000);
+        assert!(parsed.description.ends_with("a…[truncated]"));
+    }
+
+    /// Tests that entries go to `fallback_destination` when the log file
+    /// cannot be written.
+    #[tokio::test]
+    async fn test_log_with_config_fallback_destination() {
+        let temp_dir = tempdir().unwrap();
+        let fallback_path = temp_dir.path().join("fallback.log");
+        // A directory cannot be opened for appending, even by root, which
+        // ignores read-only permissions.
+        let mut config = Config {
+            log_file_path: temp_dir.path().to_path_buf(),
+            fallback_destination: Some(LoggingDestination::File(
+                fallback_path.clone(),
+            )),
+            ..Default::default()
+        };
+        let log = sample_log(LogFormat::CLF);
+
+        log.log_with_config(&config).await.unwrap();
+        let content = fs::read_to_string(&fallback_path).await.unwrap();
+        let parsed = Log::from_clf_str(content.trim_end()).unwrap();
+        assert!(parsed.description.starts_with(
+            "[fallback: I/O error: Failed to open log file"
+        ));
+        assert!(parsed
+            .description
+            .ends_with(&format!("] {}", log.description)));
+
+        // The original error is returned when the fallback fails too.
+        config.fallback_destination = Some(LoggingDestination::File(
+            temp_dir.path().join("missing").join("fallback.log"),
+        ));
+        match log.log_with_config(&config).await {
+            Err(RlgError::IoError(error)) => assert!(error
+                .to_string()
+                .starts_with("Failed to open log file")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        config.fallback_destination = None;
+        assert!(matches!(
+            log.log_with_config(&config).await,
+            Err(RlgError::IoError(_))
+        ));
+    }
+
+    /// Tests that sequence numbers are rendered and parsed back.
+    #[test]
+    fn test_log_sequence_rendering() {
+        let log = Log::new_with_sequence(
+            42,
+            "session-1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::INFO,
+            "api",
+            "request handled",
+            &LogFormat::CLF,
+        )
+        .with_field("user_id", "7");
+        assert_eq!(log.sequence_number(), Some(42));
+
+        let clf = log.render(LogFormat::CLF).unwrap();
+        assert!(clf.ends_with(" user_id=7 seq=42"));
+        assert_eq!(Log::from_clf_str(&clf).unwrap(), log);
+
+        let json = log.convert_format(LogFormat::JSON).unwrap();
+        let output = json.to_string();
+        let value: serde_json::Value =
+            serde_json::from_str(&output).unwrap();
+        assert_eq!(value["Sequence"], 42);
+        assert_eq!(Log::from_json_str(&output).unwrap(), json);
+
+        for format in [LogFormat::GELF, LogFormat::Logstash] {
+            let value: serde_json::Value = serde_json::from_str(
+                &log.render(format.clone()).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(value["sequence"], 42, "{}", format);
+        }
+        assert_eq!(
+            Log::from_bytes(&log.to_bytes().unwrap()).unwrap(),
+            log
+        );
+
+        let unnumbered = sample_log(LogFormat::CLF);
+        assert_eq!(unnumbered.sequence_number(), None);
+        for format in [
+            LogFormat::CLF,
+            LogFormat::JSON,
+            LogFormat::GELF,
+            LogFormat::Logstash,
+        ] {
+            let output = unnumbered.render(format.clone()).unwrap();
+            assert!(!output.contains("seq"), "{}", format);
+            assert!(!output.contains("Sequence"), "{}", format);
+        }
+        assert!(Log::from_json_str(
+            r#"{"SessionID":"1","Timestamp":"t","Level":"INFO","Component":"c","Description":"d","Sequence":"x"}"#
+        )
+        .is_err());
+    }
+
+    /// Tests that concurrently logged entries get unique sequence numbers.
+    #[tokio::test]
+    async fn test_log_sequence_concurrent() {
+        let temp_dir = tempdir().unwrap();
+        let config = Arc::new(Config {
+            log_file_path: temp_dir.path().join("sequence.log"),
+            ..Default::default()
+        });
+
+        let tasks: Vec<_> = (0..100)
+            .map(|i| {
+                let config = Arc::clone(&config);
+                tokio::spawn(async move {
+                    Log {
+                        description: format!("entry {}", i),
+                        ..sample_log(LogFormat::CLF)
+                    }
+                    .log_with_config(&config)
+                    .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        let sequences: HashSet<u64> = content
+            .lines()
+            .map(|line| {
+                Log::from_clf_str(line)
+                    .unwrap()
+                    .sequence_number()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(content.lines().count(), 100);
+        assert_eq!(sequences.len(), 100);
+
+        // Entries that already have a sequence number keep it.
+        let numbered = Log {
+            sequence: Some(u64::MAX),
+            ..sample_log(LogFormat::CLF)
+        };
+        numbered.log_with_config(&config).await.unwrap();
+        let content =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        assert!(content.ends_with(&format!(" seq={}\n", u64::MAX)));
+    }
+
+    /// Tests the RFC 5424 header produced for a log entry.
+    #[test]
+    fn test_log_display_rfc5424() {
+        let log = sample_log(LogFormat::RFC5424);
+        let output = log.to_string();
+
+        let parts: Vec<&str> = output.splitn(7, ' ').collect();
+        assert_eq!(parts[0], "<14>1");
+        assert_eq!(parts[1], "2024-01-01T00:00:00Z");
+        assert_eq!(parts[3], "api");
+        assert_eq!(parts[4], std::process::id().to_string());
+        assert_eq!(parts[5], "-");
+        assert_eq!(
+            parts[6],
+            "[rlg@32473 sessionID=\"session-1\"] request handled"
+        );
+        assert!(LogFormat::RFC5424.validate(&output));
+    }
+
+    /// Tests that RFC 5424 priorities follow the log level.
+    #[test]
+    fn test_log_display_rfc5424_priority() {
+        for (level, priority) in [
+            (LogLevel::DEBUG, "<15>"),
+            (LogLevel::INFO, "<14>"),
+            (LogLevel::WARN, "<12>"),
+            (LogLevel::ERROR, "<11>"),
+            (LogLevel::CRITICAL, "<10>"),
+            (LogLevel::SECURITY, "<9>"),
+            (LogLevel::FATAL, "<8>"),
+        ] {
+            let log = Log {
+                level,
+                ..sample_log(LogFormat::RFC5424)
+            };
+            assert!(log.to_string().starts_with(priority), "{}", level);
+        }
+    }
+
+    /// Tests that RFC 5424 output stays valid for awkward values.
+    #[test]
+    fn test_log_display_rfc5424_edge_cases() {
+        let log = Log::new(
+            "quote\"back\\slash]",
+            "2024-01-01T00:00:00.123456789+02:00",
+            &LogLevel::INFO,
+            "",
+            "Grüße 🦀",
+            &LogFormat::RFC5424,
+        )
+        .with_fields(&[("user id", "4\"2"), ("=", "ignored")]);
+        let output = log.to_string();
+
+        assert!(LogFormat::RFC5424.validate(&output), "{}", output);
+        assert!(output.contains(" 2024-01-01T00:00:00.123456+02:00 "));
+        assert!(output.contains(
+            "[rlg@32473 sessionID=\"quote\\\"back\\\\slash\\]\" userid=\"4\\\"2\"]"
+        ));
+        assert!(output.ends_with("] Grüße 🦀"));
+
+        let log = Log::new(
+            "1",
+            "not a timestamp",
+            &LogLevel::INFO,
+            "my app",
+            "message",
+            &LogFormat::RFC5424,
+        );
+        let output = log.to_string();
+        assert!(LogFormat::RFC5424.validate(&output), "{}", output);
+        assert!(output.starts_with("<14>1 - "));
+        assert!(output.contains(" myapp "));
+    }
+
+    /// Tests that RFC 5424 entries can be written to a log file.
+    #[tokio::test]
+    async fn test_log_batch_rfc5424() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("syslog.log");
+        let entries = [
+            sample_log(LogFormat::RFC5424),
+            sample_log(LogFormat::RFC5424),
+        ];
+        Log::log_batch_to_path(&path, &entries).await.unwrap();
+
+        let content = fs::read_to_string(&path).await.unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert!(content
+            .lines()
+            .all(|line| LogFormat::RFC5424.validate(line)));
+    }
+
+    /// Tests the CSV output of a log entry.
+    #[test]
+    fn test_log_display_csv() {
+        let log =
+            sample_log(LogFormat::CSV).with_field("user_id", "42");
+        assert_eq!(
+            log.to_string(),
+            r#""session-1","2024-01-01T00:00:00Z","INFO","api","request handled""#
+        );
+        assert!(LogFormat::CSV.validate(&log.to_string()));
+    }
+
+    /// Tests RFC 4180 escaping of commas, quotes and newlines.
+    #[test]
+    fn test_log_display_csv_escaping() {
+        for (description, expected) in [
+            ("a, b, c", r#""a, b, c""#),
+            (r#"say "hi""#, r#""say ""hi""""#),
+            ("line one\nline two", "\"line one\nline two\""),
+            (r#"",""#, r#"""",""""#),
+        ] {
+            let log = Log {
+                description: description.to_string(),
+                ..sample_log(LogFormat::CSV)
+            };
+            let output = log.to_string();
+            assert!(
+                output.ends_with(&format!(",{}", expected)),
+                "{}",
+                output
+            );
+            assert!(LogFormat::CSV.validate(&output), "{}", output);
+        }
+    }
+
+    /// Tests that CSV entries can be written to a log file.
+    #[tokio::test]
+    async fn test_log_batch_csv() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("export.csv");
+        fs::write(&path, format!("{}\n", LogFormat::CSV.header()))
+            .await
+            .unwrap();
+        let entries =
+            [sample_log(LogFormat::CSV), sample_log(LogFormat::CSV)];
+        Log::log_batch_to_path(&path, &entries).await.unwrap();
+
+        let content = fs::read_to_string(&path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], LogFormat::CSV.header());
+        assert!(lines.iter().all(|line| LogFormat::CSV.validate(line)));
+    }
+
+    /// Tests that OpenTelemetry output has the OTLP log record keys with
+    /// the expected types.
+    #[test]
+    fn test_log_display_opentelemetry() {
+        let log = sample_log(LogFormat::OpenTelemetry)
+            .with_field("user_id", "42");
+        let record: serde_json::Value =
+            serde_json::from_str(&log.to_string()).unwrap();
+
+        assert_eq!(record["timeUnixNano"], "1704067200000000000");
+        assert_eq!(record["severityNumber"], 6);
+        assert!(record["severityNumber"].is_u64());
+        assert_eq!(record["severityText"], "INFO");
+        assert_eq!(record["body"]["stringValue"], "request handled");
+        let attributes = record["attributes"].as_array().unwrap();
+        for (key, value) in [
+            ("session_id", "session-1"),
+            ("component", "api"),
+            ("user_id", "42"),
+        ] {
+            assert!(attributes.contains(&serde_json::json!({
+                "key": key,
+                "value": { "stringValue": value },
+            })));
+        }
+        assert!(record.get("traceId").is_none());
+        assert!(LogFormat::OpenTelemetry.validate(&log.to_string()));
+    }
+
+    /// Tests that trace and span IDs become the record's `traceId` and
+    /// `spanId` rather than attributes.
+    #[test]
+    fn test_log_display_opentelemetry_trace_context() {
+        let log = sample_log(LogFormat::OpenTelemetry).with_fields(&[
+            ("trace_id", "4bf92f3577b34da6a3ce929d0e0e4736"),
+            ("span_id", "00f067aa0ba902b7"),
+        ]);
+        let record: serde_json::Value =
+            serde_json::from_str(&log.to_string()).unwrap();
+
+        assert_eq!(
+            record["traceId"],
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert_eq!(record["spanId"], "00f067aa0ba902b7");
+        assert_eq!(record["attributes"].as_array().unwrap().len(), 2);
+    }
+
+    /// Tests that an unparseable timestamp gives a zero `timeUnixNano`
+    /// and that the severity follows the log level.
+    #[test]
+    fn test_log_display_opentelemetry_edge_cases() {
+        let log = Log {
+            time: "not a timestamp".to_string(),
+            level: LogLevel::ERROR,
+            description: "say \"hi\"\n".to_string(),
+            ..sample_log(LogFormat::OpenTelemetry)
+        };
+        let output = log.to_string();
+        assert!(!output.contains('\n'));
+        let record: serde_json::Value =
+            serde_json::from_str(&output).unwrap();
+
+        assert_eq!(record["timeUnixNano"], "0");
+        assert_eq!(record["severityNumber"], 3);
+        assert_eq!(record["severityText"], "ERROR");
+        assert_eq!(record["body"]["stringValue"], "say \"hi\"\n");
+    }
+
+    /// Tests that OpenTelemetry entries can be written to a log file, one
+    /// record per line.
+    #[tokio::test]
+    async fn test_log_batch_opentelemetry() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("otel.log");
+        let entries = [
+            sample_log(LogFormat::OpenTelemetry),
+            sample_log(LogFormat::OpenTelemetry),
+        ];
+        Log::log_batch_to_path(&path, &entries).await.unwrap();
+
+        let content = fs::read_to_string(&path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines
+            .iter()
+            .all(|line| LogFormat::OpenTelemetry.validate(line)));
+    }
+
+    /// Tests that Fluentd output is a `[tag, time, record]` array with the
+    /// component as tag.
+    #[test]
+    fn test_log_display_fluentd() {
+        let log =
+            sample_log(LogFormat::Fluentd).with_field("pid", "42");
+        let output = log.to_string();
+        assert!(LogFormat::Fluentd.validate(&output));
+        let entry: serde_json::Value =
+            serde_json::from_str(&output).unwrap();
+
+        assert_eq!(entry[0], "api");
+        assert!(entry[1].is_number());
+        assert_eq!(entry[1], 1_704_067_200);
+        let record = entry[2].as_object().unwrap();
+        assert_eq!(record["level"], "INFO");
+        assert_eq!(record["session_id"], "session-1");
+        assert_eq!(record["message"], "request handled");
+        assert_eq!(record["pid"], 42);
+    }
+
+    /// Tests that an unparseable timestamp gives a zero Fluentd time.
+    #[test]
+    fn test_log_display_fluentd_invalid_time() {
+        let log = Log {
+            time: "not a timestamp".to_string(),
+            ..sample_log(LogFormat::Fluentd)
+        };
+        let entry: serde_json::Value =
+            serde_json::from_str(&log.to_string()).unwrap();
+        assert_eq!(entry[1], 0);
+    }
+}
@@ -6,7 +6,7 @@
 
 #[cfg(test)]
 mod tests {
-    use rlg::log_format::LogFormat;
+    use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
 
     #[test]
     fn test_log_format_display() {
@@ -23,6 +23,8 @@ fn test_log_format_display() {
         assert_eq!(format!("{}", LogFormat::Logstash), "Logstash");
         assert_eq!(format!("{}", LogFormat::Log4jXML), "Log4j XML");
         assert_eq!(format!("{}", LogFormat::NDJSON), "NDJSON");
+        assert_eq!(format!("{}", LogFormat::RFC5424), "RFC5424");
+        assert_eq!(format!("{}", LogFormat::CSV), "CSV");
     }
 
     #[test]
@@ -55,6 +57,11 @@ fn test_log_format_from_str() {
             "NDJSON".parse::<LogFormat>().unwrap(),
             LogFormat::NDJSON
         );
+        assert_eq!(
+            "RFC5424".parse::<LogFormat>().unwrap(),
+            LogFormat::RFC5424
+        );
+        assert_eq!("CSV".parse::<LogFormat>().unwrap(), LogFormat::CSV);
         assert!("Invalid".parse::<LogFormat>().is_err());
     }
 
@@ -173,4 +180,224 @@ fn test_log_format_specific_validations() {
         // #[should_panic(expected = "NDJSON validation not implemented")]
         // assert!(LogFormat::NDJSON.validate("{\"key1\":\"value1\"}\n{\"key2\":\"value2\"}"));
     }
+
+    #[test]
+    fn test_log_format_rfc5424_round_trip() {
+        let format = LogFormat::RFC5424;
+        assert_eq!(
+            format.to_string().parse::<LogFormat>().unwrap(),
+            format
+        );
+        assert_eq!("rfc5424".parse::<LogFormat>().unwrap(), format);
+    }
+
+    #[test]
+    fn test_log_format_rfc5424_validate() {
+        let valid = [
+            "<14>1 2024-01-01T00:00:00Z host app 1234 - - message",
+            "<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut=\"3\" eventSource=\"Application\" eventID=\"1011\"] An application event log entry",
+            "<0>1 - - - - - -",
+            "<191>1 2024-01-01T00:00:00.123456+02:00 host app 1 - [a@1 k=\"esc\\\"aped\\]\"][b@2] msg",
+        ];
+        for entry in valid {
+            assert!(LogFormat::RFC5424.validate(entry), "{}", entry);
+        }
+
+        let invalid = [
+            "",
+            "<14> 2024-01-01T00:00:00Z host app 1234 - - message",
+            "<192>1 2024-01-01T00:00:00Z host app 1234 - - message",
+            "<14>1 2024-01-01 00:00:00 host app 1234 - - message",
+            "<14>1 2024-01-01T00:00:00.1234567Z host app 1234 - - msg",
+            "<14>1 2024-01-01T00:00:00Z host app 1234 - [bad sd msg",
+            "<14>1 2024-01-01T00:00:00Z host app 1234 -",
+        ];
+        for entry in invalid {
+            assert!(!LogFormat::RFC5424.validate(entry), "{}", entry);
+        }
+    }
+
+    #[test]
+    fn test_log_format_csv_header() {
+        assert_eq!(
+            LogFormat::CSV.header(),
+            "session_id,time,level,component,description"
+        );
+        assert!(LogFormat::CSV.validate(LogFormat::CSV.header()));
+        assert_eq!(LogFormat::CLF.header(), "");
+    }
+
+    #[test]
+    fn test_log_format_csv_validate() {
+        let valid = [
+            r#""1","2024-01-01T00:00:00Z","INFO","api","ok""#,
+            r#""1","t","INFO","api","a, b and ""c""""#,
+            "\"1\",\"t\",\"INFO\",\"api\",\"line one\nline two\"",
+            "1,t,INFO,api,plain\r\n",
+            "1,t,INFO,,",
+        ];
+        for entry in valid {
+            assert!(LogFormat::CSV.validate(entry), "{:?}", entry);
+        }
+
+        let invalid = [
+            "",
+            r#""1","t","INFO","api""#,
+            r#""1","t","INFO","api","ok","extra""#,
+            r#""1","t","INFO","api","unterminated"#,
+            r#""1","t","INFO","api","bad"quote""#,
+            r#"1,t,INFO,api,stray"quote"#,
+        ];
+        for entry in invalid {
+            assert!(!LogFormat::CSV.validate(entry), "{:?}", entry);
+        }
+    }
+
+    #[test]
+    fn test_log_format_csv_format_log() {
+        let entry =
+            "\"1\",\"t\",\"INFO\",\"api\",\"line one\nline two\"";
+        assert_eq!(
+            LogFormat::CSV.format_log(entry).unwrap(),
+            "\"1\",\"t\",\"INFO\",\"api\",\"line one line two\""
+        );
+    }
+
+    #[test]
+    fn test_log_format_opentelemetry_round_trip() {
+        let format = LogFormat::OpenTelemetry;
+        assert_eq!(format.to_string(), "OpenTelemetry");
+        assert_eq!(
+            format.to_string().parse::<LogFormat>().unwrap(),
+            format
+        );
+        assert_eq!("otel".parse::<LogFormat>().unwrap(), format);
+    }
+
+    #[test]
+    fn test_log_format_opentelemetry_validate() {
+        let valid = r#"{"timeUnixNano":"0","severityNumber":6,"severityText":"INFO","body":{"stringValue":"ok"},"attributes":[]}"#;
+        assert!(LogFormat::OpenTelemetry.validate(valid));
+
+        let invalid = [
+            "",
+            "not json",
+            r#"{"severityNumber":6,"severityText":"INFO","body":{"stringValue":"ok"},"attributes":[]}"#,
+            r#"{"timeUnixNano":"0","severityNumber":6,"severityText":"INFO","attributes":[]}"#,
+        ];
+        for entry in invalid {
+            assert!(
+                !LogFormat::OpenTelemetry.validate(entry),
+                "{}",
+                entry
+            );
+        }
+    }
+
+    #[test]
+    fn test_log_format_fluentd_round_trip() {
+        let format = LogFormat::Fluentd;
+        assert_eq!(format.to_string(), "Fluentd");
+        assert_eq!(
+            format.to_string().parse::<LogFormat>().unwrap(),
+            format
+        );
+    }
+
+    #[test]
+    fn test_log_format_fluentd_validate() {
+        let valid = r#"["api",1704067200,{"level":"INFO","session_id":"1","message":"ok"}]"#;
+        assert!(LogFormat::Fluentd.validate(valid));
+
+        let invalid = [
+            "",
+            "not json",
+            r#"{"tag":"api"}"#,
+            r#"["api",1704067200]"#,
+            r#"["api","1704067200",{}]"#,
+            r#"[1,1704067200,{}]"#,
+            r#"["api",1704067200,[]]"#,
+            r#"["api",1704067200,{},{}]"#,
+        ];
+        for entry in invalid {
+            assert!(!LogFormat::Fluentd.validate(entry), "{}", entry);
+        }
+    }
+
+    #[test]
+    fn test_log_format_validate_strict() {
+        let error = |format: LogFormat, input: &str| {
+            format.validate_strict(input).unwrap_err().to_string()
+        };
+
+        let message = error(
+            LogFormat::CEF,
+            "CEF:0|Security|threatmanager|1.0|100|worm stopped|10 src=10.0.0.1",
+        );
+        assert!(message.contains("'|'"), "{}", message);
+        assert!(message.contains("severity"), "{}", message);
+        assert!(
+            error(LogFormat::CEF, "Invalid CEF log").contains("'CEF:'")
+        );
+
+        let message = error(LogFormat::JSON, r#"{"level":"INFO",}"#);
+        assert!(message.contains("trailing comma"), "{}", message);
+
+        let message = error(
+            LogFormat::CLF,
+            "127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] \"GET / HTTP/1.0\" 2000 2326",
+        );
+        assert!(message.contains("'status'"), "{}", message);
+        let message = error(
+            LogFormat::CLF,
+            "127.0.0.1 - - 10/Oct/2000:13:55:36 \"GET / HTTP/1.0\" 200 2326",
+        );
+        assert!(message.contains("'time'"), "{}", message);
+
+        assert!(error(LogFormat::CSV, "1,2,3").contains("found 3"));
+
+        // Valid entries pass, exactly as with `validate`.
+        assert!(LogFormat::CLF
+            .validate_strict("127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] \"GET /apache_pb.gif HTTP/1.0\" 200 2326")
+            .is_ok());
+        assert!(LogFormat::CEF
+            .validate_strict("CEF:0|security|threat|1.0|100|Something happened|5|msg=hello")
+            .is_ok());
+        assert!(LogFormat::JSON
+            .validate_strict(r#"{"key":"value"}"#)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_log_format_custom_display() {
+        let format = LogFormat::new_custom(|log: &Log| {
+            format!(
+                "{} [{}] {}",
+                log.level, log.component, log.description
+            )
+        });
+        let log = Log::new(
+            "1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::ERROR,
+            "db",
+            "connection lost",
+            &format,
+        );
+        assert_eq!(log.to_string(), "ERROR [db] connection lost");
+        assert_eq!(format.to_string(), "Custom");
+    }
+
+    #[test]
+    fn test_log_format_custom_equality() {
+        let format =
+            LogFormat::new_custom(|log: &Log| log.description.clone());
+        let other =
+            LogFormat::new_custom(|log: &Log| log.description.clone());
+        assert_eq!(format, format.clone());
+        assert_ne!(format, other);
+        assert_ne!(format, LogFormat::CLF);
+        assert!(LogFormat::Fluentd < format);
+        assert!(serde_json::to_string(&format).is_err());
+    }
 }
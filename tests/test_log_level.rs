@@ -220,6 +220,28 @@ fn test_log_level_from_str_case_insensitivity() {
         assert_eq!(LogLevel::from_str("INFO").unwrap(), LogLevel::INFO);
     }
 
+    /// Tests that `WARNING` is accepted as an alias of `WARN`.
+    #[test]
+    fn test_log_level_warning_alias() {
+        assert_eq!(
+            "WARNING".parse::<LogLevel>().unwrap(),
+            LogLevel::WARN
+        );
+        assert_eq!(
+            "warning".parse::<LogLevel>().unwrap(),
+            LogLevel::WARN
+        );
+        assert_eq!(
+            TryInto::<LogLevel>::try_into(String::from("Warning"))
+                .unwrap(),
+            LogLevel::WARN
+        );
+        assert_eq!(
+            "WARNING".parse::<LogLevel>().unwrap().to_string(),
+            "WARN"
+        );
+    }
+
     /// Tests the formatting of `ParseLogLevelError`.
     #[test]
     fn test_parse_log_level_error_formatting() {
@@ -260,7 +282,7 @@ fn test_log_level_to_numeric() {
     fn test_log_level_from_numeric() {
         assert_eq!(LogLevel::from_numeric(0), Some(LogLevel::ALL));
         assert_eq!(LogLevel::from_numeric(8), Some(LogLevel::ERROR));
-        assert_eq!(LogLevel::from_numeric(11), None);
+        assert_eq!(LogLevel::from_numeric(13), None);
     }
 
     /// Tests the default value of `LogLevel`.
@@ -403,10 +425,12 @@ fn test_log_level_discriminants() {
         let error = LogLevel::ERROR as u8;
         let fatal = LogLevel::FATAL as u8;
         let critical = LogLevel::CRITICAL as u8;
+        let security = LogLevel::SECURITY as u8;
+        let audit = LogLevel::AUDIT as u8;
 
         let discriminants = [
             all, none, disabled, debug, trace, verbose, info, warn,
-            error, fatal, critical,
+            error, fatal, critical, security, audit,
         ];
         // Ensure all discriminants are unique
         let unique_discriminants: HashSet<_> =
@@ -433,9 +457,287 @@ fn test_log_level_from_numeric_exhaustive() {
             Some(LogLevel::CRITICAL)
         );
 
+        assert_eq!(
+            LogLevel::from_numeric(11),
+            Some(LogLevel::SECURITY)
+        );
+        assert_eq!(LogLevel::from_numeric(12), Some(LogLevel::AUDIT));
+
         // Invalid conversions
-        assert_eq!(LogLevel::from_numeric(11), None);
+        assert_eq!(LogLevel::from_numeric(13), None);
         assert_eq!(LogLevel::from_numeric(255), None); // Test with a higher out-of-bounds value
         assert_eq!(LogLevel::from_numeric(u8::MAX), None);
     }
+
+    /// Tests that `iter()` yields levels in strictly increasing numeric order.
+    #[test]
+    fn test_log_level_iter_order() {
+        let numerics: Vec<u8> =
+            LogLevel::iter().map(|l| l.to_numeric()).collect();
+        assert!(numerics.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(numerics.first(), Some(&0));
+    }
+
+    /// Tests that `count()` equals the number of `LogLevel` variants.
+    #[test]
+    fn test_log_level_count() {
+        // This match fails to compile when a variant is added, keeping the
+        // list below in sync with the enum.
+        fn is_listed(level: LogLevel) -> bool {
+            match level {
+                LogLevel::ALL
+                | LogLevel::NONE
+                | LogLevel::DISABLED
+                | LogLevel::DEBUG
+                | LogLevel::TRACE
+                | LogLevel::VERBOSE
+                | LogLevel::INFO
+                | LogLevel::WARN
+                | LogLevel::ERROR
+                | LogLevel::FATAL
+                | LogLevel::CRITICAL
+                | LogLevel::SECURITY
+                | LogLevel::AUDIT => true,
+            }
+        }
+        let variants = [
+            LogLevel::ALL,
+            LogLevel::NONE,
+            LogLevel::DISABLED,
+            LogLevel::DEBUG,
+            LogLevel::TRACE,
+            LogLevel::VERBOSE,
+            LogLevel::INFO,
+            LogLevel::WARN,
+            LogLevel::ERROR,
+            LogLevel::FATAL,
+            LogLevel::CRITICAL,
+            LogLevel::SECURITY,
+            LogLevel::AUDIT,
+        ];
+
+        assert_eq!(LogLevel::count(), variants.len());
+        let levels: HashSet<LogLevel> = LogLevel::iter().collect();
+        for variant in variants {
+            assert!(is_listed(variant));
+            assert!(levels.contains(&variant));
+        }
+    }
+
+    /// Tests the numeric values of the `SECURITY` and `AUDIT` levels.
+    #[test]
+    fn test_log_level_security_audit_numeric() {
+        assert_eq!(LogLevel::SECURITY.to_numeric(), 11);
+        assert_eq!(LogLevel::AUDIT.to_numeric(), 12);
+        assert_eq!(LogLevel::iter().last(), Some(LogLevel::AUDIT));
+    }
+
+    /// Tests parsing and displaying the `SECURITY` and `AUDIT` levels.
+    #[test]
+    fn test_log_level_security_audit_strings() {
+        for (input, level) in [
+            ("SECURITY", LogLevel::SECURITY),
+            ("security", LogLevel::SECURITY),
+            ("AUDIT", LogLevel::AUDIT),
+            ("Audit", LogLevel::AUDIT),
+        ] {
+            assert_eq!(input.parse::<LogLevel>().unwrap(), level);
+        }
+        assert_eq!(LogLevel::SECURITY.to_string(), "SECURITY");
+        assert_eq!(LogLevel::AUDIT.to_string(), "AUDIT");
+        assert_eq!(
+            serde_json::to_string(&LogLevel::AUDIT).unwrap(),
+            "\"AUDIT\""
+        );
+    }
+
+    /// Tests that `SECURITY` and `AUDIT` sort and filter above `CRITICAL`.
+    #[test]
+    fn test_log_level_security_audit_ordering() {
+        let mut levels = vec![
+            LogLevel::AUDIT,
+            LogLevel::INFO,
+            LogLevel::SECURITY,
+            LogLevel::CRITICAL,
+        ];
+        levels.sort();
+        assert_eq!(
+            levels,
+            vec![
+                LogLevel::INFO,
+                LogLevel::CRITICAL,
+                LogLevel::SECURITY,
+                LogLevel::AUDIT
+            ]
+        );
+
+        assert!(LogLevel::SECURITY.includes(LogLevel::CRITICAL));
+        assert!(LogLevel::AUDIT.includes(LogLevel::SECURITY));
+        assert!(!LogLevel::CRITICAL.includes(LogLevel::SECURITY));
+        assert!(!LogLevel::SECURITY.includes(LogLevel::AUDIT));
+    }
+
+    /// Tests the mapping of every level to a syslog severity.
+    #[test]
+    fn test_log_level_to_syslog_priority() {
+        let expected = [
+            (LogLevel::ALL, 7),
+            (LogLevel::NONE, 7),
+            (LogLevel::DISABLED, 7),
+            (LogLevel::DEBUG, 7),
+            (LogLevel::TRACE, 7),
+            (LogLevel::VERBOSE, 7),
+            (LogLevel::INFO, 6),
+            (LogLevel::WARN, 4),
+            (LogLevel::ERROR, 3),
+            (LogLevel::FATAL, 0),
+            (LogLevel::CRITICAL, 2),
+            (LogLevel::SECURITY, 1),
+            (LogLevel::AUDIT, 1),
+        ];
+        assert_eq!(expected.len(), LogLevel::count());
+        for (level, priority) in expected {
+            assert_eq!(
+                level.to_syslog_priority(),
+                priority,
+                "{}",
+                level
+            );
+        }
+    }
+
+    /// Tests converting syslog severities back to levels.
+    #[test]
+    fn test_log_level_from_syslog_priority() {
+        let expected = [
+            (0, LogLevel::FATAL),
+            (1, LogLevel::SECURITY),
+            (2, LogLevel::CRITICAL),
+            (3, LogLevel::ERROR),
+            (4, LogLevel::WARN),
+            (5, LogLevel::INFO),
+            (6, LogLevel::INFO),
+            (7, LogLevel::DEBUG),
+        ];
+        for (priority, level) in expected {
+            assert_eq!(
+                LogLevel::from_syslog_priority(priority),
+                Some(level)
+            );
+        }
+        for priority in [8, 100, u8::MAX] {
+            assert_eq!(LogLevel::from_syslog_priority(priority), None);
+        }
+    }
+
+    /// Tests that converting to a syslog severity and back yields a level
+    /// with the same severity.
+    #[test]
+    fn test_log_level_syslog_priority_round_trip() {
+        for level in LogLevel::iter() {
+            let priority = level.to_syslog_priority();
+            let round_trip =
+                LogLevel::from_syslog_priority(priority).unwrap();
+            assert_eq!(round_trip.to_syslog_priority(), priority);
+            if priority < 7 && level != LogLevel::AUDIT {
+                assert_eq!(round_trip, level);
+            }
+        }
+    }
+
+    /// Tests that `between` checks an inclusive numeric range.
+    #[test]
+    fn test_log_level_between() {
+        assert!(
+            LogLevel::INFO.between(LogLevel::DEBUG, LogLevel::ERROR)
+        );
+        assert!(
+            LogLevel::DEBUG.between(LogLevel::DEBUG, LogLevel::ERROR)
+        );
+        assert!(
+            LogLevel::ERROR.between(LogLevel::DEBUG, LogLevel::ERROR)
+        );
+        assert!(
+            !LogLevel::FATAL.between(LogLevel::DEBUG, LogLevel::ERROR)
+        );
+        assert!(
+            !LogLevel::NONE.between(LogLevel::DEBUG, LogLevel::ERROR)
+        );
+        assert!(
+            !LogLevel::INFO.between(LogLevel::ERROR, LogLevel::DEBUG)
+        );
+    }
+
+    /// Tests that `clamp` returns the boundary for levels out of range.
+    #[test]
+    fn test_log_level_clamp() {
+        let (min, max) = (LogLevel::DEBUG, LogLevel::ERROR);
+        assert_eq!(LogLevel::FATAL.clamp(min, max), LogLevel::ERROR);
+        assert_eq!(LogLevel::AUDIT.clamp(min, max), LogLevel::ERROR);
+        assert_eq!(LogLevel::ALL.clamp(min, max), LogLevel::DEBUG);
+        assert_eq!(LogLevel::INFO.clamp(min, max), LogLevel::INFO);
+        assert_eq!(LogLevel::DEBUG.clamp(min, max), LogLevel::DEBUG);
+        for level in LogLevel::iter() {
+            assert!(level.clamp(min, max).between(min, max));
+        }
+    }
+
+    /// Tests that `display_ansi` wraps the level name in color codes.
+    #[test]
+    fn test_log_level_display_ansi() {
+        for level in LogLevel::iter() {
+            let colored = level.display_ansi();
+            assert!(colored.starts_with("\x1b["), "{:?}", colored);
+            assert!(colored.contains(&level.to_string()));
+            assert_eq!(
+                strip_ansi_escapes::strip_str(colored),
+                level.to_string()
+            );
+        }
+        assert_eq!(
+            LogLevel::FATAL.display_ansi(),
+            "\x1b[1;31mFATAL\x1b[0m"
+        );
+        assert_eq!(
+            LogLevel::WARN.display_ansi(),
+            "\x1b[33mWARN\x1b[0m"
+        );
+        assert_eq!(
+            LogLevel::INFO.display_ansi(),
+            "\x1b[32mINFO\x1b[0m"
+        );
+    }
+
+    /// Tests that `has_ansi_support` follows the color override.
+    #[test]
+    fn test_log_level_has_ansi_support() {
+        colored::control::set_override(false);
+        assert!(!LogLevel::has_ansi_support());
+        colored::control::set_override(true);
+        assert!(LogLevel::has_ansi_support());
+        colored::control::unset_override();
+    }
+
+    /// Tests that `base_severity` spans `0.0` to `1.0` in level order.
+    #[test]
+    fn test_log_level_base_severity() {
+        assert_eq!(LogLevel::ALL.base_severity(), 0.0);
+        assert_eq!(LogLevel::CRITICAL.base_severity(), 1.0);
+        assert_eq!(LogLevel::AUDIT.base_severity(), 1.0);
+
+        let mut levels: Vec<LogLevel> = LogLevel::iter().collect();
+        levels.sort_by_key(|level| level.to_numeric());
+        for pair in levels.windows(2) {
+            let (lower, higher) =
+                (pair[0].base_severity(), pair[1].base_severity());
+            if pair[1].to_numeric() <= LogLevel::CRITICAL.to_numeric() {
+                assert!(lower < higher, "{:?}", pair);
+            } else {
+                assert!(lower <= higher, "{:?}", pair);
+            }
+        }
+        for level in LogLevel::iter() {
+            assert!((0.0..=1.0).contains(&level.base_severity()));
+        }
+    }
 }
@@ -7,18 +7,26 @@
 #[cfg(test)]
 mod tests {
     use dtt::datetime::DateTime;
-    use rlg::{log_format::LogFormat, log_level::LogLevel};
-    #[allow(unused_imports)]
-    use rlg::{macro_debug_log, macro_error_log, macro_fatal_log};
+    use rlg::{log::Log, log_format::LogFormat, log_level::LogLevel};
     use rlg::{
-        macro_info_log, macro_log, macro_log_if,
-        macro_log_with_metadata, macro_print_log,
-        macro_set_log_format_clf, macro_trace_log, macro_warn_log,
+        macro_critical_log, macro_info_log, macro_log, macro_log_error,
+        macro_log_every_n, macro_log_here, macro_log_if,
+        macro_log_if_level, macro_log_once, macro_log_rate_limited,
+        macro_log_structured, macro_log_to_multiple,
+        macro_log_with_metadata, macro_log_with_span, macro_print_log,
+        macro_set_log_format_clf, macro_trace_log, macro_verbose_log,
+        macro_warn_log,
     };
+    #[allow(unused_imports)]
+    use rlg::{macro_debug_log, macro_error_log, macro_fatal_log};
 
     #[allow(unused_imports)]
     use std::io::{self, Write};
 
+    use parking_lot::RwLock;
+    use rlg::config::Config;
+    use std::sync::Arc;
+
     #[test]
     fn test_macro_log() {
         let log = macro_log!(
@@ -91,6 +99,148 @@ fn test_macro_fatal_log() {
         assert_eq!(log.description, "fatal message");
     }
 
+    #[test]
+    fn test_macro_critical_log() {
+        let log = macro_critical_log!(
+            "2022-01-01",
+            "app",
+            "critical message"
+        );
+        assert_eq!(log.level, LogLevel::CRITICAL);
+        assert_eq!(log.format, LogFormat::CLF);
+        assert_eq!(log.time, "2022-01-01");
+        assert_eq!(log.component, "app");
+        assert_eq!(log.description, "critical message");
+        #[cfg(not(feature = "uuid_session_ids"))]
+        assert!(log.session_id.parse::<u32>().is_ok());
+    }
+
+    #[test]
+    fn test_macro_verbose_log() {
+        let log =
+            macro_verbose_log!("2022-01-01", "app", "verbose message");
+        assert_eq!(log.level, LogLevel::VERBOSE);
+        assert_eq!(log.format, LogFormat::CLF);
+        assert_eq!(log.time, "2022-01-01");
+        assert_eq!(log.component, "app");
+        assert_eq!(log.description, "verbose message");
+        #[cfg(not(feature = "uuid_session_ids"))]
+        assert!(log.session_id.parse::<u32>().is_ok());
+    }
+
+    #[test]
+    fn test_macro_log_here() {
+        let log =
+            macro_log_here!(&LogLevel::DEBUG, "app", "cache miss");
+        let prefix = format!("{}:{} ", file!(), line!() - 1);
+        assert!(log.description.starts_with(&prefix));
+        assert!(log.description.ends_with("cache miss"));
+        assert_eq!(log.level, LogLevel::DEBUG);
+        assert_eq!(log.component, "app");
+        assert_eq!(log.format, LogFormat::CLF);
+        assert!(!log.time.is_empty());
+        #[cfg(not(feature = "uuid_session_ids"))]
+        assert!(log.session_id.parse::<u32>().is_ok());
+    }
+
+    #[cfg(feature = "uuid_session_ids")]
+    #[test]
+    fn test_macro_uuid_session_ids() {
+        let first = macro_info_log!("2022-01-01", "app", "message");
+        let second = macro_warn_log!("2022-01-01", "app", "message");
+        for log in [&first, &second] {
+            let uuid = uuid::Uuid::parse_str(&log.session_id).unwrap();
+            assert_eq!(uuid.get_version_num(), 4);
+        }
+        assert_ne!(first.session_id, second.session_id);
+    }
+
+    #[test]
+    fn test_macro_log_here_clf_round_trip() {
+        let log = macro_log_here!(&LogLevel::INFO, "app", "ready");
+        let parsed = Log::from_clf_str(&log.to_string()).unwrap();
+        assert_eq!(parsed.description, log.description);
+        assert!(parsed.description.starts_with(file!()));
+    }
+
+    /// Tests that `macro_log_error!` logs the error with the global
+    /// configuration, which is set here only, as it is process-wide.
+    #[tokio::test]
+    async fn test_macro_log_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("errors.log");
+        Config::init_global(Config {
+            log_file_path: path.clone(),
+            ..Default::default()
+        });
+
+        let err = io::Error::new(io::ErrorKind::Other, "disk full");
+        assert!(macro_log_error!("storage", err).is_ok());
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        let log = Log::from_clf_str(content.trim_end()).unwrap();
+        assert_eq!(log.level, LogLevel::ERROR);
+        assert_eq!(log.component, "storage");
+        assert!(log.description.contains("disk full"), "{}", content);
+    }
+
+    #[test]
+    fn test_macro_log_rate_limited() {
+        let mut sink = Vec::new();
+        for _ in 0..1000 {
+            let log =
+                macro_warn_log!("2022-01-01", "rate-test", "flood");
+            if let Some(log) = macro_log_rate_limited!(1, log) {
+                sink.push(log);
+            }
+        }
+        assert!(
+            (1..=2).contains(&sink.len()),
+            "{} entries got through",
+            sink.len()
+        );
+        assert_eq!(sink[0].description, "flood");
+        if let Some(second) = sink.get(1) {
+            assert!(second
+                .description
+                .starts_with("flood (suppressed "));
+        }
+    }
+
+    #[test]
+    fn test_macro_log_once() {
+        for _ in 0..3 {
+            let log = macro_info_log!("2022-01-01", "once-macro", "hi");
+            macro_log_once!(log);
+        }
+        let log = macro_info_log!("2022-01-02", "once-macro", "hi");
+        assert!(rlg::rate_limit::check_once(log).is_none());
+    }
+
+    #[test]
+    fn test_macro_log_every_n() {
+        let mut sink = Vec::new();
+        for i in 0..100 {
+            let log = macro_info_log!(
+                "2022-01-01",
+                "every-n",
+                &format!("event {}", i)
+            );
+            if let Some(log) = macro_log_every_n!(10, log) {
+                sink.push(log);
+            }
+        }
+        assert_eq!(sink.len(), 10);
+        assert_eq!(sink[0].description, "event 0");
+        assert_eq!(sink[1].description, "event 10");
+
+        // Each call site has its own counter.
+        let log = macro_info_log!("2022-01-01", "every-n", "other");
+        assert!(macro_log_every_n!(10, log).is_some());
+
+        let log = macro_info_log!("2022-01-01", "every-n", "never");
+        assert!(macro_log_every_n!(0, log).is_none());
+    }
+
     #[test]
     fn test_macro_set_log_format_clf() {
         let mut log = macro_info_log!("2022-01-01", "app", "message");
@@ -112,6 +262,55 @@ fn test_macro_log_if_false() {
         assert!(printed.is_empty());
     }
 
+    #[test]
+    fn test_macro_log_with_span() {
+        let span_id = "00f067aa0ba902b7";
+        let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let log = macro_info_log!("2022-01-01", "app", "traced");
+        let untraced = log.clone();
+        let log = macro_log_with_span!(span_id, trace_id, log);
+        assert_eq!(log.fields["span_id"], span_id);
+        assert_eq!(log.fields["trace_id"], trace_id);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&log.render(LogFormat::JSON).unwrap())
+                .unwrap();
+        assert_eq!(json["trace_id"], trace_id);
+        assert_eq!(json["span_id"], span_id);
+
+        let clf = log.render(LogFormat::CLF).unwrap();
+        assert!(clf.contains(&format!(" trace_id={}", trace_id)));
+        assert!(clf.contains(&format!(" span_id={}", span_id)));
+
+        for format in [LogFormat::JSON, LogFormat::CLF] {
+            let output = untraced.render(format).unwrap();
+            assert!(!output.contains("trace_id"));
+            assert!(!output.contains("span_id"));
+        }
+    }
+
+    #[test]
+    fn test_macro_log_structured() {
+        let log = macro_log_structured!(
+            LogLevel::INFO,
+            "db",
+            "query complete",
+            rows = 42u64,
+            duration_ms = 13u64
+        );
+        assert_eq!(log.level, LogLevel::INFO);
+        assert_eq!(log.component, "db");
+        assert_eq!(log.description, "query complete");
+
+        let json = log.render(LogFormat::JSON).unwrap();
+        assert!(json.contains("\"rows\":\"42\""), "{}", json);
+        assert!(json.contains("\"duration_ms\":\"13\""), "{}", json);
+
+        let log =
+            macro_log_structured!(LogLevel::WARN, "db", "no fields",);
+        assert!(log.fields.is_empty());
+    }
+
     #[test]
     fn test_macro_log_with_metadata() {
         let log_message = macro_log_with_metadata!(
@@ -312,7 +511,7 @@ fn test_macro_log_with_newlines_in_message() {
     fn test_macro_set_log_format_clf_idempotent() {
         let mut log = macro_info_log!("2022-01-01", "app", "message");
         macro_set_log_format_clf!(log);
-        let original_format = log.format;
+        let original_format = log.format.clone();
         macro_set_log_format_clf!(log);
         assert_eq!(log.format, original_format, "Calling macro_set_log_format_clf twice should not change the format");
     }
@@ -384,4 +583,58 @@ fn test_macro_log_with_dtt_custom_format() {
         let log = macro_info_log!(&formatted_now, "app", "message");
         assert_eq!(log.time, formatted_now);
     }
+
+    #[tokio::test]
+    async fn test_macro_log_if_level() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("levels.log");
+        let config = Arc::new(RwLock::new(Config {
+            log_file_path: path.clone(),
+            log_level: LogLevel::ERROR,
+            ..Default::default()
+        }));
+
+        let mut evaluated = false;
+        macro_log_if_level!(config, LogLevel::DEBUG, "app", {
+            evaluated = true;
+            "filtered"
+        })
+        .unwrap();
+        assert!(!evaluated, "description should not be evaluated");
+        assert!(!path.exists(), "no log file should be written");
+
+        config.write().log_level = LogLevel::ALL;
+        macro_log_if_level!(config, LogLevel::DEBUG, "app", "written")
+            .unwrap();
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(content.contains("Description=written"));
+        assert!(content.contains("Level=DEBUG"));
+        assert!(!content.contains("filtered"));
+    }
+
+    #[tokio::test]
+    async fn test_macro_log_to_multiple() {
+        use rlg::sink::{FileSink, LogSink, StdoutSink};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let first = temp_dir.path().join("first.log");
+        let second = temp_dir.path().join("second.log");
+        let sinks: Vec<Box<dyn LogSink>> = vec![
+            Box::new(FileSink::new(&first)),
+            Box::new(StdoutSink::default()),
+        ];
+        let log = macro_info_log!("2022-01-01", "app", "fanned out");
+        macro_log_to_multiple!(sinks, log.clone()).unwrap();
+        let content = tokio::fs::read_to_string(&first).await.unwrap();
+        assert_eq!(content.matches("fanned out").count(), 1);
+
+        let second_sink = FileSink::new(&second);
+        macro_log_to_multiple!([&*sinks[0], &second_sink], log)
+            .unwrap();
+        for path in [&first, &second] {
+            let content =
+                tokio::fs::read_to_string(path).await.unwrap();
+            assert!(content.contains("fanned out"), "{}", content);
+        }
+    }
 }
@@ -0,0 +1,117 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the panic hook of RustLogs (RLG).
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::{Mutex, RwLock};
+    use rlg::{config::Config, install_panic_hook, log::Log};
+    use std::{
+        fs, panic,
+        path::Path,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+    use tempfile::tempdir;
+
+    /// Serializes tests, as the panic hook is process-wide.
+    static HOOK_LOCK: Mutex<()> = parking_lot::const_mutex(());
+
+    /// Whether the hook installed before `install_panic_hook` ran.
+    static PREVIOUS_HOOK_CALLED: AtomicBool = AtomicBool::new(false);
+
+    /// Installs a hook recording its call, then the RLG hook on top of it,
+    /// returning the configuration of the RLG hook.
+    fn install(path: &Path) -> Arc<RwLock<Config>> {
+        PREVIOUS_HOOK_CALLED.store(false, Ordering::SeqCst);
+        panic::set_hook(Box::new(|_| {
+            PREVIOUS_HOOK_CALLED.store(true, Ordering::SeqCst);
+        }));
+        let config = Config {
+            log_file_path: path.to_path_buf(),
+            ..Default::default()
+        };
+        let config = Arc::new(RwLock::new(config));
+        install_panic_hook(Arc::clone(&config));
+        config
+    }
+
+    /// Tests that a panic is logged at the `FATAL` level with its message
+    /// and location, and that the previous hook is still called.
+    #[test]
+    fn test_panic_hook_logs_panic() {
+        let _guard = HOOK_LOCK.lock();
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("panic.log");
+        install(&path);
+
+        let line = line!() + 1;
+        let result = panic::catch_unwind(|| panic!("disk on fire"));
+        let _ = panic::take_hook();
+        assert!(result.is_err());
+        assert!(PREVIOUS_HOOK_CALLED.load(Ordering::SeqCst));
+
+        let content = fs::read_to_string(&path).unwrap();
+        let log = Log::from_clf_str(content.trim_end()).unwrap();
+        assert_eq!(log.level.to_string(), "FATAL");
+        assert_eq!(log.component, "panic");
+        assert!(log.description.starts_with(&format!(
+            "panicked at {}:{}:",
+            file!(),
+            line
+        )));
+        assert!(log.description.ends_with(": disk on fire"));
+    }
+
+    /// Tests that panics with a formatted message inside a Tokio runtime
+    /// are logged before the panic propagates.
+    #[test]
+    fn test_panic_hook_inside_runtime() {
+        let _guard = HOOK_LOCK.lock();
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("panic.log");
+        install(&path);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let result = panic::catch_unwind(|| {
+            runtime.block_on(async {
+                panic!("request {} failed", 42);
+            })
+        });
+        let _ = panic::take_hook();
+        assert!(result.is_err());
+        assert!(PREVIOUS_HOOK_CALLED.load(Ordering::SeqCst));
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.contains("Level=FATAL"));
+        assert!(content.contains(": request 42 failed"));
+    }
+
+    /// Tests that a panic while the configuration is locked for writing
+    /// is not logged rather than deadlocking the hook.
+    #[test]
+    fn test_panic_hook_config_locked() {
+        let _guard = HOOK_LOCK.lock();
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("panic.log");
+        let config = install(&path);
+
+        let result =
+            panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                let _config = config.write();
+                panic!("while reconfiguring");
+            }));
+        let _ = panic::take_hook();
+        assert!(result.is_err());
+        assert!(PREVIOUS_HOOK_CALLED.load(Ordering::SeqCst));
+        assert!(!path.exists());
+    }
+}
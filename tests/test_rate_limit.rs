@@ -0,0 +1,90 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the `RateLimiter` type.
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use rlg::{
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+        rate_limit::{check_once, RateLimiter},
+    };
+    use std::{thread, time::Duration};
+
+    /// Creates a log entry with the given component and description.
+    fn entry(component: &str, description: &str) -> Log {
+        common::sample_log(
+            LogLevel::WARN,
+            component,
+            description,
+            LogFormat::CLF,
+        )
+    }
+
+    /// Tests that entries beyond the limit are suppressed.
+    #[test]
+    fn test_rate_limiter_suppresses_duplicates() {
+        let limiter = RateLimiter::new();
+        let allowed = (0..10)
+            .filter_map(|_| limiter.check(3, entry("cache", "miss")))
+            .count();
+        assert_eq!(allowed, 3);
+    }
+
+    /// Tests that distinct components and descriptions are limited apart.
+    #[test]
+    fn test_rate_limiter_keys() {
+        let limiter = RateLimiter::new();
+        assert!(limiter.check(1, entry("cache", "miss")).is_some());
+        assert!(limiter.check(1, entry("cache", "miss")).is_none());
+        assert!(limiter.check(1, entry("cache", "hit")).is_some());
+        assert!(limiter.check(1, entry("db", "miss")).is_some());
+    }
+
+    /// Tests that the suppressed count is reported once the window passes.
+    #[test]
+    fn test_rate_limiter_reports_suppressed_count() {
+        let limiter = RateLimiter::new();
+        assert!(limiter.check(1, entry("cache", "miss")).is_some());
+        for _ in 0..4 {
+            assert!(limiter.check(1, entry("cache", "miss")).is_none());
+        }
+
+        thread::sleep(Duration::from_millis(1100));
+        let log = limiter.check(1, entry("cache", "miss")).unwrap();
+        assert_eq!(log.description, "miss (suppressed 4 times)");
+
+        thread::sleep(Duration::from_millis(1100));
+        let log = limiter.check(1, entry("cache", "miss")).unwrap();
+        assert_eq!(log.description, "miss");
+    }
+
+    /// Tests that a limit of zero suppresses every entry.
+    #[test]
+    fn test_rate_limiter_zero_limit() {
+        let limiter = RateLimiter::new();
+        assert!(limiter.check(0, entry("cache", "miss")).is_none());
+    }
+
+    /// Tests that `check_once` lets each entry through only once per
+    /// process.
+    #[test]
+    fn test_check_once() {
+        let log = entry("once-test", "first");
+        assert_eq!(check_once(log.clone()), Some(log.clone()));
+        assert_eq!(check_once(log.clone()), None);
+
+        let other_session = Log {
+            session_id: "session-2".to_string(),
+            ..log
+        };
+        assert_eq!(check_once(other_session), None);
+        assert!(check_once(entry("once-test", "second")).is_some());
+    }
+}
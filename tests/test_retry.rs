@@ -0,0 +1,85 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the retrying log functions of RustLogs (RLG).
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::sample_log;
+    use rlg::{
+        config::{Config, LoggingDestination},
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+        RlgError,
+    };
+    use std::{env, fs, time::Duration};
+    use tempfile::tempdir;
+    use tokio::{net::TcpListener, time::Instant};
+
+    /// Returns an entry logged by the default configuration.
+    fn entry(description: &str) -> Log {
+        sample_log(
+            LogLevel::ERROR,
+            "retry-test",
+            description,
+            LogFormat::CLF,
+        )
+    }
+
+    /// Tests that `log_with_retry` backs off between failed attempts and
+    /// gives up after `max_retries` retries.
+    ///
+    /// The default configuration writes to `RLG.log` in the working
+    /// directory, which is replaced with a directory to make writes fail.
+    /// Every case shares one test, as the working directory and the
+    /// global configuration are process-wide.
+    #[tokio::test]
+    async fn test_log_with_retry() {
+        tokio::time::pause();
+        let dir = tempdir().unwrap();
+        env::set_current_dir(dir.path()).unwrap();
+        fs::create_dir("RLG.log").unwrap();
+
+        // Attempts run at 0 ms and 10 ms; the file is writable from
+        // 15 ms, so the third attempt, at 30 ms, succeeds.
+        let unblock = tokio::spawn(async {
+            tokio::time::sleep(Duration::from_millis(15)).await;
+            fs::remove_dir("RLG.log").unwrap();
+        });
+        let start = Instant::now();
+        entry("recovered").log_with_retry(3, 10).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        unblock.await.unwrap();
+        let content = fs::read_to_string("RLG.log").unwrap();
+        assert_eq!(content.matches("recovered").count(), 1);
+
+        // Every attempt fails: two retries after 10 ms and 20 ms.
+        fs::remove_file("RLG.log").unwrap();
+        fs::create_dir("RLG.log").unwrap();
+        let start = Instant::now();
+        let result = entry("lost").log_with_retry(2, 10).await;
+        assert!(matches!(result, Err(RlgError::IoError(_))));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert!(start.elapsed() < Duration::from_millis(60));
+
+        // Retries after a failed echo do not write the log file again.
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = closed.local_addr().unwrap();
+        drop(closed);
+        let path = dir.path().join("echoed.log");
+        Config::init_global(Config {
+            log_file_path: path.clone(),
+            logging_destinations: vec![LoggingDestination::Tcp(addr)],
+            ..Default::default()
+        });
+        let result = entry("echoed").log_with_retry(2, 10).await;
+        assert!(matches!(result, Err(RlgError::NetworkError(_))));
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 1, "{}", content);
+        assert!(content.contains("Description=echoed "));
+    }
+}
@@ -0,0 +1,745 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the `LogSink` trait and the built-in sinks.
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use async_trait::async_trait;
+    use gag::BufferRedirect;
+    use rlg::{
+        config::{Config, LogRotation, LoggingDestination},
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+        sink::{
+            BinarySink, FileSink, LogSink, RollingFileSink, StderrSink,
+            StdoutSink, TcpSink, UdpSink, UdpSyslogSink,
+        },
+        RlgError, RlgResult,
+    };
+    use std::{
+        collections::HashMap,
+        io::Read,
+        num::NonZeroU64,
+        path::Path,
+        sync::{Arc, Mutex},
+    };
+    use tempfile::tempdir;
+    use tokio::{
+        fs,
+        io::{AsyncBufReadExt, BufReader},
+        net::{TcpListener, TcpStream, UdpSocket},
+        sync::oneshot,
+    };
+
+    /// Serializes tests that capture stderr, which can only be redirected
+    /// once at a time.
+    static STDERR_CAPTURE: tokio::sync::Mutex<()> =
+        tokio::sync::Mutex::const_new(());
+
+    /// Serializes tests that write to or capture stdout.
+    static STDOUT_CAPTURE: tokio::sync::Mutex<()> =
+        tokio::sync::Mutex::const_new(());
+
+    /// A sink that keeps every entry in memory.
+    #[derive(Debug, Default)]
+    struct MemorySink {
+        lines: Mutex<Vec<String>>,
+        flushes: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl LogSink for MemorySink {
+        async fn write(&self, log: &Log) -> RlgResult<()> {
+            self.lines.lock().unwrap().push(log.to_string());
+            Ok(())
+        }
+
+        async fn flush(&self) -> RlgResult<()> {
+            *self.flushes.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    /// A sink that always fails with the given message.
+    #[derive(Debug)]
+    struct FailingSink(&'static str);
+
+    #[async_trait]
+    impl LogSink for FailingSink {
+        async fn write(&self, _log: &Log) -> RlgResult<()> {
+            Err(RlgError::custom(self.0))
+        }
+
+        async fn flush(&self) -> RlgResult<()> {
+            Err(RlgError::custom(self.0))
+        }
+    }
+
+    /// Creates a log entry with fixed values.
+    fn sample_log(description: &str, format: LogFormat) -> Log {
+        common::sample_log(LogLevel::INFO, "api", description, format)
+    }
+
+    /// Tests that a custom sink receives entries without touching the filesystem.
+    #[tokio::test]
+    async fn test_log_to_memory_sink() {
+        let sink = MemorySink::default();
+        sample_log("first", LogFormat::CLF)
+            .log_to(&sink)
+            .await
+            .unwrap();
+        sample_log("second", LogFormat::JSON)
+            .log_to(&sink)
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+
+        let lines = sink.lines.lock().unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("Description=first"));
+        assert!(lines[1].contains("\"Description\":\"second\""));
+        assert_eq!(*sink.flushes.lock().unwrap(), 1);
+    }
+
+    /// Tests that sinks can be used as trait objects.
+    #[tokio::test]
+    async fn test_log_to_dyn_sinks() {
+        let memory = MemorySink::default();
+        let sinks: Vec<&dyn LogSink> = vec![&memory, &memory];
+        let log = sample_log("fan out", LogFormat::CLF);
+        for sink in sinks {
+            log.log_to(sink).await.unwrap();
+        }
+        assert_eq!(memory.lines.lock().unwrap().len(), 2);
+    }
+
+    /// Tests that `FileSink` appends formatted lines to its file.
+    #[tokio::test]
+    async fn test_file_sink_appends() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sink.log");
+        let sink = FileSink::new(&path);
+        assert_eq!(sink.path(), path.as_path());
+
+        sample_log("one", LogFormat::CLF)
+            .log_to(&sink)
+            .await
+            .unwrap();
+        sample_log("two", LogFormat::CLF)
+            .log_to(&sink)
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+
+        let contents = fs::read_to_string(&path).await.unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("Description=one"));
+        assert!(lines[0].ends_with("Format=CLF"));
+        assert!(lines[1].contains("Description=two"));
+    }
+
+    /// Tests that `FileSink` keeps the unsupported format marker of `log()`.
+    #[tokio::test]
+    async fn test_file_sink_unsupported_format() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sink.log");
+        let sink = FileSink::new(&path);
+
+        sample_log("xml", LogFormat::Log4jXML)
+            .log_to(&sink)
+            .await
+            .unwrap();
+
+        let contents = fs::read_to_string(&path).await.unwrap();
+        assert_eq!(contents, "Unsupported format\n");
+    }
+
+    /// Returns the names of the rotated files of `RLG.log` in `dir`.
+    fn rotated_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                entry.unwrap().file_name().into_string().unwrap()
+            })
+            .filter(|name| name.starts_with("RLG.log."))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Returns the line a file sink writes for `log`.
+    async fn file_line(log: &Log) -> String {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("probe.log");
+        log.log_to(&FileSink::new(&path)).await.unwrap();
+        fs::read_to_string(&path).await.unwrap()
+    }
+
+    /// Tests that `RollingFileSink` rotates its file each time it reaches
+    /// the size limit.
+    #[tokio::test]
+    async fn test_rolling_file_sink_size() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("RLG.log");
+        let sink = RollingFileSink::new(
+            path.clone(),
+            LogRotation::Size(NonZeroU64::new(1_000_000).unwrap()),
+            10,
+            false,
+        );
+        assert_eq!(sink.path(), path.as_path());
+
+        // Pad entries to 1,000 bytes so that 5 MB fill exactly 5 files.
+        let line_len =
+            file_line(&sample_log("", LogFormat::CLF)).await.len();
+        let log =
+            sample_log(&"x".repeat(1_000 - line_len), LogFormat::CLF);
+        assert_eq!(file_line(&log).await.len(), 1_000);
+        for _ in 0..5_000 {
+            log.log_to(&sink).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let rotated = rotated_files(dir.path());
+        assert_eq!(
+            rotated,
+            [
+                "RLG.log.1",
+                "RLG.log.2",
+                "RLG.log.3",
+                "RLG.log.4",
+                "RLG.log.5"
+            ]
+        );
+        for name in &rotated {
+            let len =
+                std::fs::metadata(dir.path().join(name)).unwrap().len();
+            assert_eq!(len, 1_000_000);
+        }
+        assert!(!path.exists());
+    }
+
+    /// Tests that `RollingFileSink` keeps every entry of concurrent writers
+    /// and compresses rotated files when asked to.
+    #[tokio::test]
+    async fn test_rolling_file_sink_concurrent_compressed() {
+        let dir = tempdir().unwrap();
+        let sink = Arc::new(RollingFileSink::new(
+            dir.path().join("RLG.log"),
+            LogRotation::Count(10),
+            20,
+            true,
+        ));
+
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let sink = Arc::clone(&sink);
+                tokio::spawn(async move {
+                    for n in 0..25 {
+                        let description =
+                            format!("writer {} entry {}", writer, n);
+                        sample_log(&description, LogFormat::CLF)
+                            .log_to(sink.as_ref())
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.await.unwrap();
+        }
+
+        let rotated = rotated_files(dir.path());
+        assert_eq!(rotated.len(), 10);
+        let mut lines = 0;
+        for name in &rotated {
+            assert!(name.ends_with(".gz"), "{}", name);
+            let mut contents = String::new();
+            let file =
+                std::fs::File::open(dir.path().join(name)).unwrap();
+            let _ = flate2::read::GzDecoder::new(file)
+                .read_to_string(&mut contents)
+                .unwrap();
+            assert_eq!(contents.lines().count(), 10);
+            lines += contents.lines().count();
+        }
+        assert_eq!(lines, 100);
+    }
+
+    /// Tests that `FileSink` reports an error for an unwritable path.
+    #[tokio::test]
+    async fn test_file_sink_invalid_path() {
+        let dir = tempdir().unwrap();
+        let sink =
+            FileSink::new(dir.path().join("missing").join("x.log"));
+        let result =
+            sample_log("lost", LogFormat::CLF).log_to(&sink).await;
+        assert!(result.is_err());
+    }
+
+    /// Tests that `BinarySink` appends length-prefixed MessagePack frames.
+    #[tokio::test]
+    async fn test_binary_sink_frames() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sink.bin");
+        let sink = BinarySink::new(&path);
+        assert_eq!(sink.path(), path.as_path());
+
+        let logs = vec![
+            sample_log("one", LogFormat::CLF),
+            sample_log("two", LogFormat::JSON)
+                .with_field("user_id", "42"),
+        ];
+        for log in &logs {
+            log.log_to(&sink).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let contents = fs::read(&path).await.unwrap();
+        let mut decoded = Vec::new();
+        let mut rest = contents.as_slice();
+        while !rest.is_empty() {
+            let mut length = [0; 4];
+            length.copy_from_slice(&rest[..4]);
+            let length = u32::from_be_bytes(length) as usize;
+            decoded
+                .push(Log::from_bytes(&rest[4..4 + length]).unwrap());
+            rest = &rest[4 + length..];
+        }
+        assert_eq!(decoded, logs);
+    }
+
+    /// Receives a single datagram on `socket` as a string.
+    async fn receive_datagram(socket: &UdpSocket) -> String {
+        let mut buffer = [0; 2048];
+        let (length, _) = socket.recv_from(&mut buffer).await.unwrap();
+        String::from_utf8(buffer[..length].to_vec()).unwrap()
+    }
+
+    /// Tests that `UdpSyslogSink` sends each entry as an RFC 5424 datagram.
+    #[tokio::test]
+    async fn test_udp_syslog_sink() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = receiver.local_addr().unwrap();
+        let sink = UdpSyslogSink::new(addr);
+        assert_eq!(sink.addr(), addr);
+
+        sample_log("user logged in", LogFormat::JSON)
+            .with_field("user_id", "42")
+            .log_to(&sink)
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+
+        let message = receive_datagram(&receiver).await;
+        assert!(message.starts_with("<14>1 2024-01-01T00:00:00Z "));
+        assert!(message.contains(" api "));
+        assert!(message.contains(
+            "[rlg@32473 sessionID=\"session-1\" user_id=\"42\"]"
+        ));
+        assert!(message.ends_with(" user logged in"));
+        assert!(LogFormat::RFC5424.validate(&message));
+    }
+
+    /// Tests that `UdpSyslogSink` truncates messages to 1024 bytes.
+    #[tokio::test]
+    async fn test_udp_syslog_sink_truncates() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = UdpSyslogSink::new(receiver.local_addr().unwrap());
+
+        let description = "é".repeat(1000);
+        sample_log(&description, LogFormat::RFC5424)
+            .log_to(&sink)
+            .await
+            .unwrap();
+
+        let message = receive_datagram(&receiver).await;
+        assert!(message.len() <= 1024);
+        assert!(message.len() > 1020);
+        assert!(message.ends_with('é'));
+    }
+
+    /// Tests that `log_with_config` sends entries to `UdpSyslog`
+    /// destinations.
+    #[tokio::test]
+    async fn test_log_with_config_udp_syslog() {
+        let dir = tempdir().unwrap();
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let config = Config {
+            log_file_path: dir.path().join("app.log"),
+            logging_destinations: vec![LoggingDestination::UdpSyslog {
+                addr: receiver.local_addr().unwrap(),
+            }],
+            ..Default::default()
+        };
+
+        sample_log("forwarded", LogFormat::CLF)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+
+        let message = receive_datagram(&receiver).await;
+        assert!(message.starts_with("<14>1 "));
+        assert!(message.ends_with(" forwarded"));
+    }
+
+    /// Reads `count` lines from an accepted TCP connection.
+    async fn read_lines(
+        stream: TcpStream,
+        count: usize,
+    ) -> Vec<String> {
+        let mut lines = BufReader::new(stream).lines();
+        let mut received = Vec::with_capacity(count);
+        while received.len() < count {
+            received.push(lines.next_line().await.unwrap().unwrap());
+        }
+        received
+    }
+
+    /// Tests that `TcpSink` sends newline-terminated entries and
+    /// reconnects after the server drops the connection.
+    #[tokio::test]
+    async fn test_tcp_sink_reconnects() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (dropped_tx, dropped_rx) = oneshot::channel();
+        let server = tokio::spawn(async move {
+            let (first, _) = listener.accept().await.unwrap();
+            let mut received = read_lines(first, 5).await;
+            dropped_tx.send(()).unwrap();
+            let (second, _) = listener.accept().await.unwrap();
+            received.extend(read_lines(second, 5).await);
+            received
+        });
+
+        let sink = TcpSink::new(addr);
+        assert_eq!(sink.addr(), addr);
+        let logs: Vec<Log> = (0..10)
+            .map(|i| {
+                sample_log(&format!("entry {}", i), LogFormat::JSON)
+            })
+            .collect();
+        for log in &logs[..5] {
+            log.log_to(&sink).await.unwrap();
+        }
+        dropped_rx.await.unwrap();
+        for log in &logs[5..] {
+            log.log_to(&sink).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let received = server.await.unwrap();
+        assert_eq!(received.len(), 10);
+        for (line, log) in received.iter().zip(&logs) {
+            assert_eq!(line, &log.to_string());
+        }
+    }
+
+    /// Tests that `TcpSink` reports a network error once its retries are
+    /// exhausted.
+    #[tokio::test]
+    async fn test_tcp_sink_gives_up() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let result = sample_log("lost", LogFormat::CLF)
+            .log_to(&TcpSink::new(addr))
+            .await;
+        match result {
+            Err(RlgError::NetworkError(message)) => {
+                assert!(message.contains("after 3 retries"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Tests that `UdpSink` sends each entry, in its own format, as a
+    /// datagram.
+    #[tokio::test]
+    async fn test_udp_sink() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = receiver.local_addr().unwrap();
+        let sink = UdpSink::new(addr);
+        assert_eq!(sink.addr(), addr);
+
+        let log = sample_log("user logged in", LogFormat::JSON);
+        log.log_to(&sink).await.unwrap();
+        sink.flush().await.unwrap();
+
+        assert_eq!(
+            receive_datagram(&receiver).await,
+            format!("{}\n", log)
+        );
+    }
+
+    /// Tests that `log_with_config` sends entries to `Tcp` and `Udp`
+    /// destinations, reusing one connection per TCP server.
+    #[tokio::test]
+    async fn test_log_with_config_tcp_and_udp() {
+        let dir = tempdir().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let config = Config {
+            log_file_path: dir.path().join("app.log"),
+            logging_destinations: vec![
+                LoggingDestination::Tcp(listener.local_addr().unwrap()),
+                LoggingDestination::Udp(receiver.local_addr().unwrap()),
+            ],
+            ..Default::default()
+        };
+
+        for description in ["forwarded", "reused"] {
+            sample_log(description, LogFormat::CLF)
+                .log_with_config(&config)
+                .await
+                .unwrap();
+        }
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let lines = read_lines(stream, 2).await;
+        assert!(lines[0].contains("Description=forwarded "));
+        assert!(lines[1].contains("Description=reused "));
+        assert!(receive_datagram(&receiver)
+            .await
+            .contains("Description=forwarded "));
+    }
+
+    /// Tests that the standard stream sinks accept entries.
+    #[tokio::test]
+    async fn test_standard_stream_sinks() {
+        let log = sample_log("stream", LogFormat::CLF);
+        let _guard = STDOUT_CAPTURE.lock().await;
+        log.log_to(&StdoutSink::default()).await.unwrap();
+        StdoutSink::default().flush().await.unwrap();
+        log.log_to(&StderrSink).await.unwrap();
+        StderrSink.flush().await.unwrap();
+    }
+
+    /// Tests that `StdoutSink` writes the same lines as a file sink, or
+    /// colored blocks when colorizing.
+    #[tokio::test]
+    async fn test_stdout_sink_colorize() {
+        let log = sample_log("colored", LogFormat::CLF);
+        let capture = |sink: StdoutSink| {
+            let log = log.clone();
+            async move {
+                let mut redirect = BufferRedirect::stdout().unwrap();
+                log.log_to(&sink).await.unwrap();
+                let mut captured = String::new();
+                redirect.read_to_string(&mut captured).unwrap();
+                captured
+            }
+        };
+
+        // Unlike `Display`, file lines of CLF entries name their format.
+        let line = file_line(&log).await;
+        assert_eq!(line, format!("{} Format=CLF\n", log));
+
+        let _guard = STDOUT_CAPTURE.lock().await;
+        colored::control::set_override(true);
+        let plain = capture(StdoutSink::new(false)).await;
+        let pretty = capture(StdoutSink::new(true)).await;
+        colored::control::set_override(false);
+        let piped = capture(StdoutSink::new(true)).await;
+        colored::control::unset_override();
+
+        assert!(plain.contains(&line));
+        assert!(!plain.contains('\u{1b}'));
+        assert!(pretty.contains("\u{1b}["), "{:?}", pretty);
+        assert!(pretty.contains("\n  colored\n"));
+        assert!(piped.contains(&line));
+    }
+
+    /// Tests that colorized entries take the level colors of the
+    /// configuration.
+    #[tokio::test]
+    async fn test_stdout_sink_level_colors() {
+        let log = sample_log("colored", LogFormat::CLF);
+        let config = Config {
+            log_level_color_map: HashMap::from([(
+                LogLevel::INFO,
+                "\x1b[32m".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let sink = StdoutSink::new(true)
+            .with_colors(config.log_level_color_map.clone());
+
+        let _guard = STDOUT_CAPTURE.lock().await;
+        colored::control::set_override(true);
+        let pretty = log
+            .to_pretty_string_with_colors(&config.log_level_color_map);
+        let defaults =
+            log.to_pretty_string_with_colors(&HashMap::new());
+        let mut redirect = BufferRedirect::stdout().unwrap();
+        log.log_to(&sink).await.unwrap();
+        let mut captured = String::new();
+        redirect.read_to_string(&mut captured).unwrap();
+        drop(redirect);
+        colored::control::unset_override();
+
+        assert!(pretty.starts_with("\x1b[32mINFO"), "{:?}", pretty);
+        assert!(defaults.starts_with("\x1b[37mINFO"), "{:?}", defaults);
+        assert!(captured.contains(&pretty), "{:?}", captured);
+    }
+
+    /// Tests that `StderrSink` and the `Stderr` destination write to stderr.
+    #[tokio::test]
+    async fn test_stderr_output_is_captured() {
+        let temp_dir = tempdir().unwrap();
+        let config = Config {
+            log_file_path: temp_dir.path().join("stderr.log"),
+            logging_destinations: vec![LoggingDestination::Stderr],
+            ..Default::default()
+        };
+
+        let _guard = STDERR_CAPTURE.lock().await;
+        let mut redirect = BufferRedirect::stderr().unwrap();
+        sample_log("direct to stderr", LogFormat::CLF)
+            .log_to(&StderrSink)
+            .await
+            .unwrap();
+        sample_log("routed to stderr", LogFormat::CLF)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+        let mut captured = String::new();
+        redirect.read_to_string(&mut captured).unwrap();
+        drop(redirect);
+
+        assert!(captured.contains("Description=direct to stderr"));
+        assert!(captured.contains("Description=routed to stderr"));
+        let file =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        assert!(file.contains("Description=routed to stderr"));
+        assert!(!file.contains("Description=direct to stderr"));
+    }
+
+    /// Tests that one entry is rendered in each destination's own format.
+    #[tokio::test]
+    async fn test_per_destination_format() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config {
+            log_file_path: temp_dir.path().join("formats.log"),
+            logging_destinations: vec![LoggingDestination::Stderr],
+            ..Default::default()
+        };
+        config.per_destination_format.insert(
+            format!("file:{}", config.log_file_path.display()),
+            LogFormat::JSON,
+        );
+        config
+            .per_destination_format
+            .insert("stderr".to_string(), LogFormat::CLF);
+
+        let _guard = STDERR_CAPTURE.lock().await;
+        let mut redirect = BufferRedirect::stderr().unwrap();
+        sample_log("one entry", LogFormat::ELF)
+            .log_with_config(&config)
+            .await
+            .unwrap();
+        let mut captured = String::new();
+        redirect.read_to_string(&mut captured).unwrap();
+        drop(redirect);
+
+        assert!(captured.contains("Description=one entry"));
+        assert!(captured.contains("Format=CLF"));
+        let file =
+            fs::read_to_string(&config.log_file_path).await.unwrap();
+        let parsed = Log::from_json_str(file.trim_end()).unwrap();
+        assert_eq!(parsed.description, "one entry");
+        assert_eq!(parsed.format, LogFormat::JSON);
+    }
+
+    /// Tests that `log_to_all` writes to every sink.
+    #[tokio::test]
+    async fn test_log_to_all() {
+        let first = MemorySink::default();
+        let second = MemorySink::default();
+        sample_log("fan out", LogFormat::CLF)
+            .log_to_all(&[&first, &second])
+            .await
+            .unwrap();
+
+        assert_eq!(first.lines.lock().unwrap().len(), 1);
+        assert_eq!(second.lines.lock().unwrap().len(), 1);
+        assert!(sample_log("none", LogFormat::CLF)
+            .log_to_all(&[])
+            .await
+            .is_ok());
+    }
+
+    /// Tests that `log_to_all` keeps going after a failing sink.
+    #[tokio::test]
+    async fn test_log_to_all_with_failures() {
+        let first = MemorySink::default();
+        let second = MemorySink::default();
+        let result = sample_log("partial", LogFormat::CLF)
+            .log_to_all(&[
+                &first,
+                &FailingSink("first failure"),
+                &second,
+                &FailingSink("second failure"),
+            ])
+            .await;
+
+        match result {
+            Err(RlgError::Custom(message)) => {
+                assert_eq!(message, "first failure")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(first.lines.lock().unwrap().len(), 1);
+        assert_eq!(second.lines.lock().unwrap().len(), 1);
+    }
+
+    /// Tests that `log_to_all_concurrent` writes to every sink.
+    #[tokio::test]
+    async fn test_log_to_all_concurrent() {
+        let first = Arc::new(MemorySink::default());
+        let second = Arc::new(MemorySink::default());
+        let sinks: Vec<Arc<dyn LogSink>> =
+            vec![first.clone(), second.clone()];
+        sample_log("concurrent", LogFormat::CLF)
+            .log_to_all_concurrent(&sinks)
+            .await
+            .unwrap();
+
+        assert_eq!(first.lines.lock().unwrap().len(), 1);
+        assert_eq!(second.lines.lock().unwrap().len(), 1);
+    }
+
+    /// Tests that `log_to_all_concurrent` keeps going after a failing sink.
+    #[tokio::test]
+    async fn test_log_to_all_concurrent_with_failures() {
+        let first = Arc::new(MemorySink::default());
+        let second = Arc::new(MemorySink::default());
+        let sinks: Vec<Arc<dyn LogSink>> = vec![
+            Arc::new(FailingSink("first failure")),
+            first.clone(),
+            Arc::new(FailingSink("second failure")),
+            second.clone(),
+        ];
+        let result = sample_log("partial", LogFormat::CLF)
+            .log_to_all_concurrent(&sinks)
+            .await;
+
+        match result {
+            Err(RlgError::Custom(message)) => {
+                assert_eq!(message, "first failure")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(first.lines.lock().unwrap().len(), 1);
+        assert_eq!(second.lines.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright © 2024 RustLogs (RLG). All rights reserved.
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tests for the `LogTimer` guard.
+
+#[cfg(test)]
+mod tests {
+    use rlg::{
+        context::{with_log_context, LogContext},
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+    };
+    use std::time::Duration;
+
+    /// Tests that the completion entry reports the elapsed time.
+    #[tokio::test(start_paused = true)]
+    async fn test_log_timer_elapsed() {
+        let timer = Log::new_timed("db", "query users");
+        tokio::time::advance(Duration::from_millis(250)).await;
+
+        assert_eq!(timer.elapsed(), Duration::from_millis(250));
+        let entry = timer.entry();
+        assert_eq!(entry.description, "query users completed in 250ms");
+        assert_eq!(entry.component, "db");
+        assert_eq!(entry.level, LogLevel::INFO);
+        assert_eq!(entry.format, LogFormat::CLF);
+        assert!(!entry.session_id.is_empty());
+        timer.abort();
+    }
+
+    /// Tests that the timer keeps the session ID of the context it was
+    /// started in.
+    #[tokio::test]
+    async fn test_log_timer_context_session() {
+        let context = LogContext::new("request-42", "api");
+        let timer = with_log_context(context, async {
+            Log::new_timed("api", "handle")
+        })
+        .await;
+        assert_eq!(timer.entry().session_id, "request-42");
+        timer.abort();
+    }
+
+    /// Tests that dropping or aborting a timer does not panic, inside or
+    /// outside a runtime.
+    #[test]
+    fn test_log_timer_drop() {
+        Log::new_timed("app", "outside runtime");
+        Log::new_timed("app", "aborted").abort();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            tokio::time::pause();
+            let timer = Log::new_timed("app", "inside runtime");
+            tokio::time::advance(Duration::from_secs(2)).await;
+            assert!(timer
+                .entry()
+                .description
+                .ends_with("completed in 2000ms"));
+            drop(timer);
+            tokio::task::yield_now().await;
+        });
+    }
+}
@@ -1,10 +1,23 @@
 #[cfg(test)]
 mod tests {
     use rlg::utils::*;
-    use tokio::fs::{self, File};
+    use rlg::{
+        config::{
+            Config, ConfigError, LogRotation, RotationNamingConvention,
+        },
+        log::Log,
+        log_format::LogFormat,
+        log_level::LogLevel,
+        sink::FileSink,
+        RlgError,
+    };
+    use tokio::fs::{self, File, OpenOptions};
 
+    use futures::StreamExt;
+    use std::{collections::HashMap, num::NonZeroU64, time::Duration};
     use tempfile::tempdir;
     use tokio::io::AsyncWriteExt;
+    use tokio::time::timeout;
 
     #[test]
     fn test_sanitize_log_message() {
@@ -13,6 +26,53 @@ fn test_sanitize_log_message() {
         assert_eq!(sanitize_log_message(input), expected);
     }
 
+    #[test]
+    fn test_generate_trace_and_span_ids() {
+        let is_lower_hex = |id: &str| {
+            id.chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+        };
+
+        let trace_id = generate_trace_id();
+        assert_eq!(trace_id.len(), 32);
+        assert!(is_lower_hex(&trace_id), "{}", trace_id);
+        assert_ne!(trace_id, generate_trace_id());
+
+        let span_id = generate_span_id();
+        assert_eq!(span_id.len(), 16);
+        assert!(is_lower_hex(&span_id), "{}", span_id);
+        assert_ne!(span_id, generate_span_id());
+
+        let traceparent = new_traceparent(0, true);
+        let parts: Vec<&str> = traceparent.split('-').collect();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0], "00");
+        assert_eq!(parts[1].len(), 32);
+        assert_eq!(parts[2].len(), 16);
+        assert_eq!(parts[3], "01");
+        assert!(parts.iter().all(|part| is_lower_hex(part)));
+        assert!(new_traceparent(0, false).ends_with("-00"));
+        assert_ne!(traceparent, new_traceparent(0, true));
+    }
+
+    #[test]
+    fn test_apply_template() {
+        let params =
+            HashMap::from([("user", "alice"), ("ip", "10.0.0.1")]);
+        assert_eq!(
+            apply_template("%{user}@%{ip}: 100% done", &params)
+                .unwrap(),
+            "alice@10.0.0.1: 100% done"
+        );
+        assert_eq!(apply_template("", &params).unwrap(), "");
+        for template in ["%{host}", "%{user"] {
+            assert!(matches!(
+                apply_template(template, &params),
+                Err(RlgError::FormattingError(_))
+            ));
+        }
+    }
+
     #[test]
     fn test_format_file_size() {
         assert_eq!(format_file_size(1023), "1023.00 B");
@@ -81,4 +141,1434 @@ async fn test_is_directory_writable() {
             .await
             .unwrap());
     }
+
+    #[tokio::test]
+    async fn test_compress_and_decompress_file() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("test.log");
+        let gz = temp_dir.path().join("test.log.gz");
+        let restored = temp_dir.path().join("restored.log");
+        let content =
+            "SessionID=1 Level=INFO Description=repeat\n".repeat(100);
+        fs::write(&src, &content).await.unwrap();
+
+        compress_file(&src, &gz, false).await.unwrap();
+        assert!(src.exists());
+        let compressed = fs::read(&gz).await.unwrap();
+        assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+        assert!((compressed.len() as u64) < content.len() as u64);
+
+        decompress_file(&gz, &restored, false).await.unwrap();
+        assert!(gz.exists());
+        let decompressed = fs::read_to_string(&restored).await.unwrap();
+        assert_eq!(decompressed, content);
+    }
+
+    #[tokio::test]
+    async fn test_compress_and_decompress_file_delete_source() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("app.log");
+        let gz = temp_dir.path().join("app.log.gz");
+        let restored = temp_dir.path().join("restored.log");
+        let content: Vec<u8> = (0..10 * 1024)
+            .map(|i| b"INFO request served\n"[i % 20])
+            .collect();
+        fs::write(&src, &content).await.unwrap();
+
+        compress_file(&src, &gz, true).await.unwrap();
+        assert!(!src.exists());
+        let compressed_size = fs::metadata(&gz).await.unwrap().len();
+        assert_eq!(format_file_size(content.len() as u64), "10.00 KB");
+        assert!(compressed_size < 1024);
+        assert_eq!(
+            format_file_size(compressed_size),
+            format!("{}.00 B", compressed_size)
+        );
+
+        decompress_file(&gz, &restored, true).await.unwrap();
+        assert!(!gz.exists());
+        assert_eq!(fs::read(&restored).await.unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_decompress_file_keeps_source_on_error() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("corrupt.log.gz");
+        let dst = temp_dir.path().join("corrupt.log");
+        fs::write(&src, "not gzip data").await.unwrap();
+        assert!(decompress_file(&src, &dst, true).await.is_err());
+        assert!(src.exists());
+    }
+
+    #[tokio::test]
+    async fn test_compress_file_missing_source() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("missing.log");
+        let gz = temp_dir.path().join("missing.log.gz");
+        assert!(compress_file(&src, &gz, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decompress_file_invalid_data() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("plain.log.gz");
+        let dst = temp_dir.path().join("plain.log");
+        fs::write(&src, "not gzip data").await.unwrap();
+        assert!(decompress_file(&src, &dst, false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_compress_rotated_file() {
+        let temp_dir = tempdir().unwrap();
+        let rotated = temp_dir.path().join("RLG.log.1");
+        let restored = temp_dir.path().join("RLG.log.restored");
+        fs::write(&rotated, "rotated entries\n").await.unwrap();
+
+        let compressed = compress_rotated_file(&rotated).await.unwrap();
+        assert_eq!(compressed, temp_dir.path().join("RLG.log.1.gz"));
+        assert!(compressed.exists());
+        assert!(!rotated.exists());
+
+        decompress_file(&compressed, &restored, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&restored).await.unwrap(),
+            "rotated entries\n"
+        );
+    }
+
+    #[test]
+    fn test_enforce_max_rotation_files() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("base.log");
+        for n in 1..=15 {
+            std::fs::write(
+                temp_dir.path().join(format!("base.log.{}", n)),
+                "",
+            )
+            .unwrap();
+        }
+        for name in [
+            "base.log",
+            "base.log.12.gz",
+            "base.log.old",
+            "other.log.9",
+        ] {
+            std::fs::write(temp_dir.path().join(name), "").unwrap();
+        }
+
+        enforce_max_rotation_files(&base, 5).unwrap();
+
+        let mut remaining: Vec<String> =
+            std::fs::read_dir(temp_dir.path())
+                .unwrap()
+                .map(|entry| {
+                    entry.unwrap().file_name().into_string().unwrap()
+                })
+                .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                "base.log",
+                "base.log.1",
+                "base.log.2",
+                "base.log.3",
+                "base.log.4",
+                "base.log.5",
+                "base.log.old",
+                "other.log.9",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_force_rotate_log_file() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let rotated =
+            |n: u32| temp_dir.path().join(format!("RLG.log.{}", n));
+        std::fs::write(&base, "current").unwrap();
+        std::fs::write(rotated(1), "previous").unwrap();
+        std::fs::write(temp_dir.path().join("RLG.log.2.gz"), "older")
+            .unwrap();
+        std::fs::write(rotated(3), "oldest").unwrap();
+
+        assert_eq!(
+            force_rotate_log_file(&base, 3).unwrap(),
+            rotated(1)
+        );
+
+        assert!(!base.exists());
+        assert_eq!(
+            std::fs::read_to_string(rotated(1)).unwrap(),
+            "current"
+        );
+        assert_eq!(
+            std::fs::read_to_string(rotated(2)).unwrap(),
+            "previous"
+        );
+        assert_eq!(
+            std::fs::read_to_string(
+                temp_dir.path().join("RLG.log.3.gz")
+            )
+            .unwrap(),
+            "older"
+        );
+        assert!(!rotated(4).exists());
+        assert!(force_rotate_log_file(&base, 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_log_file_naming_conventions() {
+        let patterns = [
+            (RotationNamingConvention::Numeric, r"^RLG\.log\.1$"),
+            (
+                RotationNamingConvention::DateStamped,
+                r"^RLG\.\d{4}-\d{2}-\d{2}\.log$",
+            ),
+            (
+                RotationNamingConvention::TimestampMillis,
+                r"^RLG\.\d{13}\.log$",
+            ),
+        ];
+        for (convention, pattern) in patterns {
+            let temp_dir = tempdir().unwrap();
+            let base = temp_dir.path().join("RLG.log");
+            let config = Config {
+                log_rotation: Some("size:8".parse().unwrap()),
+                rotation_naming_convention: convention,
+                ..Default::default()
+            };
+            fs::write(&base, "0123456789").await.unwrap();
+            assert!(rotate_log_file(&base, &config).await.unwrap());
+
+            let names: Vec<String> = std::fs::read_dir(temp_dir.path())
+                .unwrap()
+                .map(|entry| {
+                    entry
+                        .unwrap()
+                        .file_name()
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect();
+            assert_eq!(names.len(), 1, "{:?}", names);
+            assert!(
+                regex::Regex::new(pattern).unwrap().is_match(&names[0]),
+                "{:?}: {}",
+                convention,
+                names[0]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotate_log_file_date_stamped_retention() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let config = Config {
+            log_rotation: Some("size:8".parse().unwrap()),
+            log_rotation_max_files: 2,
+            rotation_naming_convention:
+                RotationNamingConvention::DateStamped,
+            ..Default::default()
+        };
+        std::fs::write(
+            temp_dir.path().join("RLG.2000-01-01.log"),
+            "old",
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("RLG.log.1"), "numbered")
+            .unwrap();
+
+        // Rotations on the same day are numbered after the date.
+        let mut rotated = Vec::new();
+        for content in ["first.....", "second...."] {
+            fs::write(&base, content).await.unwrap();
+            assert!(rotate_log_file(&base, &config).await.unwrap());
+            let entry = std::fs::read_dir(temp_dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .find(|path| {
+                    std::fs::read_to_string(path).unwrap() == content
+                })
+                .unwrap();
+            rotated.push(entry);
+        }
+        let second = rotated[1].file_name().unwrap().to_string_lossy();
+        assert!(
+            regex::Regex::new(r"^RLG\.\d{4}-\d{2}-\d{2}\.1\.log$")
+                .unwrap()
+                .is_match(&second),
+            "{}",
+            second
+        );
+
+        // Only the two newest stamped files are kept.
+        assert!(rotated.iter().all(|path| path.exists()));
+        assert!(!temp_dir.path().join("RLG.2000-01-01.log").exists());
+        assert!(temp_dir.path().join("RLG.log.1").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_log_file_size_threshold() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let config = Config {
+            log_rotation: Some(LogRotation::Size(
+                NonZeroU64::new(64).unwrap(),
+            )),
+            ..Default::default()
+        };
+
+        // Missing and empty files are never rotated.
+        assert!(!rotate_log_file(&base, &config).await.unwrap());
+        fs::write(&base, "").await.unwrap();
+        assert!(!rotate_log_file(&base, &config).await.unwrap());
+
+        fs::write(&base, vec![b'x'; 64]).await.unwrap();
+        assert!(!rotate_log_file(&base, &config).await.unwrap());
+        assert!(!temp_dir.path().join("RLG.log.1").exists());
+
+        let mut file =
+            OpenOptions::new().append(true).open(&base).await.unwrap();
+        file.write_all(b"y").await.unwrap();
+        file.flush().await.unwrap();
+        assert!(rotate_log_file(&base, &config).await.unwrap());
+        assert!(!base.exists());
+        assert_eq!(
+            fs::read(temp_dir.path().join("RLG.log.1"))
+                .await
+                .unwrap()
+                .len(),
+            65
+        );
+
+        let no_rotation = Config {
+            log_rotation: None,
+            ..config.clone()
+        };
+        fs::write(&base, vec![b'x'; 128]).await.unwrap();
+        assert!(!rotate_log_file(&base, &no_rotation).await.unwrap());
+    }
+
+    /// One day, the unit of `Config::max_log_age_days`.
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Writes `contents` to a file last modified `age` ago.
+    #[allow(clippy::incompatible_msrv)]
+    fn create_aged_file(
+        path: &std::path::Path,
+        contents: &str,
+        age: Duration,
+    ) {
+        std::fs::write(path, contents).unwrap();
+        let file =
+            std::fs::OpenOptions::new().write(true).open(path).unwrap();
+        file.set_modified(std::time::SystemTime::now() - age)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_archive_old_logs() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path();
+        let archive = dir.join("archive");
+        create_aged_file(&dir.join("RLG.log.1"), "", DAY * 10);
+        create_aged_file(&dir.join("RLG.log.2.gz"), "", DAY * 30);
+        create_aged_file(&dir.join("RLG.log.3"), "", DAY);
+        create_aged_file(&dir.join("RLG.log"), "", DAY * 10);
+        create_aged_file(&dir.join("notes.txt"), "", DAY * 10);
+        // Rotated files of other log files are not ours to archive.
+        create_aged_file(&dir.join("other.log.1"), "", DAY * 10);
+        create_aged_file(&dir.join("RLG.log.old"), "", DAY * 10);
+        let base = dir.join("RLG.log");
+
+        assert_eq!(
+            archive_old_logs(&base, 7, &archive).await.unwrap(),
+            2
+        );
+        assert!(archive.join("RLG.log.1").exists());
+        assert!(archive.join("RLG.log.2.gz").exists());
+        assert!(!dir.join("RLG.log.1").exists());
+        assert!(!dir.join("RLG.log.2.gz").exists());
+        for kept in [
+            "RLG.log.3",
+            "RLG.log",
+            "notes.txt",
+            "other.log.1",
+            "RLG.log.old",
+        ] {
+            assert!(dir.join(kept).exists(), "{}", kept);
+            assert!(!archive.join(kept).exists(), "{}", kept);
+        }
+
+        // Nothing is left to archive, and no directory is created.
+        let unused = dir.join("unused");
+        assert_eq!(
+            archive_old_logs(&base, 7, &unused).await.unwrap(),
+            0
+        );
+        assert!(!unused.exists());
+        assert!(archive_old_logs(
+            &dir.join("missing").join("RLG.log"),
+            7,
+            &archive
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_log_file_archives_old_logs() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let archive = temp_dir.path().join("archive");
+        let config = Config {
+            log_rotation: Some(LogRotation::Size(
+                NonZeroU64::new(8).unwrap(),
+            )),
+            log_archive_dir: Some(archive.clone()),
+            max_log_age_days: Some(7),
+            ..Default::default()
+        };
+        create_aged_file(
+            &temp_dir.path().join("RLG.log.1"),
+            "",
+            DAY * 10,
+        );
+        fs::write(&base, vec![b'x'; 16]).await.unwrap();
+
+        assert!(rotate_log_file(&base, &config).await.unwrap());
+        // The old file was shifted to `RLG.log.2`, then archived.
+        assert!(archive.join("RLG.log.2").exists());
+        assert!(!temp_dir.path().join("RLG.log.2").exists());
+        assert!(temp_dir.path().join("RLG.log.1").exists());
+        assert!(!archive.join("RLG.log.1").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_log_file_archives_stamped_logs() {
+        for convention in [
+            RotationNamingConvention::DateStamped,
+            RotationNamingConvention::TimestampMillis,
+        ] {
+            let temp_dir = tempdir().unwrap();
+            let dir = temp_dir.path();
+            let base = dir.join("RLG.log");
+            let archive = dir.join("archive");
+            let config = Config {
+                log_rotation: Some(LogRotation::Size(
+                    NonZeroU64::new(8).unwrap(),
+                )),
+                rotation_naming_convention: convention,
+                log_archive_dir: Some(archive.clone()),
+                max_log_age_days: Some(7),
+                ..Default::default()
+            };
+            create_aged_file(
+                &dir.join("RLG.2024-01-15.log"),
+                "",
+                DAY * 10,
+            );
+            create_aged_file(
+                &dir.join("RLG.1700000000000.log.gz"),
+                "",
+                DAY * 10,
+            );
+            create_aged_file(&dir.join("RLG.notes.log"), "", DAY * 10);
+            fs::write(&base, vec![b'x'; 16]).await.unwrap();
+
+            assert!(rotate_log_file(&base, &config).await.unwrap());
+            for archived in
+                ["RLG.2024-01-15.log", "RLG.1700000000000.log.gz"]
+            {
+                assert!(
+                    archive.join(archived).exists(),
+                    "{}",
+                    archived
+                );
+                assert!(!dir.join(archived).exists(), "{}", archived);
+            }
+            assert!(dir.join("RLG.notes.log").exists());
+            // The file rotated just now is too recent to be archived.
+            let rotated = std::fs::read_dir(dir)
+                .unwrap()
+                .filter_map(|entry| {
+                    entry.unwrap().file_name().into_string().ok()
+                })
+                .filter(|name| {
+                    name.starts_with("RLG.") && name != "RLG.log"
+                })
+                .count();
+            assert_eq!(rotated, 2, "{:?}", convention);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_rotation_time() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("RLG.log");
+        let secs =
+            |estimate: Option<Duration>| estimate.unwrap().as_secs();
+
+        // 1000 bytes written in 100 seconds leave 1000 bytes for another
+        // 100 seconds.
+        create_aged_file(
+            &path,
+            &"x".repeat(1000),
+            Duration::from_secs(100),
+        );
+        let size = LogRotation::Size(NonZeroU64::new(2000).unwrap());
+        let estimate =
+            estimate_rotation_time(&path, &size).await.unwrap();
+        assert!((95..=105).contains(&secs(estimate)), "{:?}", estimate);
+
+        let full = LogRotation::Size(NonZeroU64::new(500).unwrap());
+        assert_eq!(
+            estimate_rotation_time(&path, &full).await.unwrap(),
+            Some(Duration::ZERO)
+        );
+
+        // 10 lines in 100 seconds leave 30 lines for another 300 seconds.
+        create_aged_file(
+            &path,
+            &"entry\n".repeat(10),
+            Duration::from_secs(100),
+        );
+        let estimate =
+            estimate_rotation_time(&path, &LogRotation::Count(40))
+                .await
+                .unwrap();
+        assert!(
+            (290..=310).contains(&secs(estimate)),
+            "{:?}",
+            estimate
+        );
+
+        let time = LogRotation::Time(NonZeroU64::new(3600).unwrap());
+        let estimate =
+            estimate_rotation_time(&path, &time).await.unwrap();
+        assert!(
+            (3490..=3600).contains(&secs(estimate)),
+            "{:?}",
+            estimate
+        );
+
+        let cron = LogRotation::Cron("0 0 * * *".to_string());
+        let estimate =
+            estimate_rotation_time(&path, &cron).await.unwrap();
+        assert!(secs(estimate) <= 24 * 60 * 60, "{:?}", estimate);
+
+        assert_eq!(
+            estimate_rotation_time(&path, &LogRotation::Date)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimate_rotation_time_missing_file() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("missing.log");
+        let size = LogRotation::Size(NonZeroU64::new(1024).unwrap());
+        assert_eq!(
+            estimate_rotation_time(&path, &size).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            estimate_rotation_time(&path, &LogRotation::Count(10))
+                .await
+                .unwrap(),
+            None
+        );
+        let time = LogRotation::Time(NonZeroU64::new(60).unwrap());
+        assert_eq!(
+            estimate_rotation_time(&path, &time).await.unwrap(),
+            Some(Duration::from_secs(60))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotate_log_file_policies() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let config = |rotation: LogRotation| Config {
+            log_rotation: Some(rotation),
+            log_rotation_max_files: 2,
+            compress_rotated_files: true,
+            ..Default::default()
+        };
+
+        fs::write(&base, "1\n2\n").await.unwrap();
+        let count = config(LogRotation::Count(3));
+        assert!(!rotate_log_file(&base, &count).await.unwrap());
+        fs::write(&base, "1\n2\n3\n").await.unwrap();
+        assert!(rotate_log_file(&base, &count).await.unwrap());
+        let compressed = temp_dir.path().join("RLG.log.1.gz");
+        assert!(compressed.exists());
+        assert!(!temp_dir.path().join("RLG.log.1").exists());
+
+        // A file created just now is neither too old nor from a
+        // previous day.
+        fs::write(&base, "entry\n").await.unwrap();
+        let time =
+            config(LogRotation::Time(NonZeroU64::new(3600).unwrap()));
+        assert!(!rotate_log_file(&base, &time).await.unwrap());
+        assert!(!rotate_log_file(&base, &config(LogRotation::Date))
+            .await
+            .unwrap());
+
+        for _ in 0..3 {
+            fs::write(&base, "1\n2\n3\n").await.unwrap();
+            assert!(rotate_log_file(&base, &count).await.unwrap());
+        }
+        assert!(temp_dir.path().join("RLG.log.2.gz").exists());
+        assert!(!temp_dir.path().join("RLG.log.3.gz").exists());
+    }
+
+    #[tokio::test]
+    async fn test_log_with_config_rotates_log_file() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let config = Config {
+            log_file_path: base.clone(),
+            log_rotation: Some(LogRotation::Count(2)),
+            logging_destinations: Vec::new(),
+            ..Default::default()
+        };
+        for i in 0..3 {
+            Log::new(
+                &i.to_string(),
+                "2024-01-01T00:00:00Z",
+                &LogLevel::INFO,
+                "app",
+                &format!("entry {}", i),
+                &LogFormat::CLF,
+            )
+            .log_with_config(&config)
+            .await
+            .unwrap();
+        }
+
+        let rotated =
+            fs::read_to_string(temp_dir.path().join("RLG.log.1"))
+                .await
+                .unwrap();
+        assert_eq!(rotated.lines().count(), 2);
+        assert!(rotated.contains("entry 0"));
+        let current = fs::read_to_string(&base).await.unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert!(current.contains("entry 2"));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_log_file_count_appended_lines() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let config = Config {
+            log_rotation: Some(LogRotation::Count(3)),
+            ..Default::default()
+        };
+
+        // An unterminated line counts, and is read again once finished.
+        fs::write(&base, "1\n2").await.unwrap();
+        assert!(!rotate_log_file(&base, &config).await.unwrap());
+        let mut file =
+            OpenOptions::new().append(true).open(&base).await.unwrap();
+        file.write_all(b"\n").await.unwrap();
+        assert!(!rotate_log_file(&base, &config).await.unwrap());
+        file.write_all(b"3\n").await.unwrap();
+        assert!(rotate_log_file(&base, &config).await.unwrap());
+
+        // The count starts over in the new file.
+        fs::write(&base, "4\n").await.unwrap();
+        assert!(!rotate_log_file(&base, &config).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_log_with_config_rotation_failure_keeps_entry() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().join("RLG.log");
+        let blocker = temp_dir.path().join("blocker");
+        fs::write(&blocker, "").await.unwrap();
+        fs::write(&base, "old\n").await.unwrap();
+        // The archive directory cannot be created under a file, so
+        // rotation fails after renaming the log file.
+        let config = Config {
+            log_file_path: base.clone(),
+            log_rotation: Some(LogRotation::Count(1)),
+            log_archive_dir: Some(blocker.join("archive")),
+            max_log_age_days: Some(0),
+            logging_destinations: Vec::new(),
+            ..Default::default()
+        };
+        assert!(rotate_log_file(&base, &config).await.is_err());
+        fs::write(&base, "old\n").await.unwrap();
+
+        Log::new(
+            "1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::INFO,
+            "app",
+            "kept",
+            &LogFormat::CLF,
+        )
+        .log_with_config(&config)
+        .await
+        .unwrap();
+        let current = fs::read_to_string(&base).await.unwrap();
+        assert!(current.contains("kept"));
+    }
+
+    #[tokio::test]
+    async fn test_split_log_file_by_level() {
+        let levels = [
+            LogLevel::ALL,
+            LogLevel::NONE,
+            LogLevel::DISABLED,
+            LogLevel::DEBUG,
+            LogLevel::TRACE,
+            LogLevel::VERBOSE,
+            LogLevel::INFO,
+            LogLevel::WARN,
+            LogLevel::ERROR,
+            LogLevel::FATAL,
+            LogLevel::CRITICAL,
+            LogLevel::SECURITY,
+            LogLevel::AUDIT,
+        ];
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("mixed.log");
+        let dest_dir = temp_dir.path().join("split");
+        let mut content = String::from("not a log entry\n");
+        for i in 0..300 {
+            let log = Log::new(
+                &i.to_string(),
+                "2024-01-01T00:00:00Z",
+                &levels[i % levels.len()],
+                "app",
+                &format!("entry {}", i),
+                &LogFormat::CLF,
+            );
+            content.push_str(&format!("{}\n", log));
+        }
+        fs::write(&src, content).await.unwrap();
+
+        let files =
+            split_log_file_by_level(&src, &dest_dir).await.unwrap();
+        assert_eq!(files.len(), levels.len());
+
+        let mut total = 0;
+        for level in &levels {
+            let path = &files[level];
+            assert_eq!(path, &dest_dir.join(format!("{}.log", level)));
+            let content = fs::read_to_string(path).await.unwrap();
+            for line in content.lines() {
+                assert_eq!(
+                    &Log::from_clf_str(line).unwrap().level,
+                    level
+                );
+                total += 1;
+            }
+        }
+        assert_eq!(total, 300);
+    }
+
+    #[tokio::test]
+    async fn test_split_log_file_by_level_only_present_levels() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("errors.log");
+        let log = Log::new(
+            "1",
+            "2024-01-01T00:00:00Z",
+            &LogLevel::ERROR,
+            "db",
+            "connection lost",
+            &LogFormat::CLF,
+        );
+        fs::write(&src, format!("{}\n{}\n", log, log))
+            .await
+            .unwrap();
+
+        let files = split_log_file_by_level(&src, temp_dir.path())
+            .await
+            .unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            [&LogLevel::ERROR]
+        );
+        assert_eq!(
+            fs::read_to_string(&files[&LogLevel::ERROR]).await.unwrap(),
+            format!("{}\n{}\n", log, log)
+        );
+
+        assert!(split_log_file_by_level(
+            &temp_dir.path().join("missing.log"),
+            temp_dir.path()
+        )
+        .await
+        .is_err());
+    }
+
+    fn merge_entry(file: usize, minute: u32) -> String {
+        format!(
+            "{}\n",
+            Log::new(
+                &file.to_string(),
+                &format!("2024-01-01T00:{:02}:00Z", minute),
+                &LogLevel::INFO,
+                "app",
+                &format!("file {} minute {}", file, minute),
+                &LogFormat::CLF,
+            )
+        )
+    }
+
+    #[tokio::test]
+    async fn test_merge_log_files_sorted() {
+        let temp_dir = tempdir().unwrap();
+        let mut sources = Vec::new();
+        for file in 0..3 {
+            let path =
+                temp_dir.path().join(format!("app.log.{}", file));
+            let content: String = (0..20)
+                .map(|i| merge_entry(file, i * 3 + file as u32))
+                .collect();
+            fs::write(&path, content).await.unwrap();
+            sources.push(path);
+        }
+        let sources: Vec<&std::path::Path> =
+            sources.iter().map(|path| path.as_path()).collect();
+        let dest = temp_dir.path().join("merged.log");
+
+        assert_eq!(
+            merge_log_files(&sources, &dest, true).await.unwrap(),
+            60
+        );
+
+        let content = fs::read_to_string(&dest).await.unwrap();
+        let times: Vec<String> = content
+            .lines()
+            .map(|line| Log::from_clf_str(line).unwrap().time)
+            .collect();
+        assert_eq!(times.len(), 60);
+        assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn test_merge_log_files_unsorted() {
+        let temp_dir = tempdir().unwrap();
+        let first = temp_dir.path().join("first.log");
+        let second = temp_dir.path().join("second.log");
+        let first_content =
+            format!("{}{}", merge_entry(0, 5), merge_entry(0, 9));
+        let second_content =
+            format!("{}not an entry\n", merge_entry(1, 1));
+        fs::write(&first, &first_content).await.unwrap();
+        fs::write(&second, &second_content).await.unwrap();
+        let dest = temp_dir.path().join("merged.log");
+
+        assert_eq!(
+            merge_log_files(&[&first, &second], &dest, false)
+                .await
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            fs::read_to_string(&dest).await.unwrap(),
+            format!("{}{}", first_content, second_content)
+        );
+
+        // Lines without a timestamp stay after the entry before them.
+        assert_eq!(
+            merge_log_files(&[&first, &second], &dest, true)
+                .await
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            fs::read_to_string(&dest).await.unwrap(),
+            format!(
+                "{}not an entry\n{}{}",
+                merge_entry(1, 1),
+                merge_entry(0, 5),
+                merge_entry(0, 9)
+            )
+        );
+
+        let missing = temp_dir.path().join("missing.log");
+        assert!(merge_log_files(&[&first, &missing], &dest, true)
+            .await
+            .is_err());
+    }
+
+    fn dedup_entry(level: &LogLevel, second: u32) -> String {
+        format!(
+            "{}\n",
+            Log::new(
+                "1",
+                &format!("2024-01-01T00:00:{:02}Z", second),
+                level,
+                "app",
+                "crashed",
+                &LogFormat::CLF,
+            )
+        )
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_log_file() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("app.log");
+        let dest = temp_dir.path().join("dedup.log");
+        let content: String = (0..100)
+            .map(|i| dedup_entry(&LogLevel::ERROR, i / 50))
+            .collect();
+        fs::write(&src, content).await.unwrap();
+
+        assert_eq!(
+            deduplicate_log_file(&src, &dest, Duration::from_secs(1))
+                .await
+                .unwrap(),
+            99
+        );
+        let output = fs::read_to_string(&dest).await.unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.contains("Level=ERROR"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("(repeated "))
+                .count(),
+            1
+        );
+        assert_eq!(
+            output,
+            format!(
+                "{}(repeated 99 times)\n",
+                dedup_entry(&LogLevel::ERROR, 0)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_log_file_breaks_runs() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("app.log");
+        let dest = temp_dir.path().join("dedup.log");
+        let content = [
+            dedup_entry(&LogLevel::ERROR, 0),
+            dedup_entry(&LogLevel::ERROR, 1),
+            dedup_entry(&LogLevel::INFO, 1),
+            "not an entry\n".to_string(),
+            dedup_entry(&LogLevel::INFO, 1),
+            dedup_entry(&LogLevel::INFO, 3),
+        ]
+        .concat();
+        fs::write(&src, &content).await.unwrap();
+
+        // Runs end at a different entry, a line that is not an entry, or
+        // a gap longer than the window.
+        assert_eq!(
+            deduplicate_log_file(&src, &dest, Duration::from_secs(1))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            fs::read_to_string(&dest).await.unwrap(),
+            [
+                dedup_entry(&LogLevel::ERROR, 0),
+                "(repeated 1 times)\n".to_string(),
+                dedup_entry(&LogLevel::INFO, 1),
+                "not an entry\n".to_string(),
+                dedup_entry(&LogLevel::INFO, 1),
+                dedup_entry(&LogLevel::INFO, 3),
+            ]
+            .concat()
+        );
+
+        let missing = temp_dir.path().join("missing.log");
+        assert!(deduplicate_log_file(
+            &missing,
+            &dest,
+            Duration::from_secs(1)
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_log_file_statistics() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("stats.log");
+        let sink = FileSink::new(&path);
+        let entries = [
+            ("2024-01-01T00:00:00Z", LogLevel::INFO),
+            ("2024-01-01T00:00:01Z", LogLevel::WARN),
+            ("2024-01-01T00:00:02Z", LogLevel::INFO),
+            ("2024-01-01T00:00:03Z", LogLevel::ERROR),
+            ("2024-01-01T00:00:04Z", LogLevel::INFO),
+        ];
+        for (time, level) in &entries {
+            Log::new("1", time, level, "app", "event", &LogFormat::CLF)
+                .log_to(&sink)
+                .await
+                .unwrap();
+        }
+        // Lines that are not CLF entries are not counted.
+        let mut file =
+            OpenOptions::new().append(true).open(&path).await.unwrap();
+        file.write_all(b"garbage line\n").await.unwrap();
+        file.flush().await.unwrap();
+
+        let stats = log_file_statistics(&path).await.unwrap();
+        assert_eq!(stats.entry_count, 5);
+        assert_eq!(stats.level_counts.len(), 3);
+        assert_eq!(stats.level_counts["INFO"], 3);
+        assert_eq!(stats.level_counts["WARN"], 1);
+        assert_eq!(stats.level_counts["ERROR"], 1);
+        assert_eq!(
+            stats.file_size_bytes,
+            fs::metadata(&path).await.unwrap().len()
+        );
+        assert_eq!(
+            stats.first_entry_time.as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            stats.last_entry_time.as_deref(),
+            Some("2024-01-01T00:00:04Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_file_statistics_empty_and_missing() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("empty.log");
+        File::create(&path).await.unwrap();
+
+        let stats = log_file_statistics(&path).await.unwrap();
+        assert_eq!(stats, LogFileStats::default());
+
+        let missing = temp_dir.path().join("missing.log");
+        assert!(log_file_statistics(&missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_count_errors_in_file() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("triage.log");
+        let mut entries = Vec::new();
+        for (level, count) in [
+            (LogLevel::ERROR, 50),
+            (LogLevel::WARN, 30),
+            (LogLevel::INFO, 20),
+        ] {
+            for i in 0..count {
+                entries.push(Log::new(
+                    &i.to_string(),
+                    "2024-01-01T00:00:00Z",
+                    &level,
+                    "app",
+                    "event",
+                    &LogFormat::CLF,
+                ));
+            }
+        }
+        Log::log_batch_to_path(&path, &entries).await.unwrap();
+
+        assert_eq!(count_errors_in_file(&path).await.unwrap(), 50);
+        assert_eq!(
+            count_level_in_file(&path, LogLevel::WARN).await.unwrap(),
+            30
+        );
+        assert_eq!(
+            count_level_in_file(&path, LogLevel::INFO).await.unwrap(),
+            20
+        );
+        assert_eq!(
+            count_level_in_file(&path, LogLevel::FATAL).await.unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_errors_in_file_json_and_fatal() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("triage.log");
+        let entries = [
+            (LogLevel::FATAL, LogFormat::CLF),
+            (LogLevel::ERROR, LogFormat::JSON),
+            (LogLevel::FATAL, LogFormat::JSON),
+            (LogLevel::INFO, LogFormat::JSON),
+        ]
+        .iter()
+        .map(|(level, format)| {
+            Log::new(
+                "1",
+                "2024-01-01T00:00:00Z",
+                level,
+                "app",
+                "event",
+                format,
+            )
+        })
+        .collect::<Vec<_>>();
+        Log::log_batch_to_path(&path, &entries).await.unwrap();
+        // Lines without a level marker are not counted.
+        let mut file =
+            OpenOptions::new().append(true).open(&path).await.unwrap();
+        file.write_all(b"ERROR without a marker\n\xff\n")
+            .await
+            .unwrap();
+        file.flush().await.unwrap();
+
+        assert_eq!(count_errors_in_file(&path).await.unwrap(), 3);
+        assert_eq!(
+            count_level_in_file(&path, LogLevel::FATAL).await.unwrap(),
+            2
+        );
+
+        let missing = temp_dir.path().join("missing.log");
+        assert!(count_errors_in_file(&missing).await.is_err());
+    }
+
+    fn sample_logs(format: LogFormat) -> Vec<Log> {
+        vec![
+            Log::new(
+                "1",
+                "2024-01-01T00:00:00Z",
+                &LogLevel::INFO,
+                "app",
+                "started",
+                &format,
+            ),
+            Log::new(
+                "2",
+                "2024-01-01T00:00:01Z",
+                &LogLevel::ERROR,
+                "db",
+                "connection lost: retrying in 5s",
+                &format,
+            )
+            .with_field("attempt", "3"),
+            Log::new(
+                "3",
+                "2024-01-01T00:00:02Z",
+                &LogLevel::DEBUG,
+                "",
+                "empty component",
+                &format,
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_parse_log_file_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        for format in [LogFormat::CLF, LogFormat::JSON] {
+            let path = temp_dir.path().join(format!("{}.log", format));
+            let sink = FileSink::new(&path);
+            let logs = sample_logs(format.clone());
+            for log in &logs {
+                log.log_to(&sink).await.unwrap();
+            }
+
+            let parsed =
+                parse_log_file(&path, format.clone()).await.unwrap();
+            assert_eq!(parsed.logs, logs);
+            assert!(parsed.parse_errors.is_empty());
+            assert_eq!(
+                parse_log_file_strict(&path, format).await.unwrap(),
+                logs
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_log_file_collects_errors() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("mixed.log");
+        let logs = sample_logs(LogFormat::CLF);
+        let contents = format!(
+            "{}\ngarbage\n\n{}\nSessionID=9 Timestamp=t Description=d Level=LOUD Component=c\n",
+            logs[0], logs[1]
+        );
+        fs::write(&path, contents).await.unwrap();
+
+        let parsed =
+            parse_log_file(&path, LogFormat::CLF).await.unwrap();
+        assert_eq!(parsed.logs, logs[..2].to_vec());
+        assert_eq!(parsed.parse_errors.len(), 2);
+        for error in &parsed.parse_errors {
+            assert!(matches!(error, RlgError::FormatParseError(_)));
+        }
+        assert!(parsed.parse_errors[0].to_string().contains("Line 2:"));
+        assert!(parsed.parse_errors[1].to_string().contains("Line 5:"));
+
+        match parse_log_file_strict(&path, LogFormat::CLF).await {
+            Err(RlgError::FormatParseError(message)) => {
+                assert!(message.starts_with("Line 2:"), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_log_file_unsupported_and_missing() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("app.log");
+        fs::write(&path, "").await.unwrap();
+
+        assert!(matches!(
+            parse_log_file(&path, LogFormat::Log4jXML).await,
+            Err(RlgError::UnsupportedFormat(_))
+        ));
+        assert!(parse_log_file(&path, LogFormat::CLF)
+            .await
+            .unwrap()
+            .logs
+            .is_empty());
+        let missing = temp_dir.path().join("missing.log");
+        assert!(parse_log_file(&missing, LogFormat::CLF)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_async_log_file_reader() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("large.log");
+        let levels = [LogLevel::INFO, LogLevel::WARN, LogLevel::ERROR];
+        let logs: Vec<Log> = (0..500)
+            .map(|n| {
+                Log::new(
+                    &n.to_string(),
+                    "2024-01-01T00:00:00Z",
+                    &levels[n % levels.len()],
+                    "app",
+                    &format!("entry {}", n),
+                    &LogFormat::CLF,
+                )
+                .with_field("n", &n.to_string())
+            })
+            .collect();
+        let sink = FileSink::new(&path);
+        for log in &logs {
+            log.log_to(&sink).await.unwrap();
+        }
+
+        let entries: Vec<_> =
+            async_log_file_reader(&path, LogFormat::CLF)
+                .collect::<Vec<_>>()
+                .await;
+        assert_eq!(entries.len(), 500);
+        for (entry, log) in entries.into_iter().zip(&logs) {
+            let entry = entry.unwrap();
+            assert_eq!(&entry, log);
+            assert_eq!(entry.fields["n"], log.session_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_log_file_reader_errors() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("mixed.log");
+        let logs = sample_logs(LogFormat::JSON);
+        let contents =
+            format!("{}\n{{not json\n\n{}\n", logs[0], logs[2]);
+        fs::write(&path, contents).await.unwrap();
+
+        let entries: Vec<_> =
+            async_log_file_reader(&path, LogFormat::JSON)
+                .collect::<Vec<_>>()
+                .await;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].as_ref().unwrap(), &logs[0]);
+        match &entries[1] {
+            Err(RlgError::FormatParseError(message)) => {
+                assert!(message.starts_with("Line 2:"), "{}", message)
+            }
+            other => panic!("unexpected entry: {:?}", other),
+        }
+        assert_eq!(entries[2].as_ref().unwrap(), &logs[2]);
+
+        let entries: Vec<_> =
+            async_log_file_reader(&path, LogFormat::Log4jXML)
+                .collect::<Vec<_>>()
+                .await;
+        assert!(matches!(
+            entries.as_slice(),
+            [Err(RlgError::UnsupportedFormat(_))]
+        ));
+        let missing = temp_dir.path().join("missing.log");
+        let entries: Vec<_> =
+            async_log_file_reader(&missing, LogFormat::CLF)
+                .collect::<Vec<_>>()
+                .await;
+        assert!(matches!(
+            entries.as_slice(),
+            [Err(RlgError::IoError(_))]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_async_tail_follow() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("tail.log");
+        fs::write(&path, "written before tailing\n").await.unwrap();
+
+        let lines = async_tail_follow(&path);
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Log>(10);
+        let sink_path = path.clone();
+        let writer = tokio::spawn(async move {
+            let sink = FileSink::new(&sink_path);
+            while let Some(log) = rx.recv().await {
+                log.log_to(&sink).await.unwrap();
+            }
+        });
+
+        let logs: Vec<Log> = (0..10)
+            .map(|i| {
+                Log::new(
+                    &i.to_string(),
+                    "2024-01-01T00:00:00Z",
+                    &LogLevel::INFO,
+                    "tail",
+                    &format!("entry {}", i),
+                    &LogFormat::CLF,
+                )
+            })
+            .collect();
+        for log in &logs {
+            tx.send(log.clone()).await.unwrap();
+        }
+        drop(tx);
+        writer.await.unwrap();
+
+        let received: Vec<String> = timeout(
+            Duration::from_secs(10),
+            lines.take(10).collect::<Vec<_>>(),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+        assert_eq!(received.len(), 10);
+        for (line, log) in received.iter().zip(&logs) {
+            assert_eq!(&Log::from_clf_str(line).unwrap(), log);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_config_file() {
+        /// Returns the next configuration, failing after 10 seconds.
+        async fn next(
+            configs: &mut (impl futures::Stream<
+                Item = rlg::error::RlgResult<Config>,
+            > + Unpin),
+        ) -> rlg::error::RlgResult<Config> {
+            timeout(Duration::from_secs(10), configs.next())
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rlg.toml");
+        let config = |level: &str| {
+            format!("version = \"1.0\"\nlog_level = \"{}\"\n", level)
+        };
+        fs::write(&path, config("WARN")).await.unwrap();
+
+        let mut configs = Box::pin(watch_config_file(&path));
+        let first = next(&mut configs).await.unwrap();
+        fs::write(&path, config("DEBUG")).await.unwrap();
+        let second = next(&mut configs).await.unwrap();
+        assert_eq!(first.log_level, LogLevel::WARN);
+        assert_eq!(second.log_level, LogLevel::DEBUG);
+
+        // Errors do not end the stream.
+        fs::write(&path, "version = ").await.unwrap();
+        assert!(matches!(
+            next(&mut configs).await,
+            Err(RlgError::ConfigError(ConfigError::ConfigParseError(
+                _
+            )))
+        ));
+        fs::write(&path, config("ERROR")).await.unwrap();
+        assert_eq!(
+            next(&mut configs).await.unwrap().log_level,
+            LogLevel::ERROR
+        );
+        fs::remove_file(&path).await.unwrap();
+        assert!(matches!(
+            next(&mut configs).await,
+            Err(RlgError::ConfigError(ConfigError::FileReadError(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_async_tail_follow_partial_and_truncated() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("tail.log");
+        fs::write(&path, "").await.unwrap();
+        let mut lines = async_tail_follow(&path);
+
+        let mut file =
+            OpenOptions::new().append(true).open(&path).await.unwrap();
+        file.write_all(b"first ").await.unwrap();
+        file.write_all(b"line\r\nsecond").await.unwrap();
+        assert_eq!(
+            timeout(Duration::from_secs(10), lines.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap(),
+            "first line"
+        );
+        file.write_all(b" line\n").await.unwrap();
+        assert_eq!(
+            timeout(Duration::from_secs(10), lines.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap(),
+            "second line"
+        );
+
+        fs::write(&path, "after rotation\n").await.unwrap();
+        assert_eq!(
+            timeout(Duration::from_secs(10), lines.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap(),
+            "after rotation"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_async_tail_follow_missing_file() {
+        let temp_dir = tempdir().unwrap();
+        let mut lines =
+            async_tail_follow(&temp_dir.path().join("missing.log"));
+        assert!(matches!(
+            lines.next().await,
+            Some(Err(RlgError::IoError(_)))
+        ));
+        assert!(lines.next().await.is_none());
+    }
 }